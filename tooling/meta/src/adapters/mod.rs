@@ -1,41 +1,131 @@
-use std::{path::Path, process::Stdio};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    path::Path,
+    process::Stdio,
+    sync::Mutex,
+};
 
 use anyhow::Result;
-use tokio::process::Command;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    process::Command,
+};
+
+/// Number of stderr lines kept for failure summaries
+const STDERR_TAIL_LINES: usize = 10;
+
+/// Error returned (inside `anyhow::Error`) when a tool exits unsuccessfully
+#[derive(Debug)]
+pub struct ExecFailure {
+    message: String,
+    /// Exit code, or `None` if the process was killed by a signal
+    pub exit_code: Option<i32>,
+    /// Last lines the tool wrote to stderr
+    pub stderr_tail: Vec<String>,
+}
+
+impl fmt::Display for ExecFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ExecFailure {}
 
 pub struct ToolAdapter {
     pub name: String,
     pub command: String,
+    /// Environment variables set for every invocation
+    pub env: BTreeMap<String, String>,
 }
 
 impl ToolAdapter {
     pub fn new(name: String, command: String) -> Self {
-        Self { name, command }
+        // Enable colored output
+        let env = BTreeMap::from([
+            ("CARGO_TERM_COLOR".to_string(), "always".to_string()),
+            ("FORCE_COLOR".to_string(), "1".to_string()),
+        ]);
+        Self { name, command, env }
     }
 
-    pub async fn execute_in(&self, args: &[&str], working_dir: &Path) -> Result<()> {
+    /// Run the tool in `working_dir`, echoing each line of its stdout/stderr
+    /// with a `[prefix]` so output from concurrent tasks stays readable.
+    ///
+    /// Returns the combined output so it can be replayed later. A non-zero
+    /// exit is reported as an [`ExecFailure`].
+    pub async fn execute_in(
+        &self,
+        args: &[&str],
+        working_dir: &Path,
+        prefix: &str,
+    ) -> Result<String> {
         let mut cmd = Command::new(&self.command);
         cmd.args(args);
         cmd.current_dir(working_dir);
-        cmd.stdout(Stdio::inherit());
-        cmd.stderr(Stdio::inherit());
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+        cmd.envs(&self.env);
 
-        // Enable colored output
-        cmd.env("CARGO_TERM_COLOR", "always");
-        cmd.env("FORCE_COLOR", "1");
+        let mut child = cmd.spawn()?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
 
-        let status = cmd.status().await?;
+        let captured = Mutex::new(String::new());
+        let (status, _, stderr_tail) = tokio::join!(
+            child.wait(),
+            echo_lines(stdout, prefix, false, &captured),
+            echo_lines(stderr, prefix, true, &captured),
+        );
+        let status = status?;
 
         if !status.success() {
-            anyhow::bail!(
-                "{} command failed: {} {} (in {})",
-                self.name,
-                self.command,
-                args.join(" "),
-                working_dir.display()
-            );
+            return Err(ExecFailure {
+                message: format!(
+                    "{} command failed: {} {} (in {})",
+                    self.name,
+                    self.command,
+                    args.join(" "),
+                    working_dir.display()
+                ),
+                exit_code: status.code(),
+                stderr_tail: stderr_tail.into(),
+            }
+            .into());
         }
 
-        Ok(())
+        Ok(captured.into_inner().unwrap_or_default())
+    }
+}
+
+/// Print every line from `reader` as `[prefix] line` and append it to
+/// `captured`. Each line is written with a single `println!`, which holds the
+/// stdout lock for the whole line.
+///
+/// Returns the last few lines read.
+async fn echo_lines(
+    reader: impl AsyncRead + Unpin,
+    prefix: &str,
+    to_stderr: bool,
+    captured: &Mutex<String>,
+) -> VecDeque<String> {
+    let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if to_stderr {
+            eprintln!("[{}] {}", prefix, line);
+        } else {
+            println!("[{}] {}", prefix, line);
+        }
+        if let Ok(mut captured) = captured.lock() {
+            captured.push_str(&line);
+            captured.push('\n');
+        }
+        if tail.len() == STDERR_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
     }
+    tail
 }
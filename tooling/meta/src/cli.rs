@@ -1,10 +1,23 @@
+use std::{path::PathBuf, time::Duration};
+
 use clap::{Parser, Subcommand};
 
+use crate::{config::McpServer, execution::LogLevel};
+
 #[derive(Parser)]
 #[command(name = "meta")]
 #[command(about = "Meta task orchestrator for monorepos", long_about = None)]
 #[command(version)]
 pub struct Cli {
+    /// meta.toml to use instead of the nearest one at or above the current
+    /// directory
+    #[arg(long, global = true, env = "META_CONFIG", value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Merge `[profiles.<name>]` over the config
+    #[arg(long, global = true, env = "META_PROFILE", value_name = "NAME")]
+    pub profile: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -13,9 +26,13 @@ pub struct Cli {
 pub enum Commands {
     /// Initialize meta configuration
     Init {
-        /// Skip writing an mcp-log-server entry to .mcp.json
+        /// Skip writing an MCP server entry to .mcp.json
         #[arg(long = "no-mcp")]
         no_mcp: bool,
+
+        /// MCP server to add to .mcp.json
+        #[arg(long, value_enum, default_value = "docker", conflicts_with = "no_mcp")]
+        mcp: McpServer,
     },
 
     /// Start development servers for all projects
@@ -27,12 +44,37 @@ pub enum Commands {
         /// Start in background without attaching to tmux (useful for CI/agents)
         #[arg(short, long)]
         detach: bool,
+
+        /// Supervise processes directly instead of using tmux (automatic when
+        /// tmux is not installed)
+        #[arg(long, conflicts_with = "detach")]
+        no_tmux: bool,
+
+        /// Add the projects to the running tmux session, skipping any that
+        /// already have a pane, instead of replacing the session
+        #[arg(long, conflicts_with = "no_tmux")]
+        add: bool,
     },
 
     /// Stop all running tmux development sessions
     #[command(name = "dev:stop")]
     DevStop,
 
+    /// Restart one project's dev process in the running tmux session
+    ///
+    /// Stops the process tree in the project's pane, then starts the dev task
+    /// again in the same pane. Logged to .meta/logs/dev.log.
+    Restart {
+        /// Project to restart
+        project: String,
+    },
+
+    /// Stop one project's dev process, leaving the rest of the session running
+    Stop {
+        /// Project to stop
+        project: String,
+    },
+
     /// Show status of running dev processes (useful for Claude Code)
     ///
     /// Displays process info, restart history, and binary modification times.
@@ -49,6 +91,14 @@ pub enum Commands {
         /// Output JSON for programmatic consumption
         #[arg(long)]
         json: bool,
+
+        /// Keep redrawing a compact status table until Ctrl+C
+        #[arg(short, long, conflicts_with = "json")]
+        watch: bool,
+
+        /// How often --watch refreshes, e.g. 2s, 500ms or 1m
+        #[arg(long, default_value = "2s", value_parser = parse_duration, requires = "watch")]
+        interval: Duration,
     },
 
     /// Build projects
@@ -60,6 +110,27 @@ pub enum Commands {
         /// Specific projects to build (optional)
         #[arg(short, long)]
         projects: Option<Vec<String>>,
+
+        /// Maximum number of tasks to run concurrently (default: number of
+        /// CPUs)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+
+        /// Always run tasks, ignoring and not updating .meta/cache
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Run every project even if some fail, then summarize the failures
+        #[arg(short = 'k', long, visible_alias = "keep-going")]
+        continue_on_error: bool,
+
+        /// Only include projects affected by changes since --base (git diff)
+        #[arg(long)]
+        affected: bool,
+
+        /// Git ref to compare against with --affected
+        #[arg(long, default_value = "main", requires = "affected")]
+        base: String,
     },
 
     /// Run tests
@@ -67,6 +138,27 @@ pub enum Commands {
         /// Watch mode
         #[arg(short, long)]
         watch: bool,
+
+        /// Maximum number of tasks to run concurrently (default: number of
+        /// CPUs)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+
+        /// Always run tasks, ignoring and not updating .meta/cache
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Run every project even if some fail, then summarize the failures
+        #[arg(short = 'k', long, visible_alias = "keep-going")]
+        continue_on_error: bool,
+
+        /// Only include projects affected by changes since --base (git diff)
+        #[arg(long)]
+        affected: bool,
+
+        /// Git ref to compare against with --affected
+        #[arg(long, default_value = "main", requires = "affected")]
+        base: String,
     },
 
     /// Run a specific task (e.g., meta run fmt, meta run clippy)
@@ -77,27 +169,104 @@ pub enum Commands {
         /// Specific projects to run task for (optional)
         #[arg(short, long)]
         projects: Option<Vec<String>>,
+
+        /// Maximum number of tasks to run concurrently (default: number of
+        /// CPUs)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+
+        /// Always run tasks, ignoring and not updating .meta/cache
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Run every project even if some fail, then summarize the failures
+        #[arg(short = 'k', long, visible_alias = "keep-going")]
+        continue_on_error: bool,
+
+        /// Only include projects affected by changes since --base (git diff)
+        #[arg(long)]
+        affected: bool,
+
+        /// Git ref to compare against with --affected
+        #[arg(long, default_value = "main", requires = "affected")]
+        base: String,
     },
 
     /// Validate meta.toml configuration and check tool availability
-    Doctor,
+    Doctor {
+        /// Output every check as JSON (stable id, severity, message, fix)
+        #[arg(long)]
+        json: bool,
+
+        /// Treat warnings as errors (non-zero exit status)
+        #[arg(long)]
+        strict: bool,
+
+        /// Show fixes for meta.toml, bacon.toml and .mcp.json as diffs and
+        /// apply them after confirmation
+        #[arg(long, conflicts_with = "json")]
+        fix: bool,
+
+        /// Apply --fix changes without asking
+        #[arg(short, long, requires = "fix")]
+        yes: bool,
+    },
+
+    /// Run an MCP server on stdin/stdout for agents
+    ///
+    /// Tools: status, logs, run_task, restart and doctor. Resources: the
+    /// project logs in .meta/logs. Add it to .mcp.json with
+    /// `meta init --mcp meta`.
+    Mcp,
 
     /// View project logs
     ///
     /// Shows stdout/stderr captured from dev processes.
     /// Without a project name, lists available log files.
     Logs {
-        /// Project name to view logs for (optional - lists available if
-        /// omitted)
-        project: Option<String>,
+        /// Projects to view logs for (optional - lists available if omitted)
+        projects: Vec<String>,
+
+        /// View the logs of every project, interleaved
+        #[arg(short, long, conflicts_with = "projects")]
+        all: bool,
 
         /// Follow log output (like tail -f)
         #[arg(short, long)]
         follow: bool,
 
-        /// Number of lines to show (default: 50)
+        /// Number of lines to show per project (default: 50)
         #[arg(short, long, default_value = "50")]
         lines: usize,
+
+        /// Only show lines matching a regex
+        #[arg(long)]
+        grep: Option<String>,
+
+        /// Only show lines at this level or above
+        #[arg(long, value_enum)]
+        level: Option<LogLevel>,
+
+        /// Only show lines logged within this long, e.g. 10m (uses
+        /// timestamps in the lines)
+        #[arg(long, value_parser = parse_duration)]
+        since: Option<Duration>,
+
+        /// Output line-delimited JSON records
+        #[arg(long)]
+        json: bool,
+
+        /// Delete rotated logs and empty the current ones (every project's
+        /// unless projects are given)
+        #[arg(long, conflicts_with_all = ["all", "follow", "grep", "level", "since", "json"])]
+        clean: bool,
+    },
+
+    /// Append stdin to a project's log with rotation (used by tmux
+    /// pipe-pane)
+    #[command(name = "log-sink", hide = true)]
+    LogSink {
+        project: String,
     },
 
     /// List all active meta tmux sessions
@@ -106,3 +275,49 @@ pub enum Commands {
     /// Helpful for managing multiple development environments.
     Sessions,
 }
+
+/// Parse a duration such as `500ms`, `2s`, `5m` or `1h`; a bare number is
+/// seconds
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}', expected e.g. 2s, 500ms or 5m", value))?;
+    let duration = match unit {
+        "ms" => Duration::from_millis(number),
+        "" | "s" => Duration::from_secs(number),
+        "m" => Duration::from_secs(number * 60),
+        "h" => Duration::from_secs(number * 3600),
+        _ => {
+            return Err(format!(
+                "invalid duration unit '{}', expected ms, s, m or h",
+                unit
+            ))
+        }
+    };
+    if duration.is_zero() {
+        return Err("duration must be greater than zero".to_string());
+    }
+    Ok(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("3"), Ok(Duration::from_secs(3)));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("2d").is_err());
+        assert!(parse_duration("fast").is_err());
+    }
+}
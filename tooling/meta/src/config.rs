@@ -1,13 +1,29 @@
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+use crate::version::VersionReq;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub workspace: WorkspaceConfig,
     pub tools: HashMap<String, ToolConfig>,
     pub projects: HashMap<String, ProjectConfig>,
+    /// Environment variables set for every task and dev process
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Rotation and retention of project logs in `.meta/logs`
+    #[serde(default)]
+    pub logs: LogsConfig,
+    /// What was merged over meta.toml (`meta.local.toml`, `profile 'ci'`)
+    #[serde(skip)]
+    pub layers: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -20,6 +36,9 @@ pub struct WorkspaceConfig {
 pub struct ToolConfig {
     pub enabled: bool,
     pub command: String,
+    /// Versions of the tool this workspace works with, such as `">=1.20"`.
+    /// `meta run` and `meta dev` refuse to start with any other.
+    pub version: Option<VersionReq>,
     #[serde(default)]
     pub for_languages: Vec<String>,
     #[serde(default)]
@@ -33,6 +52,16 @@ pub struct ProjectConfig {
     pub path: String,
     #[serde(default = "default_true")]
     pub dev_default: bool,
+    /// Projects this one depends on. `^task` entries in a task's
+    /// `depends_on` run `task` in each of these projects first.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Environment variables for every task in this project
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Dotenv file (relative to the project path) loaded for every task.
+    /// Skipped if it does not exist.
+    pub env_file: Option<String>,
     pub tasks: HashMap<String, TaskConfig>,
 }
 
@@ -40,24 +69,500 @@ fn default_true() -> bool {
     true
 }
 
+/// `[logs]`: project logs are rotated once they grow past `max_size`, and
+/// rotated files beyond `max_files` or older than `max_age` are deleted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogsConfig {
+    #[serde(default = "default_log_max_size")]
+    pub max_size: ByteSize,
+    /// Rotated files kept per project (`api.log.1`, `api.log.2`, ...)
+    #[serde(default = "default_log_max_files")]
+    pub max_files: u32,
+    pub max_age: Option<Age>,
+    /// Compress rotated files with `gzip`
+    #[serde(default)]
+    pub gzip: bool,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        LogsConfig {
+            max_size: default_log_max_size(),
+            max_files: default_log_max_files(),
+            max_age: None,
+            gzip: false,
+        }
+    }
+}
+
+fn default_log_max_size() -> ByteSize {
+    ByteSize(10 * 1024 * 1024)
+}
+
+fn default_log_max_files() -> u32 {
+    1
+}
+
+/// A size in bytes: a number, or a string with a unit like `"512KB"`,
+/// `"10MB"` or `"1GB"` (powers of 1024)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "QuantityValue", into = "QuantityValue")]
+pub struct ByteSize(pub u64);
+
+/// A duration in seconds: a number, or a string with a unit like `"30m"`,
+/// `"12h"`, `"7d"` or `"2w"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "QuantityValue", into = "QuantityValue")]
+pub struct Age(pub u64);
+
+/// A `ByteSize` or `Age` as written in meta.toml
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum QuantityValue {
+    Number(i64),
+    Text(String),
+}
+
+impl QuantityValue {
+    /// The value in base units, given multipliers for the accepted suffixes
+    fn scaled(self, units: &[(&str, u64)], expected: &str) -> Result<u64, String> {
+        let invalid = |value: &str| format!("invalid value '{}', expected {}", value, expected);
+        match self {
+            QuantityValue::Number(n) => u64::try_from(n).map_err(|_| invalid(&n.to_string())),
+            QuantityValue::Text(text) => {
+                let trimmed = text.trim();
+                let split = trimmed
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(trimmed.len());
+                let (number, unit) = trimmed.split_at(split);
+                let number: u64 = number.parse().map_err(|_| invalid(&text))?;
+                let (_, multiplier) = units
+                    .iter()
+                    .find(|(suffix, _)| suffix.eq_ignore_ascii_case(unit.trim()))
+                    .ok_or_else(|| invalid(&text))?;
+                number.checked_mul(*multiplier).ok_or_else(|| invalid(&text))
+            }
+        }
+    }
+}
+
+impl TryFrom<QuantityValue> for ByteSize {
+    type Error = String;
+
+    fn try_from(value: QuantityValue) -> Result<Self, Self::Error> {
+        const UNITS: &[(&str, u64)] = &[
+            ("", 1),
+            ("B", 1),
+            ("KB", 1 << 10),
+            ("K", 1 << 10),
+            ("MB", 1 << 20),
+            ("M", 1 << 20),
+            ("GB", 1 << 30),
+            ("G", 1 << 30),
+        ];
+        value
+            .scaled(UNITS, "bytes or a size like \"10MB\"")
+            .map(ByteSize)
+    }
+}
+
+impl TryFrom<QuantityValue> for Age {
+    type Error = String;
+
+    fn try_from(value: QuantityValue) -> Result<Self, Self::Error> {
+        const UNITS: &[(&str, u64)] = &[
+            ("", 1),
+            ("s", 1),
+            ("m", 60),
+            ("h", 3_600),
+            ("d", 86_400),
+            ("w", 604_800),
+        ];
+        value
+            .scaled(UNITS, "seconds or a duration like \"7d\"")
+            .map(Age)
+    }
+}
+
+impl From<ByteSize> for QuantityValue {
+    fn from(size: ByteSize) -> Self {
+        QuantityValue::Number(size.0 as i64)
+    }
+}
+
+impl From<Age> for QuantityValue {
+    fn from(age: Age) -> Self {
+        QuantityValue::Number(age.0 as i64)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TaskConfig {
     pub tool: String,
-    pub command: String,
+    pub command: TaskCommand,
+    /// Run `<tool> <command>` through `sh -c`, so pipes, `&&` and variable
+    /// expansion work
+    #[serde(default)]
+    pub shell: bool,
+    /// Tasks that must finish before this one starts:
+    /// `"lint"` (same project), `"api:build"` (another project) or
+    /// `"^build"` (`build` in every project listed in `depends_on`)
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Globs (relative to the project path) whose contents make up the
+    /// task's cache key. Caching is enabled only when this is non-empty.
+    /// Prefix a glob with `!` to exclude matches.
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    /// Globs of files the task produces, restored from `.meta/cache` on a
+    /// cache hit
+    #[serde(default)]
+    pub outputs: Vec<String>,
+    /// Environment variables for this task, overriding project ones
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Dotenv file (relative to the project path) loaded for this task.
+    /// Skipped if it does not exist.
+    pub env_file: Option<String>,
+    /// When `meta dev` restarts this process after it exits
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// Automatic restarts allowed within `restart_window` before giving up
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    /// Length in seconds of the window `max_restarts` applies to
+    #[serde(default = "default_restart_window")]
+    pub restart_window: u64,
+    /// How `meta dev` tells that this process is ready to serve
+    pub ready: Option<ReadyProbe>,
+    /// Projects whose dev processes must be ready before this one starts
+    #[serde(default)]
+    pub after: Vec<String>,
+    /// Port the dev process listens on, checked before launch and exported
+    /// as `PORT`
+    pub port: Option<PortConfig>,
+}
+
+/// A dev task's port: a fixed number, or `"auto"` for a free port picked at
+/// launch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "PortValue", into = "PortValue")]
+pub enum PortConfig {
+    Fixed(u16),
+    Auto,
+}
+
+/// `port` as written in meta.toml
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum PortValue {
+    Number(i64),
+    Name(String),
+}
+
+impl TryFrom<PortValue> for PortConfig {
+    type Error = String;
+
+    fn try_from(value: PortValue) -> Result<Self, Self::Error> {
+        match value {
+            PortValue::Number(n) => match u16::try_from(n) {
+                Ok(port) if port > 0 => Ok(PortConfig::Fixed(port)),
+                _ => Err(format!("invalid port {}, expected 1-65535 or \"auto\"", n)),
+            },
+            PortValue::Name(name) if name == "auto" => Ok(PortConfig::Auto),
+            PortValue::Name(name) => Err(format!(
+                "invalid port '{}', expected 1-65535 or \"auto\"",
+                name
+            )),
+        }
+    }
+}
+
+impl From<PortConfig> for PortValue {
+    fn from(port: PortConfig) -> Self {
+        match port {
+            PortConfig::Fixed(port) => PortValue::Number(i64::from(port)),
+            PortConfig::Auto => PortValue::Name("auto".to_string()),
+        }
+    }
+}
+
+/// Readiness probe for a dev task. Every probe that is set must pass.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadyProbe {
+    /// TCP port on localhost that accepts connections
+    pub port: Option<u16>,
+    /// `http://` URL that answers with a 2xx or 3xx status
+    pub http: Option<String>,
+    /// Regex matched against lines of the project's log
+    pub log: Option<String>,
+    /// Seconds to wait before giving up
+    #[serde(default = "default_ready_timeout")]
+    pub timeout: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Wait for Enter in the tmux pane (the default)
+    #[default]
+    Never,
+    /// Restart when the process exits with a non-zero code
+    OnFailure,
+    /// Restart whenever the process exits
+    Always,
+}
+
+impl RestartPolicy {
+    pub fn should_restart(self, exit_code: Option<i32>) -> bool {
+        match self {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => exit_code != Some(0),
+            RestartPolicy::Always => true,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RestartPolicy::Never => "never",
+            RestartPolicy::OnFailure => "on-failure",
+            RestartPolicy::Always => "always",
+        }
+    }
+}
+
+fn default_max_restarts() -> u32 {
+    5
+}
+
+fn default_restart_window() -> u64 {
+    60
+}
+
+fn default_ready_timeout() -> u64 {
+    60
+}
+
+/// Arguments passed to a task's tool: either a string split with POSIX
+/// shell quoting rules (`"clippy -- -D \"clippy::all\""`) or an explicit
+/// TOML array (`["clippy", "--", "-D", "clippy::all"]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TaskCommand {
+    Line(String),
+    Args(Vec<String>),
+}
+
+/// Tokens that only mean something to a shell. Passed to a tool directly
+/// they would become literal arguments.
+const SHELL_OPERATORS: &[&str] = &["&&", "||", "|", ";", "&", ">", ">>", "<", "2>&1"];
+
+impl TaskCommand {
+    /// The command split into arguments. Unquoted shell operators are an
+    /// error, since they only work with `shell = true`.
+    pub fn args(&self) -> Result<Vec<String>> {
+        match self {
+            TaskCommand::Line(line) => {
+                let args = shell_words::split(line)
+                    .map_err(|e| anyhow::anyhow!("Invalid command '{}': {}", line, e))?;
+                if let Some(op) = args.iter().find(|a| SHELL_OPERATORS.contains(&a.as_str())) {
+                    anyhow::bail!(
+                        "Command '{}' contains shell operator '{}' (set `shell = true` to use it)",
+                        line,
+                        op
+                    );
+                }
+                Ok(args)
+            }
+            TaskCommand::Args(args) => Ok(args.clone()),
+        }
+    }
+}
+
+impl fmt::Display for TaskCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskCommand::Line(line) => f.write_str(line),
+            TaskCommand::Args(args) => f.write_str(&shell_words::join(args)),
+        }
+    }
+}
+
+impl TaskConfig {
+    /// Full argv for running this task with `tool_command`: the tool followed
+    /// by the parsed arguments, or `sh -c "<tool> <command>"` when `shell` is
+    /// set
+    pub fn argv(&self, tool_command: &str) -> Result<Vec<String>> {
+        if self.shell {
+            return Ok(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!("{} {}", tool_command, self.command),
+            ]);
+        }
+        let mut argv = vec![tool_command.to_string()];
+        argv.extend(self.command.args()?);
+        Ok(argv)
+    }
+}
+
+/// Config file looked for in the current directory and its parents
+pub const CONFIG_FILE: &str = "meta.toml";
+
+/// Config file to load instead of searching for one, also set by `--config`
+pub const CONFIG_ENV: &str = "META_CONFIG";
+
+/// Per-developer overrides merged over meta.toml, kept out of git
+pub const LOCAL_CONFIG_FILE: &str = "meta.local.toml";
+
+/// `[profiles.<name>]` merged over the config, also set by `--profile`
+pub const PROFILE_ENV: &str = "META_PROFILE";
+
+/// The meta.toml found by `enter_workspace`
+static CONFIG: OnceLock<PathBuf> = OnceLock::new();
+
+/// The profile from `--profile` or `META_PROFILE`, see `select_profile`
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// The meta.toml in use: the one `enter_workspace` found, or meta.toml in
+/// the current directory
+pub fn config_path() -> PathBuf {
+    CONFIG
+        .get()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(CONFIG_FILE))
+}
+
+/// Merge `[profiles.<profile>]` over the config in every `Config::load`
+pub fn select_profile(profile: Option<&str>) {
+    if let Some(profile) = profile.filter(|p| !p.is_empty()) {
+        let _ = PROFILE.set(profile.to_string());
+    }
+}
+
+/// `META_CONFIG` and `META_PROFILE` for meta processes started from this one
+/// (tmux log sinks, MCP tools), so they load the same config
+pub fn child_env() -> Vec<(&'static str, String)> {
+    let mut env = vec![(CONFIG_ENV, config_path().to_string_lossy().into_owned())];
+    if let Some(profile) = PROFILE.get() {
+        env.push((PROFILE_ENV, profile.clone()));
+    }
+    env
+}
+
+/// Find the workspace's meta.toml, `explicit` (from `--config` or
+/// `META_CONFIG`) or the nearest one at or above the current directory,
+/// and move to the directory its `workspace.root` names. Project paths,
+/// `.meta/logs` and the tmux session name are all relative to that
+/// directory.
+///
+/// The config's absolute path is kept for `Config::load` and passed on by
+/// `child_env`. Without a meta.toml nothing changes, and `Config::load`
+/// reports it.
+pub fn enter_workspace(explicit: Option<&Path>) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let path = match explicit {
+        Some(path) => {
+            let path = cwd.join(path);
+            if !path.is_file() {
+                anyhow::bail!("Config file '{}' not found", path.display());
+            }
+            path
+        }
+        None => match cwd.ancestors().map(|dir| dir.join(CONFIG_FILE)).find(|p| p.is_file()) {
+            Some(path) => path,
+            None => return Ok(()),
+        },
+    };
+    let path = path.canonicalize()?;
+    let config_dir = path.parent().unwrap_or(Path::new("/"));
+
+    // An invalid meta.toml is reported by Config::load, from its directory
+    let root = fs::read_to_string(&path)
+        .ok()
+        .and_then(|contents| contents.parse::<toml::Table>().ok())
+        .and_then(|table| table.get("workspace")?.get("root")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| ".".to_string());
+    let root = config_dir.join(root);
+    std::env::set_current_dir(&root).map_err(|e| {
+        anyhow::anyhow!(
+            "workspace.root '{}' in {} is not a directory: {}",
+            root.display(),
+            path.display(),
+            e
+        )
+    })?;
+    let _ = CONFIG.set(path);
+    Ok(())
 }
 
 impl Config {
     pub fn load() -> Result<Self> {
-        let path = Path::new("meta.toml");
+        let path = config_path();
         if !path.exists() {
-            anyhow::bail!("meta.toml not found. Run 'meta init' first.");
+            anyhow::bail!(
+                "{} not found in {} or any parent directory. Run 'meta init' first, or pass \
+                 --config.",
+                CONFIG_FILE,
+                std::env::current_dir()?.display()
+            );
         }
 
-        let contents = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&contents)?;
+        let contents = fs::read_to_string(&path)?;
+        let local = fs::read_to_string(path.with_file_name(LOCAL_CONFIG_FILE)).ok();
+        let config = layered(&contents, local.as_deref(), PROFILE.get().map(String::as_str))?;
+        config.check_env_names()?;
         Ok(config)
     }
 
+    /// Fail on `env` keys that can't be environment variable names, since
+    /// tmux panes export them into a shell script
+    fn check_env_names(&self) -> Result<()> {
+        let mut tables = vec![("[env]".to_string(), &self.env)];
+        let mut projects: Vec<_> = self.projects.iter().collect();
+        projects.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, project) in projects {
+            tables.push((format!("[projects.{}.env]", name), &project.env));
+            let mut tasks: Vec<_> = project.tasks.iter().collect();
+            tasks.sort_by(|a, b| a.0.cmp(b.0));
+            for (task_name, task) in tasks {
+                tables.push((format!("env of {}:{}", name, task_name), &task.env));
+            }
+        }
+        for (table, vars) in tables {
+            for key in vars.keys() {
+                check_env_name(key, &table)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Environment for `task` in `project`, merged in increasing precedence:
+    /// workspace `[env]`, project `env_file`, project `env`, task
+    /// `env_file`, task `env`
+    pub fn task_env(&self, project: &str, task: &str) -> Result<BTreeMap<String, String>> {
+        let project_config = &self.projects[project];
+        let task_config = &project_config.tasks[task];
+        let project_dir = Path::new(&project_config.path);
+
+        let mut env = self.env.clone();
+        for (file, vars) in [
+            (&project_config.env_file, &project_config.env),
+            (&task_config.env_file, &task_config.env),
+        ] {
+            if let Some(file) = file {
+                let path = project_dir.join(file);
+                let vars = read_env_file(&path)?;
+                for (key, _) in &vars {
+                    check_env_name(key, &path.display().to_string())?;
+                }
+                env.extend(vars);
+            }
+            env.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        Ok(env)
+    }
+
     /// Returns projects that have a "dev" task configured
     pub fn projects_with_dev_task(&self) -> HashMap<String, &ProjectConfig> {
         self.projects
@@ -80,7 +585,72 @@ impl Config {
     }
 }
 
-pub fn init(with_mcp: bool) -> Result<()> {
+/// Fail unless `key`, set in `source`, is a valid environment variable name
+fn check_env_name(key: &str, source: &str) -> Result<()> {
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        anyhow::bail!(
+            "Invalid environment variable name '{}' in {}: names must be letters, digits and \
+             underscores, not starting with a digit",
+            key,
+            source
+        );
+    }
+    Ok(())
+}
+
+/// Variables from a dotenv file, or none if the file does not exist
+fn read_env_file(path: &Path) -> Result<Vec<(String, String)>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    dotenvy::from_path_iter(path)
+        .and_then(|vars| vars.collect())
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))
+}
+
+/// The MCP server `meta init` adds to `.mcp.json`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum McpServer {
+    /// mcp-log-server in Docker, reading `.meta/logs`
+    Docker,
+    /// `meta mcp`: logs plus status, tasks, restarts and doctor, no Docker
+    Meta,
+}
+
+impl McpServer {
+    /// Key of the entry under `mcpServers`
+    pub fn entry_name(self) -> &'static str {
+        match self {
+            McpServer::Docker => "mcp-log-server",
+            McpServer::Meta => "meta",
+        }
+    }
+
+    pub fn entry(self) -> serde_json::Value {
+        match self {
+            McpServer::Docker => serde_json::json!({
+                "command": "docker",
+                "args": [
+                    "run", "--rm", "-i",
+                    "-v", "./.meta/logs:/logs",
+                    "-e", "LOG_DIR=/logs",
+                    "ghcr.io/wolven-tech/mcp-log-server:latest"
+                ]
+            }),
+            McpServer::Meta => serde_json::json!({
+                "command": "meta",
+                "args": ["mcp"]
+            }),
+        }
+    }
+}
+
+pub fn init(mcp: Option<McpServer>) -> Result<()> {
     // Auto-detect projects in the monorepo
     let detected_projects = detect_projects()?;
     let docker_detected = detect_docker(Path::new("."));
@@ -90,16 +660,16 @@ pub fn init(with_mcp: bool) -> Result<()> {
 
     fs::write("meta.toml", config)?;
 
-    if with_mcp {
-        write_mcp_log_server_entry(Path::new(".mcp.json"))?;
+    if let Some(server) = mcp {
+        write_mcp_entry(Path::new(".mcp.json"), server)?;
     }
     Ok(())
 }
 
-/// Merge an mcp-log-server entry into `.mcp.json`, creating the file if absent.
+/// Merge an MCP server entry into `.mcp.json`, creating the file if absent.
 ///
-/// Idempotent: if an `mcp-log-server` entry already exists, leaves it alone.
-fn write_mcp_log_server_entry(path: &Path) -> Result<()> {
+/// Idempotent: if an entry with the same name already exists, leaves it alone.
+fn write_mcp_entry(path: &Path, server: McpServer) -> Result<()> {
     let mut root: serde_json::Value = if path.exists() {
         serde_json::from_str(&fs::read_to_string(path)?)?
     } else {
@@ -116,22 +686,11 @@ fn write_mcp_log_server_entry(path: &Path) -> Result<()> {
         .as_object_mut()
         .ok_or_else(|| anyhow::anyhow!(".mcp.json: mcpServers must be an object"))?;
 
-    if servers.contains_key("mcp-log-server") {
+    if servers.contains_key(server.entry_name()) {
         return Ok(());
     }
 
-    servers.insert(
-        "mcp-log-server".into(),
-        serde_json::json!({
-            "command": "docker",
-            "args": [
-                "run", "--rm", "-i",
-                "-v", "./.meta/logs:/logs",
-                "-e", "LOG_DIR=/logs",
-                "ghcr.io/wolven-tech/mcp-log-server:latest"
-            ]
-        }),
-    );
+    servers.insert(server.entry_name().into(), server.entry());
 
     fs::write(path, serde_json::to_string_pretty(&root)?)?;
     Ok(())
@@ -233,6 +792,77 @@ enum ProjectType {
     Node,
 }
 
+/// meta.toml (`base`) with meta.local.toml and then `[profiles.<profile>]`
+/// from either merged over it
+fn layered(base: &str, local: Option<&str>, profile: Option<&str>) -> Result<Config> {
+    if local.is_none() && profile.is_none() {
+        // Parsed directly, so type errors keep their line numbers
+        return Ok(toml::from_str(base)?);
+    }
+
+    let mut table: toml::Table = toml::from_str(base)?;
+    let mut layers = Vec::new();
+    if let Some(local) = local {
+        let local: toml::Table = toml::from_str(local)
+            .map_err(|e| anyhow::anyhow!("{} is invalid: {}", LOCAL_CONFIG_FILE, e))?;
+        reject_root(&local, LOCAL_CONFIG_FILE)?;
+        merge(&mut table, local);
+        layers.push(LOCAL_CONFIG_FILE.to_string());
+    }
+    let mut profiles = match table.remove("profiles") {
+        Some(toml::Value::Table(profiles)) => profiles,
+        _ => toml::Table::new(),
+    };
+    if let Some(name) = profile {
+        let Some(toml::Value::Table(overrides)) = profiles.remove(name) else {
+            let mut names: Vec<&String> = profiles.keys().collect();
+            names.sort();
+            anyhow::bail!(
+                "Profile '{}' is not defined (profiles: {})",
+                name,
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.iter().map(|n| n.as_str()).collect::<Vec<_>>().join(", ")
+                }
+            );
+        };
+        reject_root(&overrides, &format!("profile '{}'", name))?;
+        merge(&mut table, overrides);
+        layers.push(format!("profile '{}'", name));
+    }
+
+    let mut config = Config::deserialize(toml::Value::Table(table))
+        .map_err(|e| anyhow::anyhow!("meta.toml with {}: {}", layers.join(" and "), e))?;
+    config.layers = layers;
+    Ok(config)
+}
+
+/// `workspace.root` is read from meta.toml alone, before the layers are
+/// loaded from it, so an override would be silently ignored
+fn reject_root(layer: &toml::Table, name: &str) -> Result<()> {
+    if layer.get("workspace").and_then(|w| w.get("root")).is_some() {
+        anyhow::bail!(
+            "workspace.root can't be set in {}; set it in {} instead",
+            name,
+            CONFIG_FILE
+        );
+    }
+    Ok(())
+}
+
+/// Merge `overlay` into `base`: tables key by key, anything else replaced
+fn merge(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => merge(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 /// Parse a TOML string into a Config
 #[cfg(test)]
 pub fn parse(contents: &str) -> Result<Config> {
@@ -581,7 +1211,7 @@ build = { tool = "tauri", command = "android build --target aarch64" }
         let tmp = tempfile::tempdir().unwrap();
         let path = tmp.path().join(".mcp.json");
 
-        write_mcp_log_server_entry(&path).unwrap();
+        write_mcp_entry(&path, McpServer::Docker).unwrap();
 
         let contents = fs::read_to_string(&path).unwrap();
         let value: serde_json::Value = serde_json::from_str(&contents).unwrap();
@@ -602,7 +1232,7 @@ build = { tool = "tauri", command = "android build --target aarch64" }
         )
         .unwrap();
 
-        write_mcp_log_server_entry(&path).unwrap();
+        write_mcp_entry(&path, McpServer::Docker).unwrap();
 
         let value: serde_json::Value =
             serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
@@ -620,7 +1250,7 @@ build = { tool = "tauri", command = "android build --target aarch64" }
         )
         .unwrap();
 
-        write_mcp_log_server_entry(&path).unwrap();
+        write_mcp_entry(&path, McpServer::Docker).unwrap();
 
         // User's existing entry should be preserved, not overwritten
         let value: serde_json::Value =
@@ -634,10 +1264,322 @@ build = { tool = "tauri", command = "android build --target aarch64" }
         let path = tmp.path().join(".mcp.json");
         fs::write(&path, r#"{}"#).unwrap();
 
-        write_mcp_log_server_entry(&path).unwrap();
+        write_mcp_entry(&path, McpServer::Docker).unwrap();
 
         let value: serde_json::Value =
             serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
         assert_eq!(value["mcpServers"]["mcp-log-server"]["command"], "docker");
     }
+
+    #[test]
+    fn test_write_mcp_entry_for_meta_mcp() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(".mcp.json");
+        write_mcp_entry(&path, McpServer::Docker).unwrap();
+
+        write_mcp_entry(&path, McpServer::Meta).unwrap();
+
+        let value: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(value["mcpServers"]["meta"]["command"], "meta");
+        assert_eq!(value["mcpServers"]["meta"]["args"][0], "mcp");
+        assert_eq!(value["mcpServers"]["mcp-log-server"]["command"], "docker");
+    }
+
+    fn task(toml: &str) -> TaskConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn test_task_command_respects_quotes() {
+        let task = task(r#"tool = "cargo"
+command = 'clippy -- -D "clippy::all" --message "two words"'"#);
+        assert_eq!(
+            task.argv("cargo").unwrap(),
+            ["cargo", "clippy", "--", "-D", "clippy::all", "--message", "two words"]
+        );
+    }
+
+    #[test]
+    fn test_task_command_accepts_array() {
+        let task = task(r#"tool = "turborepo"
+command = ["run", "dev", "--filter=@org/web app"]"#);
+        assert_eq!(
+            task.argv("turbo").unwrap(),
+            ["turbo", "run", "dev", "--filter=@org/web app"]
+        );
+        assert_eq!(task.command.to_string(), "run dev '--filter=@org/web app'");
+    }
+
+    #[test]
+    fn test_task_command_rejects_shell_operators_without_shell() {
+        let task = task(r#"tool = "cargo"
+command = "build && cargo test""#);
+        let err = task.argv("cargo").unwrap_err().to_string();
+        assert!(err.contains("shell = true"), "{}", err);
+    }
+
+    #[test]
+    fn test_task_command_shell_runs_through_sh() {
+        let task = task(r#"tool = "cargo"
+command = "build && cargo test"
+shell = true"#);
+        assert_eq!(
+            task.argv("cargo").unwrap(),
+            ["sh", "-c", "cargo build && cargo test"]
+        );
+    }
+
+    #[test]
+    fn test_task_env_precedence() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(
+            tmp.path().join(".env"),
+            "A=project-file\nB=project-file\nC=project-file\n",
+        )
+        .unwrap();
+        fs::write(tmp.path().join(".env.task"), "C=task-file\nD=task-file\n").unwrap();
+        let config = parse(&format!(
+            r#"
+[workspace]
+name = "Test"
+root = "."
+
+[env]
+A = "workspace"
+W = "workspace"
+
+[tools]
+
+[projects.api]
+type = "rust"
+path = "{}"
+env_file = ".env"
+env = {{ B = "project" }}
+
+[projects.api.tasks]
+dev = {{ tool = "cargo", command = "run", env_file = ".env.task", env = {{ D = "task" }} }}
+test = {{ tool = "cargo", command = "test", env_file = ".env.missing" }}
+"#,
+            tmp.path().display()
+        ))
+        .unwrap();
+
+        let env = config.task_env("api", "dev").unwrap();
+        assert_eq!(env["W"], "workspace");
+        assert_eq!(env["A"], "project-file");
+        assert_eq!(env["B"], "project");
+        assert_eq!(env["C"], "task-file");
+        assert_eq!(env["D"], "task");
+
+        // Missing env files are skipped
+        let env = config.task_env("api", "test").unwrap();
+        assert_eq!(env["C"], "project-file");
+        assert!(!env.contains_key("D"));
+    }
+
+    #[test]
+    fn test_env_names_must_be_valid() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join(".env"), "GOOD=1\nBAD.NAME=2\n").unwrap();
+        let config = |env: &str| {
+            parse(&format!(
+                r#"
+[workspace]
+name = "Test"
+root = "."
+
+{}
+
+[tools]
+
+[projects.api]
+type = "rust"
+path = "{}"
+
+[projects.api.tasks]
+dev = {{ tool = "cargo", command = "run", env = {{ _OK_2 = "1" }} }}
+test = {{ tool = "cargo", command = "test", env_file = ".env" }}
+"#,
+                env,
+                tmp.path().display()
+            ))
+            .unwrap()
+        };
+
+        assert!(config("[env]\nRUST_LOG = \"info\"").check_env_names().is_ok());
+        let err = config("[env]\n\"A B\" = \"x\"").check_env_names().unwrap_err().to_string();
+        assert!(err.starts_with("Invalid environment variable name 'A B' in [env]"), "{}", err);
+        let err = config("[projects.api.env]\n\"X;rm -rf ~\" = \"x\"")
+            .check_env_names()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("'X;rm -rf ~' in [projects.api.env]"), "{}", err);
+        let err = config("[env]\n\"1X\" = \"x\"").check_env_names().unwrap_err().to_string();
+        assert!(err.contains("'1X'"), "{}", err);
+
+        let err = config("").task_env("api", "test").unwrap_err().to_string();
+        assert!(err.contains("'BAD.NAME' in "), "{}", err);
+    }
+
+    #[test]
+    fn test_logs_config_sizes_and_ages() {
+        let config: LogsConfig = toml::from_str("").unwrap();
+        assert_eq!(config, LogsConfig::default());
+        assert_eq!(config.max_size, ByteSize(10 * 1024 * 1024));
+
+        let config: LogsConfig = toml::from_str(
+            "max_size = \"512KB\"\nmax_files = 4\nmax_age = \"7d\"\ngzip = true",
+        )
+        .unwrap();
+        assert_eq!(config.max_size, ByteSize(512 * 1024));
+        assert_eq!(config.max_files, 4);
+        assert_eq!(config.max_age, Some(Age(7 * 86_400)));
+        assert!(config.gzip);
+
+        let config: LogsConfig = toml::from_str("max_size = 2048\nmax_age = 90").unwrap();
+        assert_eq!(config.max_size, ByteSize(2048));
+        assert_eq!(config.max_age, Some(Age(90)));
+
+        for invalid in ["max_size = \"10 parsecs\"", "max_size = -1", "max_age = \"soon\""] {
+            let err = toml::from_str::<LogsConfig>(invalid).unwrap_err().to_string();
+            assert!(err.contains("invalid value"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_tool_version_requirement() {
+        let tool: ToolConfig =
+            toml::from_str("enabled = true\ncommand = \"turbo\"\nversion = \">=1.20, <3\"")
+                .unwrap();
+        let required = tool.version.unwrap();
+        assert_eq!(required.to_string(), ">=1.20, <3");
+        assert!(required.matches(crate::version::Version([2, 0, 1])));
+
+        let tool: ToolConfig = toml::from_str("enabled = true\ncommand = \"turbo\"").unwrap();
+        assert!(tool.version.is_none());
+
+        let invalid = "enabled = true\ncommand = \"turbo\"\nversion = \"new\"";
+        let err = toml::from_str::<ToolConfig>(invalid).unwrap_err().to_string();
+        assert!(err.contains("invalid version requirement 'new'"), "{}", err);
+    }
+
+    #[test]
+    fn test_local_config_and_profile_merge_over_meta_toml() {
+        let base = r#"
+[workspace]
+name = "Test"
+root = "."
+
+[env]
+RUST_LOG = "info"
+
+[tools.bacon]
+enabled = true
+command = "bacon"
+
+[tools.docker]
+enabled = true
+command = "docker"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+dev = { tool = "bacon", command = "run-long", port = 4000 }
+
+[profiles.ci.tools.docker]
+enabled = false
+
+[profiles.ci.projects.api]
+dev_default = false
+"#;
+        let local = r#"
+[env]
+DATABASE_URL = "postgres://localhost/dev"
+
+[projects.api.tasks.dev]
+tool = "cargo"
+command = "run"
+
+[profiles.ci.env]
+RUST_LOG = "warn"
+"#;
+        let config = layered(base, None, None).unwrap();
+        assert!(config.layers.is_empty());
+        assert_eq!(config.projects["api"].tasks["dev"].tool, "bacon");
+
+        let config = layered(base, Some(local), None).unwrap();
+        assert_eq!(config.layers, ["meta.local.toml"]);
+        let dev = &config.projects["api"].tasks["dev"];
+        assert_eq!((dev.tool.as_str(), dev.command.to_string()), ("cargo", "run".to_string()));
+        // Keys the override leaves out are kept
+        assert_eq!(dev.port, Some(PortConfig::Fixed(4000)));
+        assert_eq!(config.env["RUST_LOG"], "info");
+        assert_eq!(config.env["DATABASE_URL"], "postgres://localhost/dev");
+        assert!(config.tools["docker"].enabled);
+
+        let config = layered(base, Some(local), Some("ci")).unwrap();
+        assert_eq!(config.layers, ["meta.local.toml", "profile 'ci'"]);
+        assert!(!config.tools["docker"].enabled);
+        assert!(!config.projects["api"].dev_default);
+        assert_eq!(config.env["RUST_LOG"], "warn");
+
+        let err = layered(base, None, Some("staging")).unwrap_err().to_string();
+        assert_eq!(err, "Profile 'staging' is not defined (profiles: ci)");
+        let err = layered(base, Some("[env\n"), None).unwrap_err().to_string();
+        assert!(err.starts_with("meta.local.toml is invalid"), "{}", err);
+    }
+
+    #[test]
+    fn test_workspace_root_only_from_meta_toml() {
+        let base = r#"
+[workspace]
+name = "Test"
+root = "."
+
+[tools]
+
+[projects]
+
+[profiles.ci.workspace]
+root = "../.."
+"#;
+        let err = layered(base, Some("[workspace]\nroot = \"..\"\n"), None)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "workspace.root can't be set in meta.local.toml; set it in meta.toml instead"
+        );
+        let err = layered(base, None, Some("ci")).unwrap_err().to_string();
+        assert_eq!(err, "workspace.root can't be set in profile 'ci'; set it in meta.toml instead");
+        // Other workspace keys can still be overridden
+        let config = layered(base, Some("[workspace]\nname = \"Mine\"\n"), None).unwrap();
+        assert_eq!(config.workspace.name, "Mine");
+    }
+
+    #[test]
+    fn test_task_port_accepts_number_or_auto() {
+        let fixed = task(r#"tool = "cargo"
+command = "run"
+port = 4400"#);
+        assert_eq!(fixed.port, Some(PortConfig::Fixed(4400)));
+
+        let auto = task(r#"tool = "cargo"
+command = "run"
+port = "auto""#);
+        assert_eq!(auto.port, Some(PortConfig::Auto));
+
+        for invalid in ["port = 70000", "port = 0", r#"port = "any""#] {
+            let err = toml::from_str::<TaskConfig>(&format!(
+                "tool = \"cargo\"\ncommand = \"run\"\n{}",
+                invalid
+            ))
+            .unwrap_err()
+            .to_string();
+            assert!(err.contains("expected 1-65535 or \"auto\""), "{}", err);
+        }
+    }
 }
@@ -0,0 +1,163 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use tokio::process::Command;
+
+use super::workspace::{normalize, project_dependencies};
+use crate::config::Config;
+
+/// Projects affected by changes since `base`: projects containing a changed
+/// file, plus every project that (transitively) depends on one of them
+pub async fn affected_projects(config: &Config, base: &str) -> Result<BTreeSet<String>> {
+    let changed = changed_files(base).await?;
+    Ok(resolve_affected(config, &changed, &project_dependencies(config)))
+}
+
+/// Files changed between `base` and the working tree, relative to the current
+/// directory: committed changes since the merge base, uncommitted changes and
+/// untracked files
+async fn changed_files(base: &str) -> Result<Vec<PathBuf>> {
+    let range = format!("{}...HEAD", base);
+    let mut files = BTreeSet::new();
+    for args in [
+        vec!["diff", "--name-only", "--relative", range.as_str()],
+        vec!["diff", "--name-only", "--relative", "HEAD"],
+        vec!["ls-files", "--others", "--exclude-standard"],
+    ] {
+        files.extend(git_lines(&args).await?.into_iter().map(PathBuf::from));
+    }
+    Ok(files.into_iter().collect())
+}
+
+async fn git_lines(args: &[&str]) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run git (required for --affected): {}", e))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn resolve_affected(
+    config: &Config,
+    changed: &[PathBuf],
+    deps: &BTreeMap<String, BTreeSet<String>>,
+) -> BTreeSet<String> {
+    let mut affected: BTreeSet<String> = config
+        .projects
+        .iter()
+        .filter(|(_, project)| {
+            let root = normalize(Path::new(&project.path));
+            changed.iter().any(|file| normalize(file).starts_with(&root))
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    // Walk reverse edges until no new dependents are found
+    loop {
+        let dependents: Vec<String> = deps
+            .iter()
+            .filter(|(name, upstream)| {
+                !affected.contains(*name) && upstream.iter().any(|u| affected.contains(u))
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        if dependents.is_empty() {
+            break;
+        }
+        affected.extend(dependents);
+    }
+
+    affected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        crate::config::parse(
+            r#"
+[workspace]
+name = "Test"
+root = "."
+
+[tools]
+
+[projects.shared]
+type = "rust"
+path = "crates/shared"
+[projects.shared.tasks]
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+[projects.api.tasks]
+
+[projects.app]
+type = "next"
+path = "apps/app"
+[projects.app.tasks]
+
+[projects.web]
+type = "next"
+path = "./apps/web"
+[projects.web.tasks]
+"#,
+        )
+        .unwrap()
+    }
+
+    fn deps() -> BTreeMap<String, BTreeSet<String>> {
+        BTreeMap::from([
+            ("shared".to_string(), BTreeSet::new()),
+            ("api".to_string(), BTreeSet::from(["shared".to_string()])),
+            ("app".to_string(), BTreeSet::from(["api".to_string()])),
+            ("web".to_string(), BTreeSet::new()),
+        ])
+    }
+
+    #[test]
+    fn test_changed_file_affects_owning_project() {
+        let affected = resolve_affected(&config(), &[PathBuf::from("apps/web/src/page.tsx")], &deps());
+        assert_eq!(affected, BTreeSet::from(["web".to_string()]));
+    }
+
+    #[test]
+    fn test_changed_dependency_affects_dependents_transitively() {
+        let affected =
+            resolve_affected(&config(), &[PathBuf::from("crates/shared/src/lib.rs")], &deps());
+        assert_eq!(
+            affected,
+            BTreeSet::from(["shared".to_string(), "api".to_string(), "app".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_files_outside_projects_affect_nothing() {
+        let affected = resolve_affected(&config(), &[PathBuf::from("README.md")], &deps());
+        assert!(affected.is_empty());
+    }
+
+    #[test]
+    fn test_path_prefix_must_match_whole_component() {
+        let affected = resolve_affected(&config(), &[PathBuf::from("apps/apifoo/x.rs")], &deps());
+        assert!(affected.is_empty());
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::graph::TaskId;
+
+const CACHE_DIR: &str = ".meta/cache";
+
+/// Everything that goes into a task's cache key
+pub struct HashInputs<'a> {
+    pub project_dir: &'a Path,
+    pub inputs: &'a [String],
+    pub command: &'a str,
+    pub tool_command: &'a str,
+    pub tool_version: &'a str,
+    pub env: &'a BTreeMap<String, String>,
+    /// Hashes of the task's dependencies, so upstream changes invalidate
+    /// downstream entries
+    pub dependency_hashes: &'a [String],
+}
+
+/// Content hash of a task's inputs, command, tool version, environment and
+/// dependencies, as a hex string
+pub fn compute_hash(inputs: &HashInputs) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let mut field = |name: &str, value: &[u8]| {
+        hasher.update(name.as_bytes());
+        hasher.update([0]);
+        hasher.update((value.len() as u64).to_le_bytes());
+        hasher.update(value);
+    };
+
+    field("command", inputs.command.as_bytes());
+    field("tool", inputs.tool_command.as_bytes());
+    field("tool_version", inputs.tool_version.as_bytes());
+    for (key, value) in inputs.env {
+        field("env", format!("{}={}", key, value).as_bytes());
+    }
+    for path in expand_globs(inputs.project_dir, inputs.inputs)? {
+        let contents = fs::read(inputs.project_dir.join(&path))
+            .with_context(|| format!("Failed to read input {}", path.display()))?;
+        field("file", path.to_string_lossy().as_bytes());
+        field("contents", &Sha256::digest(&contents));
+    }
+    for hash in inputs.dependency_hashes {
+        field("dependency", hash.as_bytes());
+    }
+
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Files under `base` matching `patterns`, as sorted paths relative to
+/// `base`. Patterns starting with `!` exclude matches.
+pub fn expand_globs(base: &Path, patterns: &[String]) -> Result<Vec<PathBuf>> {
+    let mut excludes = Vec::new();
+    for pattern in patterns {
+        if let Some(exclude) = pattern.strip_prefix('!') {
+            excludes.push(glob::Pattern::new(exclude)?);
+        }
+    }
+
+    let mut files = Vec::new();
+    for pattern in patterns.iter().filter(|p| !p.starts_with('!')) {
+        // `dir/**` means "everything under dir", but the glob crate only
+        // matches directories for a trailing `**`
+        let pattern = match pattern.strip_suffix("**") {
+            Some(prefix) if prefix.is_empty() || prefix.ends_with('/') => format!("{}**/*", prefix),
+            _ => pattern.clone(),
+        };
+        let full = base.join(pattern);
+        for entry in glob::glob(&full.to_string_lossy())? {
+            let path = entry?;
+            if !path.is_file() {
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(base) else {
+                continue;
+            };
+            if excludes.iter().any(|e| e.matches_path(relative)) {
+                continue;
+            }
+            files.push(relative.to_path_buf());
+        }
+    }
+
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    task: String,
+    hash: String,
+    outputs: Vec<PathBuf>,
+}
+
+/// On-disk cache of successful task runs under `.meta/cache`
+pub struct TaskCache {
+    root: PathBuf,
+}
+
+impl TaskCache {
+    pub fn new() -> Self {
+        Self::at(PathBuf::from(CACHE_DIR))
+    }
+
+    fn at(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn entry_dir(&self, id: &TaskId, hash: &str) -> PathBuf {
+        self.root.join(&id.project).join(&id.task).join(hash)
+    }
+
+    /// Restore the outputs recorded for `hash` into `project_dir` and return
+    /// the captured log, or `None` on a cache miss
+    pub fn restore(&self, id: &TaskId, hash: &str, project_dir: &Path) -> Result<Option<String>> {
+        let dir = self.entry_dir(id, hash);
+        let Ok(manifest) = fs::read_to_string(dir.join("manifest.json")) else {
+            return Ok(None);
+        };
+        let manifest: Manifest = serde_json::from_str(&manifest)?;
+
+        for output in &manifest.outputs {
+            let target = project_dir.join(output);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(dir.join("outputs").join(output), &target)
+                .with_context(|| format!("Failed to restore {}", target.display()))?;
+        }
+
+        Ok(Some(fs::read_to_string(dir.join("output.log")).unwrap_or_default()))
+    }
+
+    /// Record a successful run: copy files matching `outputs` and save the
+    /// captured log
+    pub fn store(
+        &self,
+        id: &TaskId,
+        hash: &str,
+        project_dir: &Path,
+        outputs: &[String],
+        log: &str,
+    ) -> Result<()> {
+        let dir = self.entry_dir(id, hash);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(dir.join("outputs"))?;
+
+        let files = expand_globs(project_dir, outputs)?;
+        for file in &files {
+            let target = dir.join("outputs").join(file);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(project_dir.join(file), target)?;
+        }
+
+        fs::write(dir.join("output.log"), log)?;
+        // Manifest is written last: its presence marks the entry complete
+        let manifest = Manifest {
+            task: id.to_string(),
+            hash: hash.to_string(),
+            outputs: files,
+        };
+        fs::write(dir.join("manifest.json"), serde_json::to_string_pretty(&manifest)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_of(dir: &Path, command: &str) -> String {
+        compute_hash(&HashInputs {
+            project_dir: dir,
+            inputs: &["src/**/*.rs".to_string(), "Cargo.toml".to_string()],
+            command,
+            tool_command: "cargo",
+            tool_version: "cargo 1.80.0",
+            env: &BTreeMap::new(),
+            dependency_hashes: &[],
+        })
+        .unwrap()
+    }
+
+    fn project() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("src/bin")).unwrap();
+        fs::write(tmp.path().join("Cargo.toml"), "[package]\n").unwrap();
+        fs::write(tmp.path().join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(tmp.path().join("src/bin/tool.rs"), "fn main() {}").unwrap();
+        fs::write(tmp.path().join("README.md"), "docs").unwrap();
+        tmp
+    }
+
+    #[test]
+    fn test_hash_is_stable() {
+        let tmp = project();
+        assert_eq!(hash_of(tmp.path(), "clippy"), hash_of(tmp.path(), "clippy"));
+    }
+
+    #[test]
+    fn test_hash_changes_with_input_contents() {
+        let tmp = project();
+        let before = hash_of(tmp.path(), "clippy");
+        fs::write(tmp.path().join("src/main.rs"), "fn main() { todo!() }").unwrap();
+        assert_ne!(before, hash_of(tmp.path(), "clippy"));
+    }
+
+    #[test]
+    fn test_hash_ignores_files_outside_inputs() {
+        let tmp = project();
+        let before = hash_of(tmp.path(), "clippy");
+        fs::write(tmp.path().join("README.md"), "changed").unwrap();
+        assert_eq!(before, hash_of(tmp.path(), "clippy"));
+    }
+
+    #[test]
+    fn test_hash_changes_with_command() {
+        let tmp = project();
+        assert_ne!(hash_of(tmp.path(), "clippy"), hash_of(tmp.path(), "build"));
+    }
+
+    #[test]
+    fn test_expand_globs_supports_exclusions() {
+        let tmp = project();
+        let files = expand_globs(
+            tmp.path(),
+            &["src/**/*.rs".to_string(), "!src/bin/**".to_string()],
+        )
+        .unwrap();
+        assert_eq!(files, vec![PathBuf::from("src/main.rs")]);
+    }
+
+    #[test]
+    fn test_store_and_restore_round_trip() {
+        let tmp = project();
+        let cache = TaskCache::at(tmp.path().join(".meta/cache"));
+        let id = TaskId::new("api", "build");
+        fs::create_dir_all(tmp.path().join("dist")).unwrap();
+        fs::write(tmp.path().join("dist/app.js"), "bundle").unwrap();
+
+        assert!(cache.restore(&id, "abc", tmp.path()).unwrap().is_none());
+
+        cache
+            .store(&id, "abc", tmp.path(), &["dist/**".to_string()], "built\n")
+            .unwrap();
+        fs::remove_dir_all(tmp.path().join("dist")).unwrap();
+
+        let log = cache.restore(&id, "abc", tmp.path()).unwrap();
+        assert_eq!(log.as_deref(), Some("built\n"));
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("dist/app.js")).unwrap(),
+            "bundle"
+        );
+    }
+}
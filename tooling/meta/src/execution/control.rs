@@ -0,0 +1,167 @@
+//! `meta restart <project>` and `meta stop <project>`: lifecycle control for a
+//! single dev process in the workspace's tmux session
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tokio::process::Command;
+
+use super::{
+    dev_command, events::log_event, get_session_name, get_tmux_panes, pane_command,
+    ports::AutoPorts, process::ProcessTable, ready::Readiness, DevCommand, PaneInfo,
+};
+use crate::config::Config;
+
+/// How long processes get to exit after SIGTERM before they are killed
+const STOP_GRACE: Duration = Duration::from_secs(5);
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Restart a project's dev process in place, keeping its pane, title and log
+pub async fn restart(config: &Config, project: &str) -> Result<()> {
+    let command = resolve(config, project)?;
+    let (session_name, pane) = find_pane(project).await?;
+
+    println!("🔄 Restarting {}...", project);
+    let wrapped = pane_command(project, &command);
+    terminate(&pane, &park_command(&format!("⏳ Restarting {}...", project), &wrapped)).await?;
+    log_event(project, "RESTART: Manual restart via meta restart");
+
+    let mut readiness = Readiness::default();
+    readiness.starting(project, command.ready.clone());
+    respawn(&pane, &wrapped).await?;
+    println!("✅ Restarted {} in tmux session '{}'", project, session_name);
+
+    readiness.wait_for(&[project.to_string()]).await;
+    Ok(())
+}
+
+/// Stop a project's dev process, leaving the rest of the session running.
+/// The pane stays open and starts the process again when Enter is pressed.
+pub async fn stop(config: &Config, project: &str) -> Result<()> {
+    let command = resolve(config, project)?;
+    let (session_name, pane) = find_pane(project).await?;
+
+    println!("🛑 Stopping {}...", project);
+    let message = format!(
+        "⏹  {} stopped. Press Enter or run 'meta restart {}' to start it again.",
+        project, project
+    );
+    terminate(&pane, &park_command(&message, &pane_command(project, &command))).await?;
+    log_event(project, "STOPPED: Stopped via meta stop");
+
+    println!("✅ Stopped {} in tmux session '{}'", project, session_name);
+    println!("   Start again: meta restart {}", project);
+    Ok(())
+}
+
+/// The project's dev command, with the `port = "auto"` port it was started
+/// with
+fn resolve(config: &Config, project: &str) -> Result<DevCommand> {
+    let config_project = config
+        .projects
+        .get(project)
+        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", project))?;
+    let mut auto_ports = AutoPorts::session();
+    let command = dev_command(config, project, config_project, &mut auto_ports)?
+        .ok_or_else(|| anyhow::anyhow!("Project {} has no dev task", project))?;
+    auto_ports.save()?;
+    Ok(command)
+}
+
+/// Find the pane running `project` in this workspace's session, matched by
+/// pane title like `meta status` does
+async fn find_pane(project: &str) -> Result<(String, PaneInfo)> {
+    let session_name = get_session_name();
+    let session_active = Command::new("tmux")
+        .args(["has-session", "-t", &session_name])
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if !session_active {
+        anyhow::bail!(
+            "No active {} session. Run 'meta dev' to start.",
+            session_name
+        );
+    }
+
+    let panes = get_tmux_panes(&session_name).await?;
+    let titles: Vec<String> = panes.iter().map(|p| p.title.clone()).collect();
+    let pane = panes
+        .into_iter()
+        .find(|p| p.title == project)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} is not running in {} (panes: {})",
+                project,
+                session_name,
+                titles.join(", ")
+            )
+        })?;
+    Ok((session_name, pane))
+}
+
+/// A pane command that shows `message` and runs `command` once Enter is
+/// pressed
+fn park_command(message: &str, command: &str) -> String {
+    format!("echo {}; read -r; {}", shell_words::quote(message), command)
+}
+
+/// Stop everything running in a pane.
+///
+/// The process tree is captured first, then the pane is respawned with
+/// `parked` so the wrapper shell cannot restart the command and the pane
+/// survives. Processes that outlive the respawn (e.g. children that ignore
+/// SIGHUP) get SIGTERM, then SIGKILL after [`STOP_GRACE`].
+async fn terminate(pane: &PaneInfo, parked: &str) -> Result<()> {
+    let mut pids = vec![pane.pid];
+    pids.extend(ProcessTable::snapshot().await.descendants(pane.pid));
+
+    respawn(pane, parked).await?;
+
+    signal(&pids, "-TERM").await;
+    let started = Instant::now();
+    loop {
+        let processes = ProcessTable::snapshot().await;
+        pids.retain(|pid| processes.is_alive(*pid));
+        if pids.is_empty() {
+            return Ok(());
+        }
+        if started.elapsed() >= STOP_GRACE {
+            signal(&pids, "-KILL").await;
+            return Ok(());
+        }
+        tokio::time::sleep(STOP_POLL_INTERVAL).await;
+    }
+}
+
+/// Replace whatever runs in the pane with `command`, started from the
+/// workspace root so the wrapper's relative log paths resolve
+async fn respawn(pane: &PaneInfo, command: &str) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let output = Command::new("tmux")
+        .args(["respawn-pane", "-k", "-t", &pane.id, "-c"])
+        .arg(&cwd)
+        .arg(command)
+        .output()
+        .await?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to respawn tmux pane {}: {}",
+            pane.id,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+async fn signal(pids: &[u32], signal: &str) {
+    if pids.is_empty() {
+        return;
+    }
+    let _ = Command::new("kill")
+        .arg(signal)
+        .args(pids.iter().map(u32::to_string))
+        .output()
+        .await;
+}
@@ -0,0 +1,1159 @@
+//! `meta doctor`: checks of meta.toml, the tools it runs and the files they
+//! read. Every check has a stable ID, a severity and, for problems, a
+//! suggested fix, so CI and editors can use `--json` instead of the text.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use serde::Serialize;
+use tokio::process::Command;
+
+use super::{detect_cargo_workspace, fixes, project_has_task, workspace};
+use crate::{
+    config::{Config, McpServer, PortConfig},
+    version::Version,
+};
+
+/// Bacon jobs that need no `[jobs.<name>]` in bacon.toml
+pub const BUILTIN_BACON_JOBS: &[&str] = &["check", "clippy", "test", "doc", "run", "run-long"];
+
+pub struct DoctorOptions {
+    /// Print the checks as JSON instead of text
+    pub json: bool,
+    /// Fail on warnings as well as errors
+    pub strict: bool,
+    /// Offer to edit meta.toml, bacon.toml and .mcp.json to resolve problems
+    pub fix: bool,
+    /// Apply fixes without asking
+    pub yes: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Ok,
+    Info,
+    Warning,
+    Error,
+}
+
+/// Where a check is listed in the text output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Config,
+    Tools,
+    Tmux,
+    Mcp,
+    Projects,
+    Validation,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Check {
+    /// Stable identifier such as `tool.not-found`; messages may change,
+    /// IDs don't
+    pub id: &'static str,
+    pub category: Category,
+    pub severity: Severity,
+    /// The tool, project or `project:task` checked
+    pub subject: Option<String>,
+    pub message: String,
+    /// What to change to resolve a warning or error
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn new(
+        id: &'static str,
+        category: Category,
+        severity: Severity,
+        subject: Option<&str>,
+        message: impl Into<String>,
+    ) -> Check {
+        Check {
+            id,
+            category,
+            severity,
+            subject: subject.map(str::to_string),
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Check {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+/// Run every check and report them, then offer fixes with `--fix`. Fails
+/// if any check is an error, or a warning with `--strict`. A meta.toml that
+/// fails to load is reported as a check too, so `--json` always prints a
+/// report.
+pub async fn doctor(config: Result<Config>, options: &DoctorOptions) -> Result<()> {
+    let mut checks = run_checks(&config).await;
+    let project_count = config.as_ref().map_or(0, |c| c.projects.len());
+    let (mut errors, mut warnings) = counts(&checks);
+
+    if options.json {
+        let report = serde_json::json!({
+            "ok": errors == 0 && !(options.strict && warnings > 0),
+            "strict": options.strict,
+            "errors": errors,
+            "warnings": warnings,
+            "checks": checks,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&checks, project_count);
+        print_summary(errors, warnings, options.strict);
+    }
+
+    if options.fix {
+        if let Ok(config) = &config {
+            if fixes::fix(config, &checks, options.yes)? {
+                checks = run_checks(&Config::load()).await;
+                (errors, warnings) = counts(&checks);
+                println!("🔁 After fixes:");
+                print_summary(errors, warnings, options.strict);
+            }
+        }
+    }
+
+    if errors > 0 {
+        anyhow::bail!("Configuration has errors");
+    }
+    if options.strict && warnings > 0 {
+        anyhow::bail!("Configuration has warnings (--strict)");
+    }
+    Ok(())
+}
+
+async fn run_checks(config: &Result<Config>) -> Vec<Check> {
+    let mut checks = Vec::new();
+    match config {
+        Ok(config) => {
+            checks.push(Check::new(
+                "config.loaded",
+                Category::Config,
+                Severity::Ok,
+                None,
+                if config.layers.is_empty() {
+                    format!("meta.toml loaded successfully (workspace: {})", config.workspace.name)
+                } else {
+                    format!(
+                        "meta.toml loaded successfully with {} (workspace: {})",
+                        config.layers.join(" and "),
+                        config.workspace.name
+                    )
+                },
+            ));
+            checks.extend(check_tools(config).await);
+        }
+        Err(e) => checks.push(
+            Check::new(
+                "config.invalid",
+                Category::Config,
+                Severity::Error,
+                None,
+                format!("meta.toml could not be loaded: {:#}", e),
+            )
+            .with_fix("Fix the error in meta.toml, or run 'meta init' to generate one"),
+        ),
+    }
+    checks.push(check_tmux().await);
+    checks.extend(check_mcp(Path::new(".mcp.json")).await);
+    if let Ok(config) = config {
+        checks.extend(check_projects(config));
+        checks.extend(check_validation(config));
+    }
+    checks
+}
+
+/// Errors and warnings among `checks`
+fn counts(checks: &[Check]) -> (usize, usize) {
+    let count = |severity| checks.iter().filter(|c| c.severity == severity).count();
+    (count(Severity::Error), count(Severity::Warning))
+}
+
+async fn check_tools(config: &Config) -> Vec<Check> {
+    let mut tools: Vec<_> = config.tools.iter().collect();
+    tools.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut checks = Vec::new();
+    for (tool_name, tool_config) in tools {
+        let subject = Some(tool_name.as_str());
+        if !tool_config.enabled {
+            checks.push(Check::new(
+                "tool.disabled",
+                Category::Tools,
+                Severity::Info,
+                subject,
+                format!("{} (disabled)", tool_name),
+            ));
+            continue;
+        }
+
+        let command = &tool_config.command;
+        checks.push(match Command::new(command).arg("--version").output().await {
+            Ok(output) if output.status.success() => {
+                let version_str = String::from_utf8_lossy(&output.stdout);
+                let version = version_str.lines().next().unwrap_or("unknown").trim();
+                let found = format!("{} → {} ({})", tool_name, command, version);
+                match &tool_config.version {
+                    None => {
+                        Check::new("tool.available", Category::Tools, Severity::Ok, subject, found)
+                    }
+                    // The first line is what `meta run` and `meta dev` compare
+                    Some(required) => match Version::find(version) {
+                        Some(version) if required.matches(version) => Check::new(
+                            "tool.available",
+                            Category::Tools,
+                            Severity::Ok,
+                            subject,
+                            format!("{}, satisfies {}", found, required),
+                        ),
+                        Some(version) => Check::new(
+                            "tool.version-mismatch",
+                            Category::Tools,
+                            Severity::Error,
+                            subject,
+                            format!("{}: {} does not satisfy {}", found, version, required),
+                        )
+                        .with_fix(format!(
+                            "Install {} {}, or change version under [tools.{}]",
+                            command, required, tool_name
+                        )),
+                        // `meta run` and `meta dev` refuse to start without one
+                        None => Check::new(
+                            "tool.version-unknown",
+                            Category::Tools,
+                            Severity::Error,
+                            subject,
+                            format!("{}: no version number to compare with {}", found, required),
+                        )
+                        .with_fix(format!(
+                            "Check what '{} --version' prints, or remove version under [tools.{}]",
+                            command, tool_name
+                        )),
+                    },
+                }
+            }
+            // Only a problem for `meta run` and `meta dev` if a version is required
+            Ok(_) => Check::new(
+                "tool.version-check-failed",
+                Category::Tools,
+                if tool_config.version.is_some() {
+                    Severity::Error
+                } else {
+                    Severity::Warning
+                },
+                subject,
+                format!("{} → {} (found but version check failed)", tool_name, command),
+            )
+            .with_fix(format!("Check that '{} --version' runs", command)),
+            Err(_) => Check::new(
+                "tool.not-found",
+                Category::Tools,
+                Severity::Error,
+                subject,
+                format!("{} → {} (NOT FOUND)", tool_name, command),
+            )
+            .with_fix(format!(
+                "Install {}, or set enabled = false under [tools.{}]",
+                command, tool_name
+            )),
+        });
+    }
+    checks
+}
+
+/// tmux is needed for multi-process `meta dev`
+async fn check_tmux() -> Check {
+    match Command::new("tmux").arg("-V").output().await {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            Check::new(
+                "tmux.available",
+                Category::Tmux,
+                Severity::Ok,
+                None,
+                format!("tmux → {} (multi-process dev mode available)", version),
+            )
+        }
+        _ => Check::new(
+            "tmux.not-found",
+            Category::Tmux,
+            Severity::Warning,
+            None,
+            "tmux not found (install for 'meta dev' multi-process mode)",
+        )
+        .with_fix("Install: brew install tmux (macOS) or apt install tmux (Linux)"),
+    }
+}
+
+/// Docker is required if `.mcp.json` runs an MCP server with it, and an
+/// mcp-log-server entry should match what `meta init` writes
+async fn check_mcp(mcp_path: &Path) -> Vec<Check> {
+    if !mcp_json_references_docker(mcp_path) {
+        return vec![];
+    }
+    let mut checks = vec![match Command::new("docker").arg("--version").output().await {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            Check::new(
+                "mcp.docker-available",
+                Category::Mcp,
+                Severity::Ok,
+                None,
+                format!("docker → {} (.mcp.json references docker)", version),
+            )
+        }
+        _ => Check::new(
+            "mcp.docker-not-found",
+            Category::Mcp,
+            Severity::Warning,
+            None,
+            ".mcp.json references docker but docker not found on PATH",
+        )
+        .with_fix(
+            "Install Docker to use mcp-log-server, or use the built-in server instead: \
+             {\"command\": \"meta\", \"args\": [\"mcp\"]}",
+        ),
+    }];
+
+    let name = McpServer::Docker.entry_name();
+    let entry = fs::read_to_string(mcp_path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        .and_then(|root| root["mcpServers"].get(name).cloned());
+    let stale = |entry: &serde_json::Value| {
+        entry["command"] == "docker" && *entry != McpServer::Docker.entry()
+    };
+    if entry.as_ref().is_some_and(stale) {
+        checks.push(
+            Check::new(
+                "mcp.entry-stale",
+                Category::Mcp,
+                Severity::Warning,
+                Some(name),
+                format!("{} entry in .mcp.json differs from the one 'meta init' writes", name),
+            )
+            .with_fix(format!(
+                "Run 'meta doctor --fix', or replace it with: {}",
+                serde_json::to_string(&McpServer::Docker.entry()).unwrap_or_default()
+            )),
+        );
+    }
+    checks
+}
+
+/// Project paths, dev tasks and the bacon config they need
+fn check_projects(config: &Config) -> Vec<Check> {
+    let mut projects: Vec<_> = config.projects.iter().collect();
+    projects.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut checks = Vec::new();
+    for (name, project) in projects {
+        let subject = Some(name.as_str());
+        if !Path::new(&project.path).exists() {
+            checks.push(
+                Check::new(
+                    "project.path-not-found",
+                    Category::Projects,
+                    Severity::Error,
+                    subject,
+                    format!("{} → {} (PATH NOT FOUND)", name, project.path),
+                )
+                .with_fix(format!(
+                    "Update path under [projects.{}] in meta.toml ('meta doctor --fix' finds \
+                     moved projects)",
+                    name
+                )),
+            );
+            continue;
+        }
+        checks.push(Check::new(
+            "project.path-exists",
+            Category::Projects,
+            Severity::Ok,
+            subject,
+            format!("{} → {} ({})", name, project.path, project.project_type),
+        ));
+
+        // Check if project has dev task (per-project, not path-based)
+        if !project_has_task(project, "dev") {
+            continue;
+        }
+        checks.push(Check::new(
+            "project.dev-task",
+            Category::Projects,
+            Severity::Info,
+            subject,
+            if project.dev_default {
+                "dev task configured"
+            } else {
+                "dev task configured (excluded from default 'meta dev', use -p to include)"
+            },
+        ));
+
+        // Issue #3: validate bacon config if tool is bacon
+        if let Some(dev_task) = project.tasks.get("dev") {
+            if dev_task.tool == "bacon" {
+                checks.extend(validate_bacon_config(
+                    name,
+                    &project.path,
+                    &dev_task.command.to_string(),
+                ));
+            }
+        }
+    }
+    checks
+}
+
+/// Returns true if `.mcp.json` at `path` contains an mcpServers entry whose
+/// `command` field is `"docker"`. Missing/invalid files return false.
+pub fn mcp_json_references_docker(path: &Path) -> bool {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return false;
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&contents) else {
+        return false;
+    };
+    value
+        .get("mcpServers")
+        .and_then(|s| s.as_object())
+        .map(|servers| {
+            servers
+                .values()
+                .any(|entry| entry.get("command").and_then(|c| c.as_str()) == Some("docker"))
+        })
+        .unwrap_or(false)
+}
+
+/// Validate that a bacon-based task has a valid bacon.toml configuration
+pub fn validate_bacon_config(project: &str, project_path: &str, command: &str) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    // Check for bacon.toml in the project directory
+    let bacon_toml = Path::new(project_path).join("bacon.toml");
+    let bacon_prefs = Path::new(project_path).join(".bacon.toml");
+
+    if !bacon_toml.exists() && !bacon_prefs.exists() {
+        // Also check workspace root
+        let message = match detect_cargo_workspace(project_path) {
+            Some(ws_root) => {
+                let ws_bacon = Path::new(&ws_root).join("bacon.toml");
+                let ws_bacon_prefs = Path::new(&ws_root).join(".bacon.toml");
+                (!ws_bacon.exists() && !ws_bacon_prefs.exists()).then(|| {
+                    format!(
+                        "bacon.toml not found in '{}' or workspace root — bacon may fail silently",
+                        project_path
+                    )
+                })
+            }
+            None => Some(format!(
+                "bacon.toml not found in '{}' — bacon may fail silently",
+                project_path
+            )),
+        };
+        if let Some(message) = message {
+            checks.push(
+                Check::new(
+                    "bacon.config-not-found",
+                    Category::Projects,
+                    Severity::Warning,
+                    Some(project),
+                    message,
+                )
+                .with_fix(format!(
+                    "Create one with 'bacon --init' in '{}', or run 'meta doctor --fix'",
+                    project_path
+                )),
+            );
+        }
+    }
+
+    // Check if the referenced job exists in bacon.toml
+    let job_name = command.split_whitespace().next().unwrap_or(command);
+    if let Ok(content) = fs::read_to_string(&bacon_toml) {
+        let bacon = match content.parse::<toml::Table>() {
+            Ok(bacon) => bacon,
+            Err(e) => {
+                checks.push(
+                    Check::new(
+                        "bacon.config-invalid",
+                        Category::Projects,
+                        Severity::Error,
+                        Some(project),
+                        format!("{} is not valid TOML: {}", bacon_toml.display(), e.message()),
+                    )
+                    .with_fix(format!("Fix the syntax error in {}", bacon_toml.display())),
+                );
+                return checks;
+            }
+        };
+        let job_header = format!("[jobs.{}]", job_name);
+        let defined = bacon.get("jobs").and_then(|jobs| jobs.get(job_name)).is_some();
+        // Built-in jobs don't need to be in bacon.toml
+        if !defined && !BUILTIN_BACON_JOBS.contains(&job_name) {
+            checks.push(
+                Check::new(
+                    "bacon.job-not-found",
+                    Category::Projects,
+                    Severity::Warning,
+                    Some(project),
+                    format!("job '{}' not found in bacon.toml — bacon will fail", job_name),
+                )
+                .with_fix(format!(
+                    "Add a {} section to {}, or run 'meta doctor --fix'",
+                    job_header,
+                    bacon_toml.display()
+                )),
+            );
+        }
+    }
+
+    checks
+}
+
+/// Task commands, dev ports and turborepo invocations
+fn check_validation(config: &Config) -> Vec<Check> {
+    let mut projects: Vec<_> = config.projects.iter().collect();
+    projects.sort_by(|a, b| a.0.cmp(b.0));
+    let mut checks = Vec::new();
+
+    // Validate task commands parse (quoting, shell operators)
+    for (name, project) in &projects {
+        let mut tasks: Vec<_> = project.tasks.iter().collect();
+        tasks.sort_by(|a, b| a.0.cmp(b.0));
+        for (task_name, task) in tasks {
+            if task.shell {
+                continue;
+            }
+            if let Err(e) = task.command.args() {
+                let subject = format!("{}:{}", name, task_name);
+                checks.push(
+                    Check::new(
+                        "task.command-invalid",
+                        Category::Validation,
+                        Severity::Error,
+                        Some(&subject),
+                        format!("{}: {}", subject, e),
+                    )
+                    .with_fix("Fix the quoting, or set shell = true to run it with sh -c"),
+                );
+            }
+        }
+    }
+
+    // Dev tasks sharing a port cannot run at the same time
+    let mut port_users: BTreeMap<u16, Vec<&str>> = BTreeMap::new();
+    for (name, project) in &projects {
+        if let Some(PortConfig::Fixed(port)) = project.tasks.get("dev").and_then(|t| t.port) {
+            port_users.entry(port).or_default().push(name);
+        }
+    }
+    for (port, names) in port_users {
+        if names.len() > 1 {
+            let names = names.join(", ");
+            checks.push(
+                Check::new(
+                    "dev.port-conflict",
+                    Category::Validation,
+                    Severity::Warning,
+                    Some(&names),
+                    format!(
+                        "Port {} is declared by several dev tasks: {} (they cannot run together)",
+                        port, names
+                    ),
+                )
+                .with_fix("Give each dev task its own port, or use port = \"auto\""),
+            );
+        }
+    }
+
+    // Validate turborepo commands
+    for (name, project) in &projects {
+        let Some(dev_task) = project.tasks.get("dev") else {
+            continue;
+        };
+        if dev_task.tool != "turborepo" {
+            continue;
+        }
+        let cmd = dev_task.command.to_string();
+        if !cmd.starts_with("run ") {
+            checks.push(
+                Check::new(
+                    "turbo.missing-run",
+                    Category::Validation,
+                    Severity::Warning,
+                    Some(name),
+                    format!("{} dev task should start with 'run': '{}'", name, cmd),
+                )
+                .with_fix("Suggested: 'run dev --filter=...' ('meta doctor --fix' adds it)"),
+            );
+        }
+        if !cmd.contains("--filter=") {
+            checks.push(
+                Check::new(
+                    "turbo.missing-filter",
+                    Category::Validation,
+                    Severity::Warning,
+                    Some(name),
+                    format!("{} turbo task missing --filter flag: '{}'", name, cmd),
+                )
+                .with_fix("Add --filter=<package> so only this project's dev task runs"),
+            );
+        }
+    }
+
+    // Turbo filters and tasks, cargo packages and binaries
+    for (name, project) in &projects {
+        // A missing path is already an error
+        if !Path::new(&project.path).exists() {
+            continue;
+        }
+        let mut tasks: Vec<_> = project.tasks.iter().collect();
+        tasks.sort_by(|a, b| a.0.cmp(b.0));
+        for (task_name, task) in tasks {
+            let (false, Ok(args)) = (task.shell, task.command.args()) else {
+                continue;
+            };
+            let subject = format!("{}:{}", name, task_name);
+            match task.tool.as_str() {
+                "turborepo" => checks.extend(check_turbo_task(&subject, &project.path, &args)),
+                "cargo" => checks.extend(check_cargo_task(&subject, &project.path, &args)),
+                _ => {}
+            }
+        }
+    }
+
+    checks
+}
+
+/// A `turbo run` resolved against the workspace: each `--filter` names a
+/// package, and each task is in turbo.json and the filtered packages'
+/// scripts
+fn check_turbo_task(subject: &str, project_path: &str, args: &[String]) -> Vec<Check> {
+    let Some(("run", rest)) = args.split_first().map(|(first, rest)| (first.as_str(), rest)) else {
+        return vec![];
+    };
+    let Some(root) = workspace::turbo_root(Path::new(project_path)) else {
+        return vec![Check::new(
+            "turbo.config-not-found",
+            Category::Validation,
+            Severity::Warning,
+            Some(subject),
+            format!("{}: no turbo.json in '{}' or above it", subject, project_path),
+        )
+        .with_fix("Create turbo.json at the workspace root (npx turbo init)")];
+    };
+
+    let mut checks = Vec::new();
+    let packages = workspace::js_packages(&root);
+    let mut filtered: Vec<(&String, &PathBuf)> = Vec::new();
+    for filter in flag_values(rest, &["--filter", "-F"]) {
+        // Paths, git ranges and exclusions aren't package names
+        let Some(name) = filter_package(&filter).filter(|_| !packages.is_empty()) else {
+            continue;
+        };
+        let matched: Vec<_> = match glob::Pattern::new(name) {
+            Ok(pattern) if name.contains('*') => {
+                packages.iter().filter(|(package, _)| pattern.matches(package)).collect()
+            }
+            _ => packages.get_key_value(name).into_iter().collect(),
+        };
+        if matched.is_empty() {
+            let known: Vec<&str> = packages.keys().map(String::as_str).collect();
+            checks.push(
+                Check::new(
+                    "turbo.filter-unknown",
+                    Category::Validation,
+                    Severity::Error,
+                    Some(subject),
+                    format!("{}: --filter={} matches no workspace package", subject, filter),
+                )
+                .with_fix(format!("Workspace packages: {}", known.join(", "))),
+            );
+        } else if !name.contains('*') {
+            filtered.extend(matched);
+        }
+    }
+
+    let defined = workspace::turbo_tasks(&root).unwrap_or_default();
+    for task in rest.iter().take_while(|arg| !arg.starts_with('-')) {
+        let in_package = |(package, dir): &(&String, &PathBuf)| {
+            defined.contains(&format!("{}#{}", package, task))
+                || workspace::turbo_tasks(dir).is_some_and(|tasks| tasks.contains(task))
+        };
+        if !defined.contains(task) && !filtered.iter().any(in_package) {
+            checks.push(
+                Check::new(
+                    "turbo.task-undefined",
+                    Category::Validation,
+                    Severity::Error,
+                    Some(subject),
+                    format!("{}: task '{}' is not defined in turbo.json", subject, task),
+                )
+                .with_fix(format!(
+                    "Add \"{}\" under \"tasks\" in {}",
+                    task,
+                    root.join("turbo.json").display()
+                )),
+            );
+        }
+        for (package, dir) in &filtered {
+            if !workspace::package_scripts(dir).contains(task) {
+                checks.push(
+                    Check::new(
+                        "turbo.script-missing",
+                        Category::Validation,
+                        Severity::Warning,
+                        Some(subject),
+                        format!(
+                            "{}: {} has no '{}' script, so turbo runs nothing",
+                            subject, package, task
+                        ),
+                    )
+                    .with_fix(format!(
+                        "Add a \"{}\" script to {}",
+                        task,
+                        dir.join("package.json").display()
+                    )),
+                );
+            }
+        }
+    }
+    checks
+}
+
+/// The package a turbo filter selects, without the `...` and `^` that add
+/// its dependencies or dependents. None for exclusions, directories and git
+/// ranges.
+fn filter_package(filter: &str) -> Option<&str> {
+    let name = filter.trim_start_matches("...").trim_start_matches('^');
+    if filter.starts_with('!') || name.starts_with(['.', '/', '{', '[']) {
+        return None;
+    }
+    let name = name.trim_end_matches("...").trim_end_matches('^');
+    (!name.is_empty() && !name.contains(['{', '['])).then_some(name)
+}
+
+/// `-p`/`--package` and `--bin` of a cargo task name packages and binaries
+/// that exist
+fn check_cargo_task(subject: &str, project_path: &str, args: &[String]) -> Vec<Check> {
+    let Some(targets) = workspace::cargo_targets(project_path) else {
+        return vec![];
+    };
+    let mut checks = Vec::new();
+    let packages = flag_values(args, &["--package", "-p"]);
+    for package in &packages {
+        // `-p serde@1.0` selects by version too
+        let name = package.split('@').next().unwrap_or(package);
+        if !targets.packages.contains_key(name) {
+            let known: Vec<&str> = targets.packages.keys().map(String::as_str).collect();
+            checks.push(
+                Check::new(
+                    "cargo.package-not-found",
+                    Category::Validation,
+                    Severity::Error,
+                    Some(subject),
+                    format!("{}: -p {} is not a package in the Cargo workspace", subject, package),
+                )
+                .with_fix(format!("Packages: {}", known.join(", "))),
+            );
+        }
+    }
+    if !checks.is_empty() {
+        return checks;
+    }
+
+    let bins = targets.bins(packages.first().map(|p| p.split('@').next().unwrap_or(p)));
+    for bin in flag_values(args, &["--bin"]) {
+        if !bins.contains(bin.as_str()) {
+            let known: Vec<&str> = bins.iter().copied().collect();
+            checks.push(
+                Check::new(
+                    "cargo.bin-not-found",
+                    Category::Validation,
+                    Severity::Error,
+                    Some(subject),
+                    format!("{}: --bin {} is not a binary target", subject, bin),
+                )
+                .with_fix(if known.is_empty() {
+                    "No binary targets found; add src/main.rs or a [[bin]]".to_string()
+                } else {
+                    format!("Binaries: {}", known.join(", "))
+                }),
+            );
+        }
+    }
+    checks
+}
+
+/// Values given to any of `flags` (`--flag value`, `--flag=value`, `-fvalue`)
+/// before a `--`
+fn flag_values(args: &[String], flags: &[&str]) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut args = args.iter().take_while(|arg| *arg != "--").peekable();
+    while let Some(arg) = args.next() {
+        for flag in flags {
+            if arg == flag {
+                // `--bin` alone lists the binaries
+                if let Some(value) = args.next_if(|value| !value.starts_with('-')) {
+                    values.push(value.clone());
+                }
+            } else if let Some(value) = arg.strip_prefix(flag) {
+                let short = !flag.starts_with("--");
+                match value.strip_prefix('=') {
+                    Some(value) => values.push(value.to_string()),
+                    None if short => values.push(value.to_string()),
+                    None => {}
+                }
+            }
+        }
+    }
+    values
+}
+
+fn heading(category: Category, project_count: usize) -> String {
+    match category {
+        Category::Config => "📋 Configuration File:".to_string(),
+        Category::Tools => "🔧 Tool Availability:".to_string(),
+        Category::Tmux => "🖥️  Terminal Multiplexer:".to_string(),
+        Category::Mcp => "🐳 MCP Integration:".to_string(),
+        Category::Projects => format!("📦 Projects ({}):", project_count),
+        Category::Validation => "🔍 Configuration Validation:".to_string(),
+    }
+}
+
+fn symbol(check: &Check) -> &'static str {
+    match check.severity {
+        Severity::Ok => "✓",
+        Severity::Info if check.id == "tool.disabled" => "⊘",
+        Severity::Info => "•",
+        Severity::Warning => "⚠",
+        Severity::Error => "✗",
+    }
+}
+
+fn print_report(checks: &[Check], project_count: usize) {
+    println!("🏥 Meta Doctor - Configuration Diagnostics\n");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let categories = [
+        Category::Config,
+        Category::Tools,
+        Category::Tmux,
+        Category::Mcp,
+        Category::Projects,
+        Category::Validation,
+    ];
+    for category in categories {
+        let section: Vec<&Check> = checks.iter().filter(|c| c.category == category).collect();
+        // Validation is listed even when it found nothing
+        if section.is_empty() && category != Category::Validation {
+            continue;
+        }
+        println!("\n{}", heading(category, project_count));
+        let mut project: Option<&str> = None;
+        for check in section {
+            // A project's dev task and bacon checks are listed under it
+            let nested = category == Category::Projects && check.subject.as_deref() == project;
+            if !nested {
+                project = check.subject.as_deref();
+            }
+            let indent = if nested { "    " } else { "  " };
+            println!("{}{} {}", indent, symbol(check), check.message);
+            if let Some(fix) = &check.fix {
+                println!("{}  {}", indent, fix);
+            }
+        }
+    }
+    println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+}
+
+fn print_summary(errors: usize, warnings: usize, strict: bool) {
+    println!("\n📊 Summary:");
+
+    if errors == 0 && warnings == 0 {
+        println!("  🎉 All checks passed! Meta is ready to use.");
+        println!("\n💡 Quick Start:");
+        println!("  • Run 'meta dev' to start all development servers");
+        println!("  • Run 'meta dev --projects api' to start specific project");
+        println!("  • Run 'meta run <task>' to execute any task across projects");
+    } else {
+        if errors > 0 {
+            println!("  ✗ {} error(s) found - these must be fixed", errors);
+        }
+        if warnings > 0 && strict {
+            println!("  ⚠ {} warning(s) - treated as errors with --strict", warnings);
+        } else if warnings > 0 {
+            println!(
+                "  ⚠ {} warning(s) - meta will work but with reduced functionality",
+                warnings
+            );
+        }
+    }
+
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(checks: &[Check]) -> Vec<&str> {
+        checks.iter().map(|c| c.id).collect()
+    }
+
+    // === Issue #3: bacon config validation ===
+
+    #[test]
+    fn test_validate_bacon_config_warns_when_no_bacon_toml() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_path = temp_dir.path().to_string_lossy().to_string();
+
+        let warnings = validate_bacon_config("api", &project_path, "run-long");
+        assert_eq!(ids(&warnings), ["bacon.config-not-found"]);
+        assert!(warnings[0].message.contains("bacon.toml not found"));
+        assert_eq!(warnings[0].subject.as_deref(), Some("api"));
+    }
+
+    #[test]
+    fn test_validate_bacon_config_no_warning_when_bacon_toml_exists() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+
+        // Create bacon.toml with the job
+        fs::write(
+            project_path.join("bacon.toml"),
+            r#"[jobs.run-long]
+command = ["cargo", "run"]
+"#,
+        )
+        .unwrap();
+
+        let warnings = validate_bacon_config("api", &project_path.to_string_lossy(), "run-long");
+        assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+    }
+
+    #[test]
+    fn test_validate_bacon_config_builtin_jobs_no_warning() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+
+        // Create empty bacon.toml (builtin jobs don't need config)
+        fs::write(project_path.join("bacon.toml"), "").unwrap();
+
+        // "run-long" is a builtin job
+        let warnings = validate_bacon_config("api", &project_path.to_string_lossy(), "run-long");
+        assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+    }
+
+    // === mcp_json_references_docker ===
+
+    #[test]
+    fn test_mcp_references_docker_true_when_command_is_docker() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join(".mcp.json");
+        fs::write(
+            &path,
+            r#"{"mcpServers":{"mcp-log-server":{"command":"docker","args":[]}}}"#,
+        )
+        .unwrap();
+        assert!(mcp_json_references_docker(&path));
+    }
+
+    #[test]
+    fn test_mcp_references_docker_false_when_no_docker_entry() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join(".mcp.json");
+        fs::write(
+            &path,
+            r#"{"mcpServers":{"other":{"command":"npx","args":[]}}}"#,
+        )
+        .unwrap();
+        assert!(!mcp_json_references_docker(&path));
+    }
+
+    #[test]
+    fn test_mcp_references_docker_false_when_file_missing() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        assert!(!mcp_json_references_docker(&tmp.path().join("absent.json")));
+    }
+
+    #[test]
+    fn test_mcp_references_docker_false_when_malformed() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join(".mcp.json");
+        fs::write(&path, "not json").unwrap();
+        assert!(!mcp_json_references_docker(&path));
+    }
+
+    #[test]
+    fn test_validate_bacon_config_warns_when_job_missing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+        fs::write(project_path.join("bacon.toml"), "[jobs.other]\n").unwrap();
+
+        let warnings = validate_bacon_config("api", &project_path.to_string_lossy(), "serve");
+        assert_eq!(ids(&warnings), ["bacon.job-not-found"]);
+        assert!(warnings[0].fix.as_deref().unwrap().contains("[jobs.serve]"));
+    }
+
+    #[test]
+    fn test_validate_bacon_config_parses_bacon_toml() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_path = temp_dir.path();
+
+        // A job defined in an inline `[jobs]` table, and one only in a comment
+        fs::write(
+            project_path.join("bacon.toml"),
+            "# [jobs.lint]\n[jobs]\nserve = { command = [\"cargo\", \"run\"] }\n",
+        )
+        .unwrap();
+        let path = project_path.to_string_lossy();
+        assert!(validate_bacon_config("api", &path, "serve").is_empty());
+        assert_eq!(ids(&validate_bacon_config("api", &path, "lint")), ["bacon.job-not-found"]);
+
+        fs::write(project_path.join("bacon.toml"), "[jobs.serve\n").unwrap();
+        let checks = validate_bacon_config("api", &path, "serve");
+        assert_eq!(ids(&checks), ["bacon.config-invalid"]);
+        assert_eq!(checks[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_check_validation_ids() {
+        let config = crate::config::parse(
+            r#"
+version = "1"
+[workspace]
+name = "Test"
+root = "."
+[tools.turborepo]
+enabled = true
+command = "turbo"
+[projects.web]
+type = "next"
+path = "missing/web"
+[projects.web.tasks]
+dev = { tool = "turborepo", command = "dev", port = 3000 }
+lint = { tool = "turborepo", command = "run 'lint" }
+[projects.docs]
+type = "next"
+path = "missing/docs"
+[projects.docs.tasks]
+dev = { tool = "turborepo", command = "run dev --filter=docs", port = 3000 }
+"#,
+        )
+        .unwrap();
+        let checks = check_validation(&config);
+        assert_eq!(
+            ids(&checks),
+            ["task.command-invalid", "dev.port-conflict", "turbo.missing-run", "turbo.missing-filter"]
+        );
+        assert_eq!(checks[0].subject.as_deref(), Some("web:lint"));
+        assert_eq!(checks[1].subject.as_deref(), Some("docs, web"));
+        assert!(checks.iter().all(|c| c.fix.is_some()));
+    }
+
+    #[test]
+    fn test_check_serializes_with_stable_fields() {
+        let check = Check::new("tool.not-found", Category::Tools, Severity::Error, Some("npm"), "npm")
+            .with_fix("Install npm");
+        assert_eq!(
+            serde_json::to_value(&check).unwrap(),
+            serde_json::json!({
+                "id": "tool.not-found",
+                "category": "tools",
+                "severity": "error",
+                "subject": "npm",
+                "message": "npm",
+                "fix": "Install npm",
+            })
+        );
+    }
+
+    fn args(line: &str) -> Vec<String> {
+        shell_words::split(line).unwrap()
+    }
+
+    #[test]
+    fn test_flag_values_and_filter_package() {
+        let line = args("run dev -F web --filter=...@org/ui^... -- --filter=x");
+        let values = flag_values(&line, &["--filter", "-F"]);
+        assert_eq!(values, ["web", "...@org/ui^..."]);
+        assert_eq!(flag_values(&args("run -pshared --bin"), &["--package", "-p"]), ["shared"]);
+        assert!(flag_values(&args("run --binary"), &["--bin"]).is_empty());
+
+        assert_eq!(filter_package("...@org/ui^..."), Some("@org/ui"));
+        assert_eq!(filter_package("web..."), Some("web"));
+        assert_eq!(filter_package("./apps/web"), None);
+        assert_eq!(filter_package("!docs"), None);
+        assert_eq!(filter_package("[HEAD^1]"), None);
+    }
+
+    #[test]
+    fn test_check_turbo_task_resolves_filters_tasks_and_scripts() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("package.json"), r#"{"workspaces": ["apps/*"]}"#).unwrap();
+        fs::write(root.join("turbo.json"), r#"{"tasks": {"dev": {}, "web#lint": {}}}"#).unwrap();
+        fs::create_dir_all(root.join("apps/web")).unwrap();
+        fs::write(
+            root.join("apps/web/package.json"),
+            r#"{"name": "web", "scripts": {"dev": "next dev", "lint": "next lint"}}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("apps/docs")).unwrap();
+        fs::write(root.join("apps/docs/package.json"), r#"{"name": "docs"}"#).unwrap();
+        let path = root.join("apps/web").to_string_lossy().to_string();
+
+        let check = |line: &str| check_turbo_task("web:dev", &path, &args(line));
+        assert!(check("run dev --filter=web").is_empty());
+        assert!(check("run lint --filter=web...").is_empty());
+        assert!(check("run dev --filter=./apps/web --filter=w*").is_empty());
+        assert_eq!(ids(&check("run dev --filter=wbe")), ["turbo.filter-unknown"]);
+        assert_eq!(ids(&check("run dev --filter=docs")), ["turbo.script-missing"]);
+        assert_eq!(
+            ids(&check("run start --filter=web")),
+            ["turbo.task-undefined", "turbo.script-missing"]
+        );
+        assert!(check("dev").is_empty());
+
+        let outside = tempfile::TempDir::new().unwrap();
+        let outside = outside.path().to_string_lossy();
+        let checks = check_turbo_task("web:dev", &outside, &args("run dev"));
+        assert_eq!(ids(&checks), ["turbo.config-not-found"]);
+    }
+
+    #[test]
+    fn test_check_cargo_task_resolves_packages_and_bins() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let workspace = "[workspace]\nmembers = [\"api\", \"shared\"]\n";
+        fs::write(root.join("Cargo.toml"), workspace).unwrap();
+        fs::create_dir_all(root.join("api/src")).unwrap();
+        fs::write(root.join("api/Cargo.toml"), "[package]\nname = \"api\"\n").unwrap();
+        fs::write(root.join("api/src/main.rs"), "").unwrap();
+        fs::create_dir_all(root.join("shared")).unwrap();
+        fs::write(root.join("shared/Cargo.toml"), "[package]\nname = \"shared\"\n").unwrap();
+        let path = root.join("api").to_string_lossy().to_string();
+
+        let check = |line: &str| check_cargo_task("api:dev", &path, &args(line));
+        assert!(check("run --bin api").is_empty());
+        assert!(check("test -p shared").is_empty());
+        assert_eq!(ids(&check("run --bin server")), ["cargo.bin-not-found"]);
+        assert_eq!(ids(&check("run -p apii --bin api")), ["cargo.package-not-found"]);
+        let checks = check("run -p shared --bin api");
+        assert_eq!(ids(&checks), ["cargo.bin-not-found"]);
+        assert!(checks[0].fix.as_deref().unwrap().contains("No binary targets"));
+    }
+}
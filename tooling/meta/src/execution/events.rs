@@ -0,0 +1,200 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use regex::Regex;
+
+const DEV_LOG: &str = ".meta/logs/dev.log";
+
+/// Append `[TIMESTAMP] [PROJECT] EVENT: message` to dev.log
+pub fn log_event(project: &str, event: &str) {
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(DEV_LOG) {
+        let _ = writeln!(file, "[{}] [{}] {}", utc_timestamp(), project, event);
+    }
+}
+
+/// Current UTC time as `YYYY-MM-DDTHH:MM:SS`, like `date -u +%Y-%m-%dT%H:%M:%S`
+fn utc_timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format_timestamp(secs)
+}
+
+/// Seconds since the epoch as `YYYY-MM-DDTHH:MM:SS` (UTC)
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let time = secs % 86_400;
+
+    // Days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+/// The first `YYYY-MM-DD[T ]HH:MM:SS` timestamp in `text`, as seconds since
+/// the epoch. A `Z` or `+HH:MM` offset is honored; timestamps without one are
+/// taken as UTC, like the ones in dev.log.
+pub fn parse_timestamp(text: &str) -> Option<u64> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(\d{4})-(\d{2})-(\d{2})[T ](\d{2}):(\d{2}):(\d{2})(?:[.,]\d+)?(Z|[+-]\d{2}:?\d{2})?",
+        )
+        .expect("valid timestamp pattern")
+    });
+    let caps = pattern.captures(text)?;
+    let field = |i: usize| caps[i].parse::<i64>().ok();
+    let (year, month, day) = (field(1)?, field(2)?, field(3)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Civil date to days since the epoch, the inverse of format_timestamp
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let mut secs = days * 86_400 + field(4)? * 3_600 + field(5)? * 60 + field(6)?;
+    if let Some(offset) = caps.get(7).map(|m| m.as_str()).filter(|o| *o != "Z") {
+        let digits = offset[1..].replace(':', "");
+        let minutes = digits[..2].parse::<i64>().ok()? * 60 + digits[2..].parse::<i64>().ok()?;
+        secs -= if offset.starts_with('-') { -minutes } else { minutes } * 60;
+    }
+    u64::try_from(secs).ok()
+}
+
+/// Automatic restarts of a project in its current run, from dev.log
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RestartStats {
+    pub count: u32,
+    /// Message of the most recent RESTART event, e.g. `Restarting after exit
+    /// code 1 (attempt 2/5, backoff 2s)`
+    pub last: Option<String>,
+}
+
+/// Count automatic RESTART events for `project` since it was last started
+/// fresh (a START not directly preceded by a RESTART)
+pub fn restart_stats(dev_log: &str, project: &str) -> RestartStats {
+    let tag = format!("] [{}] ", project);
+    let mut stats = RestartStats::default();
+    let mut after_restart = false;
+    for line in dev_log.lines() {
+        let Some((_, event)) = line.split_once(&tag) else {
+            continue;
+        };
+        if let Some(message) = event.strip_prefix("RESTART: ") {
+            if message.starts_with("Manual") {
+                stats = RestartStats::default();
+            } else {
+                stats.count += 1;
+                stats.last = Some(message.to_string());
+            }
+            after_restart = true;
+        } else {
+            if event.starts_with("START: ") && !after_restart {
+                stats = RestartStats::default();
+            }
+            after_restart = false;
+        }
+    }
+    stats
+}
+
+/// Whether the latest event for `project` is STOPPED, i.e. it hit its
+/// restart limit or was stopped with `meta stop` and its pane is idle
+pub fn is_stopped(dev_log: &str, project: &str) -> bool {
+    let tag = format!("] [{}] ", project);
+    dev_log
+        .lines()
+        .rev()
+        .find_map(|line| line.split_once(&tag).map(|(_, event)| event))
+        .is_some_and(|event| event.starts_with("STOPPED: "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00");
+        assert_eq!(format_timestamp(1_733_659_338), "2024-12-08T12:02:18");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00");
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("[2024-12-08T12:02:18] [api] START"), Some(1_733_659_338));
+        assert_eq!(parse_timestamp("2000-02-29 00:00:00.123 INFO up"), Some(951_782_400));
+        assert_eq!(parse_timestamp("at 2024-12-08T12:02:18Z"), Some(1_733_659_338));
+        assert_eq!(parse_timestamp("2024-12-08T14:02:18+02:00"), Some(1_733_659_338));
+        assert_eq!(parse_timestamp("2024-12-08T07:02:18-0500"), Some(1_733_659_338));
+        assert_eq!(parse_timestamp("listening on :3000"), None);
+        assert_eq!(parse_timestamp("2024-13-01T00:00:00"), None);
+    }
+
+    #[test]
+    fn test_restart_stats_counts_current_run() {
+        let log = "\
+[2025-12-08T12:00:00] [api] START: Process started (pid=1)
+[2025-12-08T12:00:01] [api] EXIT: Process exited with code 1
+[2025-12-08T12:00:01] [api] RESTART: Restarting after exit code 1 (attempt 1/5, backoff 1s)
+[2025-12-08T12:00:02] [api] START: Process started (pid=1)
+[2025-12-08T12:00:09] [web] START: Process started (pid=2)
+[2025-12-08T12:00:10] [api] EXIT: Process exited with code 2
+[2025-12-08T12:00:10] [api] RESTART: Restarting after exit code 2 (attempt 2/5, backoff 2s)
+[2025-12-08T12:00:12] [api] START: Process started (pid=1)
+";
+        assert_eq!(
+            restart_stats(log, "api"),
+            RestartStats {
+                count: 2,
+                last: Some("Restarting after exit code 2 (attempt 2/5, backoff 2s)".to_string()),
+            }
+        );
+        assert_eq!(restart_stats(log, "web"), RestartStats::default());
+
+        // A fresh `meta dev` resets the count
+        let log = format!("{}[2025-12-08T13:00:00] [api] START: Process started (pid=3)\n", log);
+        assert_eq!(restart_stats(&log, "api"), RestartStats::default());
+    }
+
+    #[test]
+    fn test_is_stopped_uses_latest_event() {
+        let log = "\
+[2025-12-08T12:00:00] [api] START: Process started (pid=1)
+[2025-12-08T12:00:05] [api] STOPPED: Stopped via meta stop
+[2025-12-08T12:00:06] [web] START: Process started (pid=2)
+";
+        assert!(is_stopped(log, "api"));
+        assert!(!is_stopped(log, "web"));
+        assert!(!is_stopped(log, "worker"));
+
+        let log = format!("{}[2025-12-08T12:01:00] [api] START: Process started (pid=3)\n", log);
+        assert!(!is_stopped(&log, "api"));
+    }
+}
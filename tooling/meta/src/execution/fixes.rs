@@ -0,0 +1,496 @@
+//! `meta doctor --fix`: edits to meta.toml, bacon.toml and .mcp.json for
+//! problems with an obvious remedy, shown as diffs and written once
+//! confirmed. meta.toml and bacon.toml are edited in place with toml_edit,
+//! so their comments and formatting survive.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use similar::TextDiff;
+use toml_edit::{DocumentMut, Item, Table};
+
+use super::doctor::{Check, BUILTIN_BACON_JOBS};
+use crate::config::{self, Config, McpServer, ProjectConfig, TaskCommand};
+
+const MCP_JSON: &str = ".mcp.json";
+/// Directories never searched for a project that moved
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build"];
+/// How far below the workspace root a project that moved is looked for
+const SEARCH_DEPTH: usize = 4;
+
+/// A file `--fix` creates or changes
+#[derive(Debug, PartialEq)]
+pub struct FileEdit {
+    pub path: PathBuf,
+    /// Current contents, or None if the file is created
+    pub before: Option<String>,
+    pub after: String,
+}
+
+/// Show the edits that would resolve `checks`, then write them once
+/// confirmed (or right away with `yes`). Returns whether anything was
+/// written.
+pub fn fix(config: &Config, checks: &[Check], yes: bool) -> Result<bool> {
+    let edits = plan(config, checks)?;
+    if edits.is_empty() {
+        println!("🔧 Nothing that --fix can change automatically.\n");
+        return Ok(false);
+    }
+
+    println!("🔧 Proposed fixes:\n");
+    let color = io::stdout().is_terminal();
+    for edit in &edits {
+        print_diff(edit, color);
+    }
+
+    if !yes {
+        if !io::stdin().is_terminal() {
+            println!("Re-run with --yes to apply these changes.\n");
+            return Ok(false);
+        }
+        print!("Apply {} change(s)? [y/N] ", edits.len());
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("No changes made.\n");
+            return Ok(false);
+        }
+    }
+
+    for edit in &edits {
+        fs::write(&edit.path, &edit.after)
+            .with_context(|| format!("Failed to write {}", edit.path.display()))?;
+        let action = if edit.before.is_some() { "Updated" } else { "Created" };
+        println!("  ✓ {} {}", action, edit.path.display());
+    }
+    println!();
+    Ok(true)
+}
+
+/// The edits that would resolve the fixable checks, one per file
+pub fn plan(config: &Config, checks: &[Check]) -> Result<Vec<FileEdit>> {
+    let mut edits = Vec::new();
+
+    // Shown relative to the workspace root, where meta.toml usually is
+    let path = config::config_path();
+    let path = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .unwrap_or(path);
+    let before = fs::read_to_string(&path)?;
+    let after = fix_meta_toml(config, checks, &before, Path::new("."))?;
+    if after != before {
+        edits.push(FileEdit {
+            path,
+            before: Some(before),
+            after,
+        });
+    }
+
+    edits.extend(fix_bacon(config, checks)?);
+
+    if let Ok(before) = fs::read_to_string(MCP_JSON) {
+        if let Some(after) = fix_mcp_json(checks, &before)? {
+            edits.push(FileEdit {
+                path: MCP_JSON.into(),
+                before: Some(before),
+                after,
+            });
+        }
+    }
+    Ok(edits)
+}
+
+/// meta.toml with turbo dev commands completed and moved project paths
+/// updated. `root` is where moved projects are looked for.
+fn fix_meta_toml(config: &Config, checks: &[Check], contents: &str, root: &Path) -> Result<String> {
+    let mut doc: DocumentMut = contents.parse().context("Failed to parse meta.toml")?;
+    for check in checks {
+        let Some((name, project)) = check
+            .subject
+            .as_deref()
+            .and_then(|name| config.projects.get_key_value(name))
+        else {
+            continue;
+        };
+        match check.id {
+            "turbo.missing-run" | "turbo.missing-filter" => {
+                let Some(dev_task) = project.tasks.get("dev") else {
+                    continue;
+                };
+                let command = turbo_command(&dev_task.command, &package_name(name, project));
+                set(&mut doc, &["projects", name, "tasks", "dev", "command"], command);
+            }
+            "project.path-not-found" => {
+                if let Some(path) = find_moved(root, name, &project.path) {
+                    set(&mut doc, &["projects", name, "path"], path.into());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// A turbo dev command with `run` in front and a `--filter=` for `package`
+fn turbo_command(command: &TaskCommand, package: &str) -> toml_edit::Value {
+    let filter = format!("--filter={}", package);
+    match command {
+        TaskCommand::Line(line) => {
+            let mut line = line.trim().to_string();
+            if !line.starts_with("run ") {
+                line = format!("run {}", line);
+            }
+            if !line.contains("--filter=") {
+                line = format!("{} {}", line, filter);
+            }
+            line.into()
+        }
+        TaskCommand::Args(args) => {
+            let mut args = args.clone();
+            if args.first().map(String::as_str) != Some("run") {
+                args.insert(0, "run".to_string());
+            }
+            if !args.iter().any(|arg| arg.starts_with("--filter=")) {
+                args.push(filter);
+            }
+            args.iter().collect::<toml_edit::Array>().into()
+        }
+    }
+}
+
+/// The workspace package name turbo knows a project by: `name` in its
+/// package.json, or the project name
+fn package_name(name: &str, project: &ProjectConfig) -> String {
+    manifest_name(Path::new(&project.path)).unwrap_or_else(|| name.to_string())
+}
+
+/// `name` from a directory's package.json or Cargo.toml
+fn manifest_name(dir: &Path) -> Option<String> {
+    if let Ok(contents) = fs::read_to_string(dir.join("package.json")) {
+        let package: serde_json::Value = serde_json::from_str(&contents).ok()?;
+        return package["name"].as_str().map(str::to_string);
+    }
+    let contents = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    let manifest: toml::Value = toml::from_str(&contents).ok()?;
+    manifest.get("package")?.get("name")?.as_str().map(str::to_string)
+}
+
+/// Replace the value at `keys`, keeping the whitespace and comments around
+/// it. Nothing is changed if a key is missing.
+fn set(doc: &mut DocumentMut, keys: &[&str], mut value: toml_edit::Value) {
+    let mut item = doc.as_item_mut();
+    for key in keys {
+        let Some(next) = item.get_mut(key) else {
+            return;
+        };
+        item = next;
+    }
+    if let Some(old) = item.as_value() {
+        *value.decor_mut() = old.decor().clone();
+    }
+    *item = Item::Value(value);
+}
+
+/// Where a project whose path no longer exists went: the only directory
+/// below `root` with the same name as its old path, or whose package.json or
+/// Cargo.toml has the project's name
+fn find_moved(root: &Path, name: &str, old_path: &str) -> Option<String> {
+    let old_name = Path::new(old_path).file_name()?;
+    let mut found = Vec::new();
+    find_dirs(root, 0, &mut found, &|dir: &Path| {
+        dir.file_name() == Some(old_name) || manifest_name(dir).as_deref() == Some(name)
+    });
+    match found.as_slice() {
+        [only] => Some(only.strip_prefix(root).unwrap_or(only).to_string_lossy().into_owned()),
+        _ => None,
+    }
+}
+
+fn find_dirs(dir: &Path, depth: usize, found: &mut Vec<PathBuf>, matches: &dyn Fn(&Path) -> bool) {
+    if depth >= SEARCH_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref())
+        })
+        .collect();
+    dirs.sort();
+    for dir in dirs {
+        if matches(&dir) {
+            found.push(dir.clone());
+        }
+        find_dirs(&dir, depth + 1, found, matches);
+    }
+}
+
+/// bacon.toml files created, or given the job a bacon dev task runs
+fn fix_bacon(config: &Config, checks: &[Check]) -> Result<Vec<FileEdit>> {
+    let mut edits: BTreeMap<PathBuf, FileEdit> = BTreeMap::new();
+    for check in checks {
+        if !matches!(check.id, "bacon.config-not-found" | "bacon.job-not-found") {
+            continue;
+        }
+        let Some(project) = check.subject.as_deref().and_then(|name| config.projects.get(name))
+        else {
+            continue;
+        };
+        let Some(dev_task) = project.tasks.get("dev") else {
+            continue;
+        };
+        let command = dev_task.command.to_string();
+        let job = command.split_whitespace().next().unwrap_or("run-long");
+
+        let path = Path::new(&project.path).join("bacon.toml");
+        let before = fs::read_to_string(&path).ok();
+        let current = match (edits.get(&path), &before) {
+            (Some(edit), _) => edit.after.clone(),
+            (None, Some(before)) => before.clone(),
+            (None, None) => new_bacon_toml(job),
+        };
+        let Some(after) = with_bacon_job(&current, job)? else {
+            continue;
+        };
+        edits
+            .entry(path.clone())
+            .or_insert(FileEdit {
+                path,
+                before,
+                after: String::new(),
+            })
+            .after = after;
+    }
+    Ok(edits.into_values().filter(|edit| edit.before.as_ref() != Some(&edit.after)).collect())
+}
+
+fn new_bacon_toml(job: &str) -> String {
+    format!(
+        "# bacon configuration: https://dystroy.org/bacon/config/\n\ndefault_job = \"{}\"\n",
+        job
+    )
+}
+
+/// bacon.toml with a `[jobs.<job>]` that runs the crate like bacon's
+/// `run-long`, unless `job` is built in or already defined
+fn with_bacon_job(contents: &str, job: &str) -> Result<Option<String>> {
+    let mut doc: DocumentMut = contents.parse().context("Failed to parse bacon.toml")?;
+    if !BUILTIN_BACON_JOBS.contains(&job) {
+        let jobs = doc.entry("jobs").or_insert_with(|| {
+            let mut jobs = Table::new();
+            jobs.set_implicit(true);
+            Item::Table(jobs)
+        });
+        // A `jobs = { ... }` inline table is left for the user to edit
+        let Some(jobs) = jobs.as_table_mut() else {
+            return Ok(None);
+        };
+        if !jobs.contains_key(job) {
+            let mut table = Table::new();
+            table.decor_mut().set_prefix("\n");
+            table["command"] = toml_edit::value(toml_edit::Array::from_iter(["cargo", "run"]));
+            table["need_stdout"] = toml_edit::value(true);
+            table["allow_warnings"] = toml_edit::value(true);
+            table["background"] = toml_edit::value(false);
+            table["on_change_strategy"] = toml_edit::value("kill_then_restart");
+            jobs.insert(job, Item::Table(table));
+        }
+    }
+    Ok(Some(doc.to_string()))
+}
+
+/// .mcp.json with a Docker mcp-log-server entry replaced by `meta mcp` when
+/// docker is missing, or brought up to date when it is stale
+fn fix_mcp_json(checks: &[Check], contents: &str) -> Result<Option<String>> {
+    let has = |id: &str| checks.iter().any(|check| check.id == id);
+    let (replace, refresh) = (has("mcp.docker-not-found"), has("mcp.entry-stale"));
+    if !replace && !refresh {
+        return Ok(None);
+    }
+
+    let mut root: serde_json::Value =
+        serde_json::from_str(contents).context("Failed to parse .mcp.json")?;
+    let Some(servers) = root.get_mut("mcpServers").and_then(|s| s.as_object_mut()) else {
+        return Ok(None);
+    };
+    let docker = McpServer::Docker.entry_name();
+    let is_docker = servers.get(docker).is_some_and(|entry| entry["command"] == "docker");
+    if replace && is_docker {
+        servers.remove(docker);
+        servers
+            .entry(McpServer::Meta.entry_name())
+            .or_insert_with(|| McpServer::Meta.entry());
+    } else if refresh {
+        servers.insert(docker.into(), McpServer::Docker.entry());
+    } else {
+        return Ok(None);
+    }
+    let mut after = serde_json::to_string_pretty(&root)?;
+    if contents.ends_with('\n') {
+        after.push('\n');
+    }
+    Ok(Some(after))
+}
+
+fn print_diff(edit: &FileEdit, color: bool) {
+    let before = edit.before.as_deref().unwrap_or("");
+    let path = edit.path.display().to_string();
+    let old_header = match edit.before {
+        Some(_) => format!("a/{}", path),
+        None => "/dev/null".to_string(),
+    };
+    let diff = TextDiff::from_lines(before, &edit.after);
+    let unified = diff
+        .unified_diff()
+        .context_radius(3)
+        .header(&old_header, &format!("b/{}", path))
+        .to_string();
+    for line in unified.lines() {
+        match line.chars().next() {
+            Some('+') if color => println!("\x1b[32m{}\x1b[0m", line),
+            Some('-') if color => println!("\x1b[31m{}\x1b[0m", line),
+            Some('@') if color => println!("\x1b[36m{}\x1b[0m", line),
+            _ => println!("{}", line),
+        }
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::doctor::{Category, Severity};
+
+    fn check(id: &'static str, subject: &str) -> Check {
+        Check {
+            id,
+            category: Category::Validation,
+            severity: Severity::Warning,
+            subject: Some(subject.to_string()),
+            message: String::new(),
+            fix: None,
+        }
+    }
+
+    #[test]
+    fn test_turbo_command_adds_run_and_filter() {
+        let line = turbo_command(&TaskCommand::Line("dev".into()), "@acme/web");
+        assert_eq!(line.as_str(), Some("run dev --filter=@acme/web"));
+
+        let line = turbo_command(&TaskCommand::Line("run dev --filter=web".into()), "web");
+        assert_eq!(line.as_str(), Some("run dev --filter=web"));
+
+        let args = turbo_command(&TaskCommand::Args(vec!["dev".into()]), "web");
+        assert_eq!(args.to_string(), r#"["run", "dev", "--filter=web"]"#);
+    }
+
+    #[test]
+    fn test_fix_meta_toml_keeps_comments_and_formatting() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("apps/api")).unwrap();
+        let contents = r#"# Workspace for the test
+version = "1"
+[workspace]
+name = "Test"
+root = "."
+
+[tools.turborepo]
+enabled = true
+command = "turbo"
+
+# The frontend
+[projects.web]
+type = "next"
+path = "web"   # moved soon
+[projects.web.tasks]
+dev = { tool = "turborepo", command = "dev", port = 3000 } # keep me
+
+[projects.api]
+type = "rust"
+path = "services/api" # old location
+[projects.api.tasks]
+dev = { tool = "turborepo", command = "run dev --filter=api" }
+"#;
+        let config = crate::config::parse(contents).unwrap();
+        let checks = [
+            check("turbo.missing-run", "web"),
+            check("turbo.missing-filter", "web"),
+            check("project.path-not-found", "api"),
+        ];
+        let fixed = fix_meta_toml(&config, &checks, contents, temp_dir.path()).unwrap();
+        let expected = contents
+            .replace(
+                r#"command = "dev", port"#,
+                r#"command = "run dev --filter=web", port"#,
+            )
+            .replace(r#""services/api" # old"#, r#""apps/api" # old"#);
+        assert_eq!(fixed, expected);
+
+        // Nothing to fix leaves the file as it was
+        assert_eq!(fix_meta_toml(&config, &[], contents, temp_dir.path()).unwrap(), contents);
+    }
+
+    #[test]
+    fn test_find_moved_needs_a_unique_match() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("apps/api")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("node_modules/api")).unwrap();
+        assert_eq!(find_moved(temp_dir.path(), "api", "api").as_deref(), Some("apps/api"));
+
+        fs::create_dir_all(temp_dir.path().join("services/api")).unwrap();
+        assert_eq!(find_moved(temp_dir.path(), "api", "api"), None);
+    }
+
+    #[test]
+    fn test_with_bacon_job_adds_missing_job() {
+        let contents = "# bacon config\ndefault_job = \"serve\"\n";
+        let fixed = with_bacon_job(contents, "serve").unwrap().unwrap();
+        assert!(fixed.starts_with(contents));
+        let doc: toml::Value = toml::from_str(&fixed).unwrap();
+        let job = &doc["jobs"]["serve"];
+        assert_eq!(job["command"], toml::Value::from(vec!["cargo", "run"]));
+        assert_eq!(job["on_change_strategy"].as_str(), Some("kill_then_restart"));
+
+        // Defined and built-in jobs are left alone
+        assert_eq!(with_bacon_job(&fixed, "serve").unwrap().unwrap(), fixed);
+        assert_eq!(with_bacon_job(contents, "run-long").unwrap().unwrap(), contents);
+    }
+
+    #[test]
+    fn test_fix_mcp_json_replaces_or_refreshes_docker_entry() {
+        let contents = r#"{
+  "mcpServers": {
+    "mcp-log-server": { "command": "docker", "args": ["run", "old"] },
+    "other": { "command": "other" }
+  }
+}
+"#;
+        assert_eq!(fix_mcp_json(&[], contents).unwrap(), None);
+
+        let checks = [check("mcp.docker-not-found", "")];
+        let fixed = fix_mcp_json(&checks, contents).unwrap().unwrap();
+        let root: serde_json::Value = serde_json::from_str(&fixed).unwrap();
+        assert!(root["mcpServers"].get("mcp-log-server").is_none());
+        assert_eq!(root["mcpServers"]["meta"], McpServer::Meta.entry());
+        assert_eq!(root["mcpServers"]["other"]["command"], "other");
+        assert!(fixed.ends_with("}\n"));
+
+        let checks = [check("mcp.entry-stale", "mcp-log-server")];
+        let fixed = fix_mcp_json(&checks, contents).unwrap().unwrap();
+        let root: serde_json::Value = serde_json::from_str(&fixed).unwrap();
+        assert_eq!(root["mcpServers"]["mcp-log-server"], McpServer::Docker.entry());
+    }
+}
@@ -0,0 +1,340 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use anyhow::Result;
+
+use super::workspace::project_dependencies;
+use crate::config::Config;
+
+/// A single task in a single project, written `project:task`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskId {
+    pub project: String,
+    pub task: String,
+}
+
+impl TaskId {
+    pub fn new(project: &str, task: &str) -> Self {
+        Self {
+            project: project.to_string(),
+            task: task.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.project, self.task)
+    }
+}
+
+/// Dependency graph of tasks, built from `depends_on` in meta.toml.
+///
+/// Uses ordered maps throughout so that the execution order is stable across
+/// runs instead of depending on `HashMap` iteration order.
+#[derive(Debug, Default)]
+pub struct TaskGraph {
+    /// task → tasks that must complete before it
+    deps: BTreeMap<TaskId, BTreeSet<TaskId>>,
+}
+
+impl TaskGraph {
+    /// Build the graph for running `task_name` in each of `projects`,
+    /// including every task reachable through `depends_on`.
+    ///
+    /// Projects that don't define `task_name` are not part of the graph.
+    pub fn build(config: &Config, task_name: &str, projects: &[String]) -> Result<Self> {
+        let project_deps = project_dependencies(config);
+        let mut graph = TaskGraph::default();
+        let mut pending: Vec<TaskId> = projects
+            .iter()
+            .filter(|name| {
+                config
+                    .projects
+                    .get(name.as_str())
+                    .is_some_and(|p| p.tasks.contains_key(task_name))
+            })
+            .map(|name| TaskId::new(name, task_name))
+            .collect();
+
+        while let Some(id) = pending.pop() {
+            if graph.deps.contains_key(&id) {
+                continue;
+            }
+            let deps = resolve_dependencies(config, &project_deps, &id)?;
+            pending.extend(deps.iter().cloned());
+            graph.deps.insert(id, deps);
+        }
+
+        Ok(graph)
+    }
+
+    /// Tasks that must complete before `id`
+    pub fn dependencies(&self, id: &TaskId) -> impl Iterator<Item = &TaskId> {
+        self.deps.get(id).into_iter().flatten()
+    }
+
+    /// Order tasks so that every task comes after its dependencies.
+    ///
+    /// Ties are broken alphabetically by `project:task`. Fails with the
+    /// offending chain if the graph contains a cycle.
+    pub fn topological_order(&self) -> Result<Vec<TaskId>> {
+        let mut remaining: BTreeMap<&TaskId, usize> =
+            self.deps.iter().map(|(id, deps)| (id, deps.len())).collect();
+        let mut dependents: BTreeMap<&TaskId, Vec<&TaskId>> = BTreeMap::new();
+        for (id, deps) in &self.deps {
+            for dep in deps {
+                dependents.entry(dep).or_default().push(id);
+            }
+        }
+
+        let mut ready: BTreeSet<&TaskId> = remaining
+            .iter()
+            .filter(|(_, &count)| count == 0)
+            .map(|(&id, _)| id)
+            .collect();
+        let mut order = Vec::with_capacity(self.deps.len());
+
+        while let Some(id) = ready.pop_first() {
+            order.push(id.clone());
+            for &dependent in dependents.get(id).into_iter().flatten() {
+                let count = remaining.get_mut(dependent).expect("dependent is in graph");
+                *count -= 1;
+                if *count == 0 {
+                    ready.insert(dependent);
+                }
+            }
+        }
+
+        if order.len() < self.deps.len() {
+            let cycle = self
+                .find_cycle()
+                .map(|ids| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(" → "))
+                .unwrap_or_else(|| "unknown".to_string());
+            anyhow::bail!("Dependency cycle detected: {}", cycle);
+        }
+
+        Ok(order)
+    }
+
+    /// Find one cycle, returned as a path that starts and ends on the same task
+    fn find_cycle(&self) -> Option<Vec<TaskId>> {
+        fn visit<'a>(
+            graph: &'a TaskGraph,
+            id: &'a TaskId,
+            stack: &mut Vec<&'a TaskId>,
+            done: &mut BTreeSet<&'a TaskId>,
+        ) -> Option<Vec<TaskId>> {
+            if let Some(pos) = stack.iter().position(|&s| s == id) {
+                let mut cycle: Vec<TaskId> = stack[pos..].iter().map(|&s| s.clone()).collect();
+                cycle.push(id.clone());
+                return Some(cycle);
+            }
+            if done.contains(id) {
+                return None;
+            }
+            stack.push(id);
+            for dep in graph.dependencies(id) {
+                if let Some(cycle) = visit(graph, dep, stack, done) {
+                    return Some(cycle);
+                }
+            }
+            stack.pop();
+            done.insert(id);
+            None
+        }
+
+        let mut done = BTreeSet::new();
+        for id in self.deps.keys() {
+            if let Some(cycle) = visit(self, id, &mut Vec::new(), &mut done) {
+                return Some(cycle);
+            }
+        }
+        None
+    }
+}
+
+/// Resolve the `depends_on` entries of a task into concrete task ids.
+///
+/// `^task` expands over `project_deps`, which includes dependencies inferred
+/// from Cargo.toml and package.json as well as the explicit `depends_on`.
+fn resolve_dependencies(
+    config: &Config,
+    project_deps: &BTreeMap<String, BTreeSet<String>>,
+    id: &TaskId,
+) -> Result<BTreeSet<TaskId>> {
+    let project = config
+        .projects
+        .get(&id.project)
+        .ok_or_else(|| anyhow::anyhow!("Project not found: {}", id.project))?;
+    let task = project
+        .tasks
+        .get(&id.task)
+        .ok_or_else(|| anyhow::anyhow!("Task not found: {}", id))?;
+
+    let mut deps = BTreeSet::new();
+    for spec in &task.depends_on {
+        if let Some(dep_task) = spec.strip_prefix('^') {
+            // Run the task in each upstream project that defines it
+            for dep_project in project_deps.get(&id.project).into_iter().flatten() {
+                let upstream = config.projects.get(dep_project).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Project '{}' depends on unknown project '{}'",
+                        id.project,
+                        dep_project
+                    )
+                })?;
+                if upstream.tasks.contains_key(dep_task) {
+                    deps.insert(TaskId::new(dep_project, dep_task));
+                }
+            }
+        } else {
+            let dep = match spec.split_once(':') {
+                Some((dep_project, dep_task)) => TaskId::new(dep_project, dep_task),
+                None => TaskId::new(&id.project, spec),
+            };
+            let exists = config
+                .projects
+                .get(&dep.project)
+                .is_some_and(|p| p.tasks.contains_key(&dep.task));
+            if !exists {
+                anyhow::bail!("Task '{}' depends on '{}', which is not defined", id, dep);
+            }
+            deps.insert(dep);
+        }
+    }
+
+    Ok(deps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        crate::config::parse(
+            r#"
+[workspace]
+name = "Test"
+root = "."
+
+[tools.cargo]
+enabled = true
+command = "cargo"
+
+[projects.shared]
+type = "rust"
+path = "crates/shared"
+
+[projects.shared.tasks]
+build = { tool = "cargo", command = "build" }
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+depends_on = ["shared"]
+
+[projects.api.tasks]
+codegen = { tool = "cargo", command = "run --bin codegen" }
+build = { tool = "cargo", command = "build", depends_on = ["^build", "codegen"] }
+
+[projects.app]
+type = "next"
+path = "apps/app"
+
+[projects.app.tasks]
+build = { tool = "cargo", command = "build", depends_on = ["api:build"] }
+lint = { tool = "cargo", command = "clippy" }
+"#,
+        )
+        .unwrap()
+    }
+
+    fn names(order: &[TaskId]) -> Vec<String> {
+        order.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_topological_order_runs_dependencies_first() {
+        let config = config();
+        let graph = TaskGraph::build(&config, "build", &["app".to_string()]).unwrap();
+        let order = names(&graph.topological_order().unwrap());
+        assert_eq!(
+            order,
+            vec!["api:codegen", "shared:build", "api:build", "app:build"]
+        );
+    }
+
+    #[test]
+    fn test_order_is_deterministic() {
+        let config = config();
+        let projects = vec!["shared".to_string(), "app".to_string(), "api".to_string()];
+        let first = TaskGraph::build(&config, "build", &projects)
+            .unwrap()
+            .topological_order()
+            .unwrap();
+        for _ in 0..10 {
+            let again = TaskGraph::build(&config, "build", &projects)
+                .unwrap()
+                .topological_order()
+                .unwrap();
+            assert_eq!(first, again);
+        }
+    }
+
+    #[test]
+    fn test_projects_without_task_are_skipped() {
+        let config = config();
+        let graph = TaskGraph::build(&config, "lint", &["api".to_string()]).unwrap();
+        assert!(graph.topological_order().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_caret_skips_upstream_projects_without_task() {
+        let mut config = config();
+        config.projects.get_mut("shared").unwrap().tasks.remove("build");
+        let graph = TaskGraph::build(&config, "build", &["api".to_string()]).unwrap();
+        assert_eq!(
+            names(&graph.topological_order().unwrap()),
+            vec!["api:codegen", "api:build"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_dependency_is_an_error() {
+        let mut config = config();
+        config
+            .projects
+            .get_mut("app")
+            .unwrap()
+            .tasks
+            .get_mut("build")
+            .unwrap()
+            .depends_on = vec!["web:build".to_string()];
+        let err = TaskGraph::build(&config, "build", &["app".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("web:build"), "{}", err);
+    }
+
+    #[test]
+    fn test_cycle_is_reported_with_path() {
+        let mut config = config();
+        config
+            .projects
+            .get_mut("shared")
+            .unwrap()
+            .tasks
+            .get_mut("build")
+            .unwrap()
+            .depends_on = vec!["app:build".to_string()];
+        let graph = TaskGraph::build(&config, "build", &["app".to_string()]).unwrap();
+        let err = graph.topological_order().unwrap_err().to_string();
+        assert!(err.contains("Dependency cycle detected"), "{}", err);
+        assert!(
+            err.contains("api:build → shared:build → app:build → api:build"),
+            "{}",
+            err
+        );
+    }
+}
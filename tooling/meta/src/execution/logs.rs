@@ -0,0 +1,533 @@
+//! `meta logs`: one or several project logs, interleaved and filtered.
+//! Files are read and followed natively instead of through `tail`, so logs
+//! that are truncated or rotated are picked up again.
+
+use std::{
+    collections::VecDeque,
+    io::{ErrorKind, IsTerminal, SeekFrom},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, BufReader},
+    sync::mpsc,
+};
+
+use super::{
+    events,
+    rotation::{self, log_path},
+    structured::{parse_line, JsonRecord, LogLevel},
+    supervisor::{prefix, strip_ansi},
+};
+use crate::config::Config;
+
+/// How often followed logs are checked for new lines
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// What `meta logs` shows
+pub struct LogOptions {
+    pub projects: Vec<String>,
+    pub all: bool,
+    pub follow: bool,
+    /// Lines of history per project
+    pub lines: usize,
+    pub grep: Option<String>,
+    pub level: Option<LogLevel>,
+    pub since: Option<Duration>,
+    pub json: bool,
+    /// Delete rotated logs and empty the current ones instead of showing them
+    pub clean: bool,
+}
+
+/// One log line
+#[derive(Debug)]
+struct Record {
+    /// Index into the projects being shown
+    project: usize,
+    line: String,
+    timestamp: Option<u64>,
+    level: Option<LogLevel>,
+    target: Option<String>,
+    message: String,
+}
+
+impl Record {
+    /// `fallback` is the timestamp of a line that carries none of its own
+    fn parse(project: usize, raw: &[u8], fallback: Option<u64>) -> Record {
+        let line = strip_ansi(String::from_utf8_lossy(raw).trim_end_matches(['\n', '\r']));
+        let parsed = parse_line(&line);
+        Record {
+            project,
+            timestamp: parsed.timestamp.or(fallback),
+            level: parsed.level,
+            target: parsed.target,
+            message: parsed.message,
+            line,
+        }
+    }
+}
+
+struct Filter {
+    grep: Option<Regex>,
+    level: Option<LogLevel>,
+    /// Seconds since the epoch lines must be logged at or after
+    since: Option<u64>,
+}
+
+impl Filter {
+    fn new(options: &LogOptions) -> Result<Filter> {
+        let grep = options
+            .grep
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .context("Invalid --grep pattern")?;
+        Ok(Filter {
+            grep,
+            level: options.level,
+            since: options.since.map(|since| now().saturating_sub(since.as_secs())),
+        })
+    }
+
+    fn matches(&self, record: &Record) -> bool {
+        self.grep.as_ref().map_or(true, |grep| grep.is_match(&record.line))
+            && self.level.map_or(true, |level| record.level >= Some(level))
+            && self
+                .since
+                .map_or(true, |since| record.timestamp.is_some_and(|t| t >= since))
+    }
+}
+
+/// How records are printed
+struct Output<'a> {
+    projects: &'a [String],
+    /// Prefix lines with `[project]` when several logs are shown
+    prefixed: bool,
+    color: bool,
+    json: bool,
+}
+
+impl Output<'_> {
+    fn print(&self, record: &Record) {
+        let name = &self.projects[record.project];
+        if self.json {
+            let entry = serde_json::json!({
+                "project": name,
+                "timestamp": record.timestamp.map(events::format_timestamp),
+                "level": record.level,
+                "target": record.target,
+                "message": record.message,
+                "line": record.line,
+            });
+            println!("{}", entry);
+        } else if self.prefixed {
+            println!("{} {}", prefix(name, record.project, self.color), record.line);
+        } else {
+            println!("{}", record.line);
+        }
+    }
+}
+
+/// View logs for one or more projects
+pub async fn logs(config: &Config, options: LogOptions) -> Result<()> {
+    let projects: Vec<String> = if options.all {
+        let mut names: Vec<String> = config.projects.keys().cloned().collect();
+        names.sort();
+        names
+    } else {
+        options.projects.clone()
+    };
+
+    if options.clean {
+        return clean(config, &projects);
+    }
+
+    // If no project specified, list available logs
+    if projects.is_empty() {
+        return list_available_logs(config).await;
+    }
+
+    // Validate projects exist in config
+    for project in &projects {
+        if !config.projects.contains_key(project) {
+            let mut available: Vec<&str> = config.projects.keys().map(String::as_str).collect();
+            available.sort();
+            anyhow::bail!(
+                "Unknown project '{}'. Available projects: {}",
+                project,
+                available.join(", ")
+            );
+        }
+    }
+
+    let filter = Filter::new(&options)?;
+    let output = Output {
+        projects: &projects,
+        prefixed: projects.len() > 1,
+        color: !options.json && std::io::stdout().is_terminal(),
+        json: options.json,
+    };
+
+    // History: the last matching lines of each log, merged by timestamp
+    let mut history = Vec::new();
+    let mut offsets = Vec::new();
+    for (i, project) in projects.iter().enumerate() {
+        let path = log_path(project);
+        let offset = match read_history(&path, i, &options, &filter).await? {
+            Some((records, offset)) => {
+                history.extend(records);
+                Some(offset)
+            }
+            None => None,
+        };
+        offsets.push((path, offset));
+    }
+    if projects.len() > 1 {
+        history.sort_by_key(|record| record.timestamp.unwrap_or(0));
+    }
+    for record in &history {
+        output.print(record);
+    }
+
+    if !options.json && offsets.iter().all(|(_, offset)| offset.is_none()) {
+        if projects.len() == 1 {
+            println!("No log file for '{}' yet.", projects[0]);
+        } else {
+            println!("No log files for these projects yet.");
+        }
+        println!(
+            "\nLogs are created when you run 'meta dev'. The project must output to stdout/stderr."
+        );
+    }
+
+    if !options.follow {
+        return Ok(());
+    }
+
+    // Follow: lines from every log, printed as they arrive
+    let (tx, mut rx) = mpsc::channel(1024);
+    for (i, (path, offset)) in offsets.into_iter().enumerate() {
+        tokio::spawn(follow_file(i, path, offset.unwrap_or(0), tx.clone()));
+    }
+    drop(tx);
+
+    loop {
+        tokio::select! {
+            record = rx.recv() => match record {
+                Some(record) if filter.matches(&record) => output.print(&record),
+                Some(_) => {}
+                None => return Ok(()),
+            },
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The last `options.lines` matching records of a log and the offset to
+/// follow it from, or `None` if it doesn't exist yet. Keeps only what will
+/// be shown. Lines without a timestamp take the time they were written from
+/// the project's `.jsonl`, or else the timestamp of the line before them.
+async fn read_history(
+    path: &Path,
+    project: usize,
+    options: &LogOptions,
+    filter: &Filter,
+) -> Result<Option<(Vec<Record>, u64)>> {
+    let file = match File::open(path).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+
+    // Every line reaches the `.log` and the `.jsonl` in the same order, but
+    // the two rotate at different sizes, so records match the last lines
+    let written = written_timestamps(&path.with_extension("jsonl")).await;
+    let lines = if written.is_empty() { 0 } else { count_lines(path).await? };
+    let first_written = lines.saturating_sub(written.len());
+    let skipped = written.len().saturating_sub(lines);
+
+    let mut reader = BufReader::new(file);
+    let mut kept = VecDeque::with_capacity(options.lines.min(1024));
+    let mut offset = 0;
+    let mut timestamp = None;
+    let mut raw = Vec::new();
+    for index in 0usize.. {
+        raw.clear();
+        let read = reader.read_until(b'\n', &mut raw).await?;
+        if read == 0 {
+            break;
+        }
+        // A line still being written is left for follow to print whole
+        let complete = raw.ends_with(b"\n");
+        if complete {
+            offset += read as u64;
+        } else if options.follow {
+            break;
+        }
+
+        let written = index
+            .checked_sub(first_written)
+            .and_then(|i| written.get(i + skipped).copied().flatten());
+        let record = Record::parse(project, &raw, written.or(timestamp));
+        timestamp = record.timestamp;
+        if options.lines > 0 && filter.matches(&record) {
+            if kept.len() == options.lines {
+                kept.pop_front();
+            }
+            kept.push_back(record);
+        }
+    }
+    Ok(Some((kept.into(), offset)))
+}
+
+/// The timestamp of each record in a `.jsonl` log, empty if there is none
+async fn written_timestamps(path: &Path) -> Vec<Option<u64>> {
+    let Ok(file) = File::open(path).await else {
+        return vec![];
+    };
+    let mut timestamps = vec![];
+    let mut lines = BufReader::new(file).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let record = serde_json::from_str::<JsonRecord>(&line).ok();
+        timestamps.push(record.and_then(|r| events::parse_timestamp(&r.timestamp)));
+    }
+    timestamps
+}
+
+/// Complete lines in a file
+async fn count_lines(path: &Path) -> Result<usize> {
+    let mut reader = BufReader::new(File::open(path).await?);
+    let mut lines = 0;
+    let mut raw = Vec::new();
+    loop {
+        raw.clear();
+        if reader.read_until(b'\n', &mut raw).await? == 0 {
+            return Ok(lines);
+        }
+        if raw.ends_with(b"\n") {
+            lines += 1;
+        }
+    }
+}
+
+/// Send complete lines appended to `path` after `offset` until the receiver
+/// is gone. A log that shrinks was truncated or rotated and is read again
+/// from the start; one that doesn't exist yet is waited for.
+async fn follow_file(project: usize, path: PathBuf, mut offset: u64, tx: mpsc::Sender<Record>) {
+    let mut pending = Vec::new();
+    loop {
+        if let Ok(mut file) = File::open(&path).await {
+            let len = file.metadata().await.map(|m| m.len()).unwrap_or(offset);
+            if len < offset {
+                offset = 0;
+                pending.clear();
+            }
+            if len > offset && file.seek(SeekFrom::Start(offset)).await.is_ok() {
+                if let Ok(read) = file.read_to_end(&mut pending).await {
+                    offset += read as u64;
+                }
+            }
+        }
+
+        // Lines without a timestamp of their own were logged just now
+        let arrived = now();
+        while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = pending.drain(..=end).collect();
+            if tx.send(Record::parse(project, &raw, Some(arrived))).await.is_err() {
+                return;
+            }
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// `meta logs --clean`: every project log unless projects are given
+fn clean(config: &Config, projects: &[String]) -> Result<()> {
+    for project in projects {
+        if !config.projects.contains_key(project) {
+            anyhow::bail!("Unknown project '{}'", project);
+        }
+    }
+    let cleaned = rotation::clean(projects)?;
+    if cleaned.removed == 0 && cleaned.emptied == 0 {
+        println!("✨ No logs to clean.");
+    } else {
+        println!(
+            "🧹 Removed {} rotated log(s) and emptied {} log(s), freeing {}",
+            cleaned.removed,
+            cleaned.emptied,
+            format_size(cleaned.bytes)
+        );
+    }
+    Ok(())
+}
+
+fn format_size(size: u64) -> String {
+    if size >= 1024 * 1024 {
+        format!("{:.1}MB", size as f64 / (1024.0 * 1024.0))
+    } else if size >= 1024 {
+        format!("{}KB", size / 1024)
+    } else {
+        format!("{}B", size)
+    }
+}
+
+/// List available log files
+async fn list_available_logs(config: &Config) -> Result<()> {
+    println!("## Available Project Logs\n");
+
+    let mut has_logs = false;
+    for name in config.projects.keys() {
+        let log_path = log_path(name);
+        if let Ok(metadata) = std::fs::metadata(&log_path) {
+            has_logs = true;
+            println!(
+                "  {} ({}) → meta logs {}",
+                name,
+                format_size(metadata.len()),
+                name
+            );
+        }
+    }
+
+    if !has_logs {
+        println!("  (no log files yet)\n");
+        println!("Logs are created when you run 'meta dev'.");
+        println!("Project stdout/stderr is captured to .meta/logs/<project>.log");
+    } else {
+        println!("\nUsage:");
+        println!("  meta logs <project>           View last 50 lines");
+        println!("  meta logs <project> -l 100    View last 100 lines");
+        println!("  meta logs <project> --follow  Stream logs in real-time");
+        println!("  meta logs --all -f            Follow every project, interleaved");
+        println!("  meta logs --all --level error Only errors (also --grep, --since 10m, --json)");
+        println!("  meta logs --clean             Delete rotated logs and empty the current ones");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_options(lines: usize, follow: bool) -> LogOptions {
+        LogOptions {
+            projects: vec![],
+            all: false,
+            follow,
+            lines,
+            grep: None,
+            level: None,
+            since: None,
+            json: false,
+            clean: false,
+        }
+    }
+
+    #[test]
+    fn test_filter_by_grep_level_and_since() {
+        let record = |line: &str, timestamp| Record::parse(0, line.as_bytes(), timestamp);
+        let mut options = log_options(10, false);
+        options.grep = Some("db".to_string());
+        options.level = Some(LogLevel::Warn);
+        options.since = Some(Duration::from_secs(600));
+        let filter = Filter::new(&options).unwrap();
+
+        assert!(filter.matches(&record("ERROR db down", Some(now()))));
+        assert!(filter.matches(&record("warning: db slow", Some(now() - 60))));
+        assert!(!filter.matches(&record("warning: db slow", Some(now() - 3600))));
+        assert!(!filter.matches(&record("warning: db slow", None)));
+        assert!(!filter.matches(&record("INFO db up", Some(now()))));
+        assert!(!filter.matches(&record("ERROR cache down", Some(now()))));
+
+        options.grep = Some("(".to_string());
+        assert!(Filter::new(&options).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_history_keeps_last_matching_lines() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("api.log");
+        std::fs::write(
+            &path,
+            "2024-12-08T12:00:00 start\nERROR one\n\x1b[31mERROR two\x1b[0m\nok\nERROR three\npartial",
+        )
+        .unwrap();
+
+        let mut options = log_options(2, false);
+        options.level = Some(LogLevel::Error);
+        let filter = Filter::new(&options).unwrap();
+        let (records, offset) = read_history(&path, 0, &options, &filter).await.unwrap().unwrap();
+        let lines: Vec<&str> = records.iter().map(|r| r.line.as_str()).collect();
+        assert_eq!(lines, ["ERROR two", "ERROR three"]);
+        assert_eq!(records[0].timestamp, Some(1_733_659_200));
+        assert_eq!(offset, std::fs::metadata(&path).unwrap().len() - "partial".len() as u64);
+
+        let options = log_options(0, true);
+        let (records, _) = read_history(&path, 0, &options, &filter).await.unwrap().unwrap();
+        assert!(records.is_empty());
+
+        assert!(read_history(&temp_dir.path().join("web.log"), 0, &options, &filter)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_history_takes_write_times_from_jsonl() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("api.log");
+        // `old` was rotated out of the .jsonl, the rest were written an hour
+        // ago and just now without printing a timestamp
+        std::fs::write(&path, "old\ncompiling\nready on :3000\nGET / 200\n").unwrap();
+        let records: Vec<String> = [
+            ("compiling", now() - 3600),
+            ("ready on :3000", now() - 5),
+            ("GET / 200", now()),
+        ]
+        .iter()
+        .map(|(line, written)| serde_json::to_string(&JsonRecord::new(line, *written)).unwrap())
+        .collect();
+        std::fs::write(path.with_extension("jsonl"), records.join("\n") + "\n").unwrap();
+
+        let mut options = log_options(10, false);
+        options.since = Some(Duration::from_secs(600));
+        let filter = Filter::new(&options).unwrap();
+        let (records, _) = read_history(&path, 0, &options, &filter).await.unwrap().unwrap();
+        let lines: Vec<&str> = records.iter().map(|r| r.line.as_str()).collect();
+        assert_eq!(lines, ["ready on :3000", "GET / 200"]);
+        assert!(records[0].timestamp.is_some_and(|t| t >= now() - 10));
+    }
+
+    #[tokio::test]
+    async fn test_follow_file_sends_appended_lines_and_survives_truncation() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("api.log");
+        std::fs::write(&path, "old\n").unwrap();
+
+        let (tx, mut rx) = mpsc::channel(16);
+        let follower = tokio::spawn(follow_file(3, path.clone(), 4, tx));
+
+        std::fs::write(&path, "old\nnew line\nhalf").unwrap();
+        let record = rx.recv().await.unwrap();
+        assert_eq!((record.project, record.line.as_str()), (3, "new line"));
+        assert!(record.timestamp.is_some());
+
+        std::fs::write(&path, "rotated\n").unwrap();
+        assert_eq!(rx.recv().await.unwrap().line, "rotated");
+
+        follower.abort();
+    }
+}
@@ -0,0 +1,508 @@
+//! `meta mcp`: a Model Context Protocol server on stdin/stdout, so agents
+//! can check status, read logs, run tasks, restart projects and run doctor
+//! without the Docker-based mcp-log-server
+
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::Command,
+};
+
+use super::{
+    rotation::{log_path, DEV_LOG, LOG_DIR},
+    structured::jsonl_path,
+    supervisor::strip_ansi,
+};
+use crate::config::Config;
+
+/// Protocol revisions this server speaks, newest first
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+/// Tool output and log resources are cut to their last this many bytes
+const MAX_OUTPUT: usize = 256 * 1024;
+const LOG_URI_PREFIX: &str = "meta://logs/";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// A JSON-RPC error: code and message
+#[derive(Debug, PartialEq)]
+struct RpcError(i64, String);
+
+fn invalid_params(message: impl Into<String>) -> RpcError {
+    RpcError(INVALID_PARAMS, message.into())
+}
+
+/// Answer requests from stdin, one JSON-RPC message per line, until it
+/// closes
+pub async fn mcp(config: &Config) -> Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(response) = handle(config, &line).await else {
+            continue;
+        };
+        let mut message = serde_json::to_string(&response)?;
+        message.push('\n');
+        stdout.write_all(message.as_bytes()).await?;
+        stdout.flush().await?;
+    }
+    Ok(())
+}
+
+/// The response to one message, or None for notifications (and responses,
+/// since this server sends no requests)
+async fn handle(config: &Config, message: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(message) {
+        Ok(request) => request,
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+    let id = request.get("id")?.clone();
+    let method = request.get("method")?.as_str().unwrap_or_default();
+    let params = request.get("params").unwrap_or(&Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(initialize(params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => call_tool(params).await,
+        "resources/list" => Ok(json!({ "resources": resources(config) })),
+        "resources/read" => read_resource(config, params),
+        _ => Err(RpcError(METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(RpcError(code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Agree on the client's protocol version if this server speaks it,
+/// otherwise offer the newest one
+fn initialize(params: &Value) -> Value {
+    let requested = params["protocolVersion"].as_str().unwrap_or_default();
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|version| **version == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {}, "resources": {} },
+        "serverInfo": { "name": "meta", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Drives the meta dev environment of this monorepo. Use `status` to see \
+            which dev processes run and how many errors they logged, `logs` to read them, \
+            `restart` after changes a dev server doesn't pick up, `run_task` for builds, \
+            tests and checks, and `doctor` when something looks misconfigured.",
+    })
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "status",
+            "description": "Dev process status per project, as `meta status --json`: state, \
+                pid, uptime, readiness, restarts, CPU, memory, ports and error/warning counts.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Only this project" },
+                },
+            },
+        },
+        {
+            "name": "logs",
+            "description": "Recent dev output of projects as JSON lines with project, \
+                timestamp, level, target, message and the raw line.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "projects": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Projects to read (default: all)",
+                    },
+                    "lines": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "Most recent matching lines to return (default 50)",
+                    },
+                    "grep": { "type": "string", "description": "Only lines matching this regex" },
+                    "level": {
+                        "type": "string",
+                        "enum": ["trace", "debug", "info", "warn", "error"],
+                        "description": "Only lines at this level or above",
+                    },
+                    "since": {
+                        "type": "string",
+                        "description": "Only lines logged within this long, e.g. 10m or 1h",
+                    },
+                },
+            },
+        },
+        {
+            "name": "run_task",
+            "description": "Run a task (build, test, lint, ...) as `meta run`, in dependency \
+                order, and return its output and summary.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "task": { "type": "string", "description": "Task name" },
+                    "projects": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Projects to run it for (default: all that define it)",
+                    },
+                },
+                "required": ["task"],
+            },
+        },
+        {
+            "name": "restart",
+            "description": "Restart one project's dev process in the running session and wait \
+                until it is ready.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Project to restart" },
+                },
+                "required": ["project"],
+            },
+        },
+        {
+            "name": "doctor",
+            "description": "Validate meta.toml and check that the configured tools are \
+                installed. Returns every check with a stable id, severity, message and \
+                suggested fix.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+    ])
+}
+
+/// Run a tool as the meta command that does the same, returning an
+/// `isError` result if the command fails
+async fn call_tool(params: &Value) -> Result<Value, RpcError> {
+    let name = params["name"].as_str().unwrap_or_default();
+    let args = tool_args(name, &params["arguments"])?;
+    let (success, text) = match run_meta(&args).await {
+        Ok((success, output)) if output.trim().is_empty() => (success, "(no output)".to_string()),
+        Ok(result) => result,
+        Err(e) => (false, format!("Failed to run meta: {}", e)),
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": !success,
+    }))
+}
+
+/// Command line for a tool call. Options use `--name=value` and names come
+/// after `--`, so arguments can't be mistaken for flags.
+fn tool_args(name: &str, arguments: &Value) -> Result<Vec<String>, RpcError> {
+    let mut args: Vec<String> = Vec::new();
+    match name {
+        "status" => {
+            args.extend(["status".into(), "--json".into()]);
+            if let Some(project) = string_arg(arguments, "project")? {
+                args.push(format!("--project={}", project));
+            }
+        }
+        "logs" => {
+            args.extend(["logs".into(), "--json".into()]);
+            if let Some(lines) = integer_arg(arguments, "lines")? {
+                args.push(format!("--lines={}", lines));
+            }
+            for option in ["grep", "level", "since"] {
+                if let Some(value) = string_arg(arguments, option)? {
+                    args.push(format!("--{}={}", option, value));
+                }
+            }
+            let projects = string_list_arg(arguments, "projects")?;
+            if projects.is_empty() {
+                args.push("--all".into());
+            } else {
+                args.push("--".into());
+                args.extend(projects);
+            }
+        }
+        "run_task" => {
+            let task = string_arg(arguments, "task")?
+                .ok_or_else(|| invalid_params("run_task: 'task' is required"))?;
+            args.push("run".into());
+            for project in string_list_arg(arguments, "projects")? {
+                args.push(format!("--projects={}", project));
+            }
+            args.extend(["--".into(), task]);
+        }
+        "restart" => {
+            let project = string_arg(arguments, "project")?
+                .ok_or_else(|| invalid_params("restart: 'project' is required"))?;
+            args.extend(["restart".into(), "--".into(), project]);
+        }
+        "doctor" => args.extend(["doctor".into(), "--json".into()]),
+        _ => return Err(invalid_params(format!("Unknown tool: {}", name))),
+    }
+    Ok(args)
+}
+
+fn string_arg(arguments: &Value, key: &str) -> Result<Option<String>, RpcError> {
+    match &arguments[key] {
+        Value::Null => Ok(None),
+        Value::String(value) => Ok(Some(value.clone())),
+        _ => Err(invalid_params(format!("'{}' must be a string", key))),
+    }
+}
+
+fn integer_arg(arguments: &Value, key: &str) -> Result<Option<u64>, RpcError> {
+    match &arguments[key] {
+        Value::Null => Ok(None),
+        value => value
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| invalid_params(format!("'{}' must be a non-negative integer", key))),
+    }
+}
+
+fn string_list_arg(arguments: &Value, key: &str) -> Result<Vec<String>, RpcError> {
+    let invalid = || invalid_params(format!("'{}' must be an array of strings", key));
+    match &arguments[key] {
+        Value::Null => Ok(vec![]),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_str().map(str::to_string).ok_or_else(invalid))
+            .collect(),
+        _ => Err(invalid()),
+    }
+}
+
+/// Run meta itself with `args`, returning whether it succeeded and its
+/// output. Going through a child process keeps command output out of the
+/// protocol stream and picks up meta.toml as it is now.
+async fn run_meta(args: &[String]) -> std::io::Result<(bool, String)> {
+    let output = Command::new(std::env::current_exe()?)
+        .args(args)
+        .envs(crate::config::child_env())
+        .env("META_LOG", "warn")
+        // Errors read better without anyhow's backtraces
+        .env("RUST_LIB_BACKTRACE", "0")
+        .stdin(Stdio::null())
+        .output()
+        .await?;
+    let mut text = strip_ansi(&String::from_utf8_lossy(&output.stdout));
+    let stderr = strip_ansi(&String::from_utf8_lossy(&output.stderr));
+    if !stderr.trim().is_empty() {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&stderr);
+    }
+    Ok((output.status.success(), tail(&text, MAX_OUTPUT)))
+}
+
+/// The end of `text`, at most `max` bytes from a line start, noting that
+/// earlier output was cut
+fn tail(text: &str, max: usize) -> String {
+    if text.len() <= max {
+        return text.to_string();
+    }
+    let mut start = text.len() - max;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    let rest = &text[start..];
+    let rest = rest.split_once('\n').map_or(rest, |(_, after)| after);
+    format!("[… earlier output truncated]\n{}", rest)
+}
+
+/// Existing logs of configured projects, text and structured, and dev.log
+fn log_files(config: &Config) -> Vec<PathBuf> {
+    let mut projects: Vec<&String> = config.projects.keys().collect();
+    projects.sort();
+    let mut files = vec![Path::new(LOG_DIR).join(DEV_LOG)];
+    for project in projects {
+        files.extend([log_path(project), jsonl_path(project)]);
+    }
+    files.retain(|path| path.is_file());
+    files
+}
+
+fn log_uri(path: &Path) -> String {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    format!("{}{}", LOG_URI_PREFIX, file_name)
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    if path.extension().is_some_and(|ext| ext == "jsonl") {
+        "application/x-ndjson"
+    } else {
+        "text/plain"
+    }
+}
+
+fn resources(config: &Config) -> Vec<Value> {
+    log_files(config)
+        .iter()
+        .map(|path| {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let description = match file_name.rsplit_once('.') {
+                _ if file_name == DEV_LOG => "Dev events: starts, restarts, readiness, stops".into(),
+                Some((project, "jsonl")) => format!("Structured log records of {}", project),
+                Some((project, _)) => format!("Dev output of {}", project),
+                None => String::new(),
+            };
+            json!({
+                "uri": log_uri(path),
+                "name": file_name,
+                "description": description,
+                "mimeType": mime_type(path),
+                "size": path.metadata().map_or(0, |m| m.len()),
+            })
+        })
+        .collect()
+}
+
+fn read_resource(config: &Config, params: &Value) -> Result<Value, RpcError> {
+    let uri = params["uri"]
+        .as_str()
+        .ok_or_else(|| invalid_params("'uri' must be a string"))?;
+    // Only listed files can be read, whatever the URI says
+    let path = log_files(config)
+        .into_iter()
+        .find(|path| log_uri(path) == uri)
+        .ok_or_else(|| RpcError(RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri)))?;
+    let text = read_tail(&path)
+        .map_err(|e| RpcError(RESOURCE_NOT_FOUND, format!("Failed to read {}: {}", uri, e)))?;
+    Ok(json!({
+        "contents": [{ "uri": uri, "mimeType": mime_type(&path), "text": text }],
+    }))
+}
+
+/// The last `MAX_OUTPUT` bytes of a log, without reading all of it
+fn read_tail(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(MAX_OUTPUT as u64);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    let text = String::from_utf8_lossy(&bytes);
+    if start == 0 {
+        return Ok(text.into_owned());
+    }
+    let rest = text.split_once('\n').map_or("", |(_, after)| after);
+    Ok(format!("[… earlier output truncated]\n{}", rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(name: &str, arguments: Value) -> Result<Vec<String>, RpcError> {
+        tool_args(name, &arguments)
+    }
+
+    #[test]
+    fn test_initialize_negotiates_protocol_version() {
+        let result = initialize(&json!({ "protocolVersion": "2024-11-05" }));
+        assert_eq!(result["protocolVersion"], "2024-11-05");
+        assert_eq!(result["serverInfo"]["name"], "meta");
+
+        let result = initialize(&json!({ "protocolVersion": "1999-01-01" }));
+        assert_eq!(result["protocolVersion"], PROTOCOL_VERSIONS[0]);
+    }
+
+    #[tokio::test]
+    async fn test_handle_requests_and_notifications() {
+        let config = crate::config::parse(
+            r#"
+version = "1"
+[workspace]
+name = "Test"
+root = "."
+[tools.npm]
+enabled = true
+command = "npm"
+[projects.web]
+type = "next"
+path = "web"
+[projects.web.tasks]
+dev = { tool = "npm", command = "run dev" }
+"#,
+        )
+        .unwrap();
+
+        let notification = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        assert_eq!(handle(&config, notification).await, None);
+
+        let response = handle(&config, r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#)
+            .await
+            .unwrap();
+        let names: Vec<&str> = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["status", "logs", "run_task", "restart", "doctor"]);
+
+        let response = handle(&config, r#"{"jsonrpc":"2.0","id":"a","method":"nope"}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["id"], "a");
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = handle(&config, "{not json").await.unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let read = r#"{"jsonrpc":"2.0","id":2,"method":"resources/read",
+            "params":{"uri":"meta://logs/../../meta.toml"}}"#;
+        let response = handle(&config, &read.replace('\n', "")).await.unwrap();
+        assert_eq!(response["error"]["code"], RESOURCE_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_tool_args() {
+        assert_eq!(args("status", json!({})).unwrap(), ["status", "--json"]);
+        assert_eq!(
+            args("logs", json!({ "projects": ["api"], "lines": 5, "level": "warn" })).unwrap(),
+            ["logs", "--json", "--lines=5", "--level=warn", "--", "api"]
+        );
+        assert_eq!(
+            args("logs", json!({ "grep": "-x" })).unwrap(),
+            ["logs", "--json", "--grep=-x", "--all"]
+        );
+        assert_eq!(
+            args("run_task", json!({ "task": "test", "projects": ["api", "web"] })).unwrap(),
+            ["run", "--projects=api", "--projects=web", "--", "test"]
+        );
+        assert_eq!(args("restart", json!({ "project": "api" })).unwrap(), ["restart", "--", "api"]);
+
+        assert_eq!(args("restart", json!({})).unwrap_err().0, INVALID_PARAMS);
+        assert_eq!(args("logs", json!({ "lines": -1 })).unwrap_err().0, INVALID_PARAMS);
+        assert_eq!(args("logs", json!({ "projects": "api" })).unwrap_err().0, INVALID_PARAMS);
+        assert_eq!(args("deploy", json!({})).unwrap_err().0, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_tail_cuts_at_a_line_start() {
+        assert_eq!(tail("short", 10), "short");
+        assert_eq!(tail("first line\nsecond\nthird", 12), "[… earlier output truncated]\nthird");
+    }
+}
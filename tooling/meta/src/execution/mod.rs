@@ -1,10 +1,40 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::IsTerminal;
 
 use anyhow::Result;
 use tokio::process::Command;
 
-use crate::{adapters::ToolAdapter, config::Config};
+use crate::config::{Config, PortConfig, RestartPolicy};
+
+mod affected;
+mod cache;
+mod control;
+mod doctor;
+mod events;
+mod fixes;
+mod graph;
+mod logs;
+mod mcp;
+mod ports;
+mod process;
+mod ready;
+mod rotation;
+mod runner;
+mod structured;
+mod supervisor;
+mod watch;
+mod workspace;
+
+pub use control::{restart, stop};
+pub use doctor::{doctor, DoctorOptions};
+pub use logs::{logs, LogOptions};
+pub use mcp::mcp;
+pub use rotation::log_sink;
+use graph::TaskGraph;
+use process::{ProcessInfo, ProcessTable, TreeUsage};
+pub use runner::{default_jobs, RunOptions};
+pub use structured::LogLevel;
+pub use watch::watch;
 
 /// Generate unique session name from current directory
 fn get_session_name() -> String {
@@ -31,6 +61,8 @@ fn sanitize_session_name(name: &str) -> String {
 
 /// Pane information from tmux
 struct PaneInfo {
+    /// Unique pane id (`%N`), stable across layout changes
+    id: String,
     index: usize,
     title: String,
     pid: u32,
@@ -45,7 +77,7 @@ async fn get_tmux_panes(session_name: &str) -> Result<Vec<PaneInfo>> {
             "-t",
             session_name,
             "-F",
-            "#{pane_index}|#{pane_title}|#{pane_pid}|#{pane_start_time}",
+            "#{pane_id}|#{pane_index}|#{pane_title}|#{pane_pid}|#{pane_start_time}",
         ])
         .output()
         .await?;
@@ -59,47 +91,19 @@ async fn get_tmux_panes(session_name: &str) -> Result<Vec<PaneInfo>> {
 
     for line in stdout.lines() {
         let parts: Vec<&str> = line.split('|').collect();
-        if parts.len() >= 4 {
+        if parts.len() >= 5 {
             panes.push(PaneInfo {
-                index: parts[0].parse().unwrap_or(0),
-                title: parts[1].to_string(),
-                pid: parts[2].parse().unwrap_or(0),
-                _start_time: parts[3].to_string(),
+                id: parts[0].to_string(),
+                index: parts[1].parse().unwrap_or(0),
+                title: parts[2].to_string(),
+                pid: parts[3].parse().unwrap_or(0),
+                _start_time: parts[4].to_string(),
             });
         }
     }
     Ok(panes)
 }
 
-/// Build a pid→children map from the full process table.
-/// This is more reliable than `pgrep -P` because it captures the tree in a
-/// single snapshot, avoiding races where processes exit between calls.
-fn build_process_tree(ps_output: &str) -> HashMap<u32, Vec<u32>> {
-    let mut tree: HashMap<u32, Vec<u32>> = HashMap::new();
-    for line in ps_output.lines().skip(1) {
-        // format: "  PID  PPID"
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            if let (Ok(pid), Ok(ppid)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-                tree.entry(ppid).or_default().push(pid);
-            }
-        }
-    }
-    tree
-}
-
-/// Recursively collect all descendant PIDs from a process tree map.
-fn collect_descendants(tree: &HashMap<u32, Vec<u32>>, pid: u32) -> Vec<u32> {
-    let mut result = Vec::new();
-    if let Some(children) = tree.get(&pid) {
-        for &child in children {
-            result.push(child);
-            result.extend(collect_descendants(tree, child));
-        }
-    }
-    result
-}
-
 /// Find the best PID to report for a project.
 ///
 /// Strategy (in order of reliability):
@@ -110,45 +114,27 @@ fn collect_descendants(tree: &HashMap<u32, Vec<u32>>, pid: u32) -> Vec<u32> {
 /// This handles the bacon case where: shell → bacon → cargo → binary
 /// If the shell exits, children are reparented to launchd (pid 1) on macOS,
 /// making pgrep -P unreliable. The full process tree snapshot avoids this.
-async fn find_active_pid(pane_pid: u32, session_name: &str, pane_index: Option<usize>) -> Option<u32> {
+async fn find_active_pid(
+    processes: &ProcessTable,
+    pane_pid: u32,
+    session_name: &str,
+    pane_index: Option<usize>,
+) -> Option<u32> {
     // Strategy 1: Check if the pane PID itself is alive
-    let direct_check = Command::new("ps")
-        .args(["-p", &pane_pid.to_string(), "-o", "pid="])
-        .output()
-        .await;
-
-    if let Ok(output) = &direct_check {
-        let pid_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if !pid_str.is_empty() {
-            return Some(pane_pid);
-        }
-    }
-
-    // Strategy 2: Build full process tree and find descendants
-    let ps_output = Command::new("ps")
-        .args(["-axo", "pid,ppid"])
-        .output()
-        .await;
-
-    if let Ok(output) = ps_output {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let tree = build_process_tree(&stdout);
-        let descendants = collect_descendants(&tree, pane_pid);
-
-        // Return the deepest alive descendant (most likely the actual binary)
-        // Check from the end since descendants are added depth-first
-        for &desc_pid in descendants.iter().rev() {
-            let alive = Command::new("ps")
-                .args(["-p", &desc_pid.to_string(), "-o", "pid="])
-                .output()
-                .await;
-            if let Ok(out) = alive {
-                let s = String::from_utf8_lossy(&out.stdout).trim().to_string();
-                if !s.is_empty() {
-                    return Some(desc_pid);
-                }
-            }
-        }
+    if processes.is_alive(pane_pid) {
+        return Some(pane_pid);
+    }
+
+    // Strategy 2: Return the deepest alive descendant (most likely the
+    // actual binary). Check from the end since descendants are added
+    // depth-first
+    if let Some(&pid) = processes
+        .descendants(pane_pid)
+        .iter()
+        .rev()
+        .find(|pid| processes.is_alive(**pid))
+    {
+        return Some(pid);
     }
 
     // Strategy 3: Ask tmux for the current pane PID (may have updated)
@@ -162,18 +148,9 @@ async fn find_active_pid(pane_pid: u32, session_name: &str, pane_index: Option<u
         if let Ok(output) = tmux_pid {
             let pid_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
             if let Ok(pid) = pid_str.parse::<u32>() {
-                if pid != pane_pid {
-                    // tmux reports a different PID — check if it's alive
-                    let alive = Command::new("ps")
-                        .args(["-p", &pid.to_string(), "-o", "pid="])
-                        .output()
-                        .await;
-                    if let Ok(out) = alive {
-                        let s = String::from_utf8_lossy(&out.stdout).trim().to_string();
-                        if !s.is_empty() {
-                            return Some(pid);
-                        }
-                    }
+                // tmux reports a different PID — check if it's alive
+                if pid != pane_pid && processes.is_alive(pid) {
+                    return Some(pid);
                 }
             }
         }
@@ -191,9 +168,9 @@ async fn find_active_pid(pane_pid: u32, session_name: &str, pane_index: Option<u
 /// Log file: .meta/logs/dev.log
 ///
 /// ## Running Processes
-/// PROJECT    PID      STARTED                    UPTIME
-/// api        12345    2025-12-08T12:02:18        1h 23m
-/// web        12346    2025-12-08T12:02:19        1h 23m
+/// PROJECT    PID      STARTED              READY   UPTIME   CPU%    MEM   ERR  WARN  PORTS
+/// api        12345    2025-12-08T12:02:18Z yes     1h 23m    0.4  48.2M     0     2  3000
+/// web        12346    2025-12-08T12:02:19Z -       1h 23m    2.1 210.5M     1     0  5173
 ///
 /// ## Recent Events (last 20)
 /// [2025-12-08T12:02:18] [api] START: Process started (pid=12345)
@@ -232,56 +209,88 @@ pub async fn status(config: &Config, project: Option<String>, lines: usize, json
     // Get tmux panes for process detection
     let panes = get_tmux_panes(&session_name).await.unwrap_or_default();
 
+    // One snapshot of the process table serves every project
+    let processes = ProcessTable::snapshot().await;
+
     // Show running processes
     println!("## Running Processes");
-    println!("{:<15} {:<10} {:<28} UPTIME", "PROJECT", "PID", "STARTED");
-    println!("{}", "-".repeat(70));
+    println!(
+        "{:<15} {:<10} {:<20} {:<10} {:<9} {:>6} {:>8} {:>5} {:>5}  PORTS",
+        "PROJECT", "PID", "STARTED", "READY", "UPTIME", "CPU%", "MEM", "ERR", "WARN"
+    );
+    println!("{}", "-".repeat(108));
 
     let dev_projects = config.projects_with_dev_task();
-    for name in dev_projects.keys() {
+    let dev_log = std::fs::read_to_string(".meta/logs/dev.log").unwrap_or_default();
+    for (name, proj) in &dev_projects {
         if let Some(ref filter) = project {
             if name != filter {
                 continue;
             }
         }
 
-        // Find the pane for this project by matching title
-        let pane = panes.iter().find(|p| &p.title == name);
+        // A stopped project's pane only holds an idle shell
+        if session_active && events::is_stopped(&dev_log, name) {
+            println!("{:<15} {:<10} {:<20} {:<10} -", name, "-", "stopped", "-");
+            continue;
+        }
 
-        if let Some(pane) = pane {
-            // Walk process tree to find the actual running process
-            // This handles bacon-spawned processes where the shell wrapper may have exited
-            let active_pid = find_active_pid(pane.pid, &session_name, Some(pane.index)).await;
-
-            if let Some(pid) = active_pid {
-                let detail_output = Command::new("ps")
-                    .args(["-p", &pid.to_string(), "-o", "pid,lstart,etime"])
-                    .output()
-                    .await;
-
-                if let Ok(detail) = detail_output {
-                    let detail_str = String::from_utf8_lossy(&detail.stdout);
-                    let lines_vec: Vec<&str> = detail_str.lines().collect();
-                    if lines_vec.len() >= 2 {
-                        let info_parts: Vec<&str> = lines_vec[1].split_whitespace().collect();
-                        if info_parts.len() >= 6 {
-                            let started = info_parts[1..6].join(" ");
-                            let uptime = info_parts.last().unwrap_or(&"?");
-                            println!("{:<15} {:<10} {:<28} {}", name, pid, started, uptime);
-                        } else {
-                            println!("{:<15} {:<10} {:<28} -", name, pid, "(running)");
-                        }
-                    } else {
-                        println!("{:<15} {:<10} {:<28} -", name, "-", "not running");
-                    }
-                } else {
-                    println!("{:<15} {:<10} {:<28} -", name, "-", "not running");
-                }
-            } else {
-                println!("{:<15} {:<10} {:<28} -", name, "-", "not running");
+        // Find the pane for this project by matching title, then walk the
+        // process tree to find the actual running process. This handles
+        // bacon-spawned processes where the shell wrapper may have exited
+        let pane = panes.iter().find(|p| &p.title == name);
+        let active_pid = match pane {
+            Some(pane) => {
+                find_active_pid(&processes, pane.pid, &session_name, Some(pane.index)).await
             }
-        } else {
-            println!("{:<15} {:<10} {:<28} -", name, "-", "not running");
+            None => None,
+        };
+        let Some(info) = active_pid.and_then(|pid| processes.get(pid)) else {
+            println!("{:<15} {:<10} {:<20} {:<10} -", name, "-", "not running", "-");
+            continue;
+        };
+
+        let usage = TreeUsage::of(&processes.tree(info.pid));
+        let ports: Vec<String> = usage.ports.iter().map(u16::to_string).collect();
+        let ready = match dev_ready(proj, name).await {
+            Some(true) => "✓ ready",
+            Some(false) => "… waiting",
+            None => "-",
+        };
+        // Only what the current run logged
+        let counts = structured::count_levels(name, info.started_secs());
+        println!(
+            "{:<15} {:<10} {:<20} {:<10} {:<9} {:>6.1} {:>8} {:>5} {:>5}  {}",
+            name,
+            info.pid,
+            info.started_at(),
+            ready,
+            format_uptime(info.uptime_seconds),
+            usage.cpu_percent,
+            format_memory(usage.rss_kb),
+            counts.errors,
+            counts.warnings,
+            if ports.is_empty() { "-".to_string() } else { ports.join(",") }
+        );
+    }
+
+    // Show automatic restarts (restart = "on-failure" / "always")
+    let mut restarted: Vec<(&String, events::RestartStats)> = dev_projects
+        .keys()
+        .filter(|name| project.as_ref().map_or(true, |filter| *name == filter))
+        .map(|name| (name, events::restart_stats(&dev_log, name)))
+        .filter(|(_, stats)| stats.count > 0)
+        .collect();
+    restarted.sort_by(|a, b| a.0.cmp(b.0));
+    if !restarted.is_empty() {
+        println!("\n## Restarts");
+        for (name, stats) in restarted {
+            println!(
+                "{:<15} {:<10} {}",
+                name,
+                stats.count,
+                stats.last.unwrap_or_default()
+            );
         }
     }
 
@@ -323,9 +332,6 @@ pub async fn status(config: &Config, project: Option<String>, lines: usize, json
     );
 
     for (name, proj) in &dev_projects {
-        if proj.project_type != "rust" {
-            continue;
-        }
         if let Some(ref filter) = project {
             if name != filter {
                 continue;
@@ -333,16 +339,9 @@ pub async fn status(config: &Config, project: Option<String>, lines: usize, json
         }
 
         // Skip library crates — they don't produce binaries
-        if is_library_crate(&proj.path) {
+        let Some(binary_path) = rust_binary_path(name, proj) else {
             continue;
-        }
-
-        // Get actual binary name from Cargo.toml
-        let binary_name = get_rust_binary_name(&proj.path).unwrap_or_else(|| name.clone());
-        // Use workspace-aware binary path
-        let workspace_root = detect_cargo_workspace(&proj.path);
-        let binary_path =
-            get_rust_binary_path(&proj.path, &binary_name, workspace_root.as_deref());
+        };
         let path = std::path::Path::new(&binary_path);
 
         if path.exists() {
@@ -358,34 +357,20 @@ pub async fn status(config: &Config, project: Option<String>, lines: usize, json
                     // Walk process tree to find active PID (handles bacon grandchildren)
                     if let Some(pane) = panes.iter().find(|p| p.title == *name) {
                         let active_pid =
-                            find_active_pid(pane.pid, &session_name, Some(pane.index)).await.unwrap_or(pane.pid);
-                        // Get elapsed time using ps -o etime (format: [[DD-]HH:]MM:SS)
-                        let etime_output = Command::new("ps")
-                            .args(["-p", &active_pid.to_string(), "-o", "etime="])
-                            .output()
-                            .await;
-
-                        if let Ok(etime) = etime_output {
-                            let etime_str =
-                                String::from_utf8_lossy(&etime.stdout).trim().to_string();
-                            if let Some(process_age_secs) = parse_etime(&etime_str) {
-                                let binary_age_secs = binary_age.as_secs();
-
-                                // Only consider stale if binary is more than 60 seconds
-                                // newer than process (to avoid false positives from timing)
-                                let stale_threshold_secs = 60;
-                                if binary_age_secs + stale_threshold_secs < process_age_secs {
-                                    // Binary is significantly newer than process - STALE!
-                                    let diff_secs = process_age_secs - binary_age_secs;
-                                    let diff_mins = diff_secs / 60;
-                                    process_status = format!(
-                                        " ⚠️  STALE: binary rebuilt {}m after process started",
-                                        diff_mins
-                                    );
-                                } else {
-                                    process_status = " ✓ running latest binary".to_string();
-                                }
-                            }
+                            find_active_pid(&processes, pane.pid, &session_name, Some(pane.index))
+                                .await
+                                .unwrap_or(pane.pid);
+                        if let Some(info) = processes.get(active_pid) {
+                            process_status = match stale_by(
+                                binary_age.as_secs(),
+                                info.uptime_seconds,
+                            ) {
+                                Some(diff_secs) => format!(
+                                    " ⚠️  STALE: binary rebuilt {}m after process started",
+                                    diff_secs / 60
+                                ),
+                                None => " ✓ running latest binary".to_string(),
+                            };
                         }
                     }
 
@@ -431,6 +416,61 @@ pub async fn status(config: &Config, project: Option<String>, lines: usize, json
     Ok(())
 }
 
+/// The binary a Rust project's dev process runs, or `None` for other project
+/// types and library crates
+fn rust_binary_path(name: &str, proj: &crate::config::ProjectConfig) -> Option<String> {
+    if proj.project_type != "rust" || is_library_crate(&proj.path) {
+        return None;
+    }
+    // Get actual binary name from Cargo.toml
+    let binary_name = get_rust_binary_name(&proj.path).unwrap_or_else(|| name.to_string());
+    // Use workspace-aware binary path
+    let workspace_root = detect_cargo_workspace(&proj.path);
+    Some(get_rust_binary_path(&proj.path, &binary_name, workspace_root.as_deref()))
+}
+
+/// How many seconds after the process started its binary was rebuilt, if
+/// the process is stale. Only a binary more than 60 seconds newer than the
+/// process counts, to avoid false positives from timing.
+fn stale_by(binary_age_secs: u64, process_age_secs: u64) -> Option<u64> {
+    const STALE_THRESHOLD_SECS: u64 = 60;
+    (binary_age_secs + STALE_THRESHOLD_SECS < process_age_secs)
+        .then(|| process_age_secs - binary_age_secs)
+}
+
+/// Uptime as `1h 23m`, `5m 3s` or `42s`
+fn format_uptime(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m {}s", mins, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Memory in KiB as `512K`, `12.3M` or `1.2G`
+fn format_memory(kb: u64) -> String {
+    if kb >= 1024 * 1024 {
+        format!("{:.1}G", kb as f64 / (1024.0 * 1024.0))
+    } else if kb >= 1024 {
+        format!("{:.1}M", kb as f64 / 1024.0)
+    } else {
+        format!("{}K", kb)
+    }
+}
+
+/// Whether a running project's dev task passes its ready probe, or `None`
+/// if it has no (valid) probe
+async fn dev_ready(project: &crate::config::ProjectConfig, name: &str) -> Option<bool> {
+    let probe = project.tasks.get("dev")?.ready.as_ref()?;
+    let probe = ready::Probe::new(probe).ok()?;
+    Some(probe.check_status(name).await)
+}
+
 /// JSON output for `meta status --json`
 async fn status_json(config: &Config, project: Option<String>) -> Result<()> {
     let session_name = get_session_name();
@@ -448,7 +488,9 @@ async fn status_json(config: &Config, project: Option<String>) -> Result<()> {
         vec![]
     };
 
+    let processes = ProcessTable::snapshot().await;
     let dev_projects = config.projects_with_dev_task();
+    let dev_log = std::fs::read_to_string(".meta/logs/dev.log").unwrap_or_default();
     let mut project_statuses = Vec::new();
 
     for (name, proj) in &dev_projects {
@@ -460,46 +502,50 @@ async fn status_json(config: &Config, project: Option<String>) -> Result<()> {
 
         let pane = panes.iter().find(|p| &p.title == name);
         let mut status = "not running".to_string();
-        let mut pid: Option<u32> = None;
-        let mut uptime_seconds: Option<u64> = None;
-        let mut started_at: Option<String> = None;
-
-        if let Some(pane) = pane {
-            let active = find_active_pid(pane.pid, &session_name, Some(pane.index)).await;
-            if let Some(active_pid) = active {
-                pid = Some(active_pid);
-                status = "running".to_string();
+        let mut info: Option<&ProcessInfo> = None;
 
-                let detail = Command::new("ps")
-                    .args(["-p", &active_pid.to_string(), "-o", "lstart,etime"])
-                    .output()
-                    .await;
-
-                if let Ok(detail) = detail {
-                    let detail_str = String::from_utf8_lossy(&detail.stdout);
-                    let lines: Vec<&str> = detail_str.lines().collect();
-                    if lines.len() >= 2 {
-                        let parts: Vec<&str> = lines[1].split_whitespace().collect();
-                        if parts.len() >= 6 {
-                            started_at = Some(parts[..5].join(" "));
-                            if let Some(secs) = parse_etime(parts.last().unwrap_or(&"")) {
-                                uptime_seconds = Some(secs);
-                            }
-                        }
-                    }
-                }
+        if let Some(pane) = pane.filter(|_| !events::is_stopped(&dev_log, name)) {
+            let active = find_active_pid(&processes, pane.pid, &session_name, Some(pane.index)).await;
+            info = active.and_then(|pid| processes.get(pid));
+            if info.is_some() {
+                status = "running".to_string();
             }
         }
 
+        if pane.is_some() && events::is_stopped(&dev_log, name) {
+            status = "stopped".to_string();
+        }
+
+        let tree = info.map(|i| processes.tree(i.pid)).unwrap_or_default();
+        let usage = info.map(|_| TreeUsage::of(&tree));
+        let pid = info.map(|i| i.pid);
+
         let tool = proj.tasks.get("dev").map(|t| t.tool.clone());
+        let restarts = events::restart_stats(&dev_log, name);
+        let ready = if pid.is_some() {
+            dev_ready(proj, name).await
+        } else {
+            None
+        };
+        let counts = info.map(|i| structured::count_levels(name, i.started_secs()));
 
         let entry = serde_json::json!({
             "name": name,
             "status": status,
             "pid": pid,
             "tool": tool,
-            "started_at": started_at,
-            "uptime_seconds": uptime_seconds,
+            "started_at": info.map(|i| i.started_at()),
+            "uptime_seconds": info.map(|i| i.uptime_seconds),
+            "ready": ready,
+            "restarts": restarts.count,
+            "last_restart": restarts.last,
+            "cpu_percent": usage.as_ref().map(|u| u.cpu_percent),
+            "rss_kb": usage.as_ref().map(|u| u.rss_kb),
+            "threads": usage.as_ref().and_then(|u| u.threads),
+            "ports": usage.map(|u| u.ports).unwrap_or_default(),
+            "processes": tree,
+            "errors": counts.as_ref().map(|c| c.errors),
+            "warnings": counts.as_ref().map(|c| c.warnings),
         });
         project_statuses.push(entry);
     }
@@ -514,110 +560,6 @@ async fn status_json(config: &Config, project: Option<String>) -> Result<()> {
     Ok(())
 }
 
-/// View logs for a specific project
-pub async fn logs(
-    config: &Config,
-    project: Option<String>,
-    follow: bool,
-    lines: usize,
-) -> Result<()> {
-    // If no project specified, list available logs
-    let Some(project) = project else {
-        return list_available_logs(config).await;
-    };
-
-    // Validate project exists in config
-    if !config.projects.contains_key(&project) {
-        let available: Vec<&String> = config.projects.keys().collect();
-        anyhow::bail!(
-            "Unknown project '{}'. Available projects: {}",
-            project,
-            available
-                .iter()
-                .map(|s| s.as_str())
-                .collect::<Vec<_>>()
-                .join(", ")
-        );
-    }
-
-    let log_path = format!(".meta/logs/{}.log", project);
-    let path = std::path::Path::new(&log_path);
-
-    if !path.exists() {
-        println!("No log file for '{}' yet.", project);
-        println!(
-            "\nLogs are created when you run 'meta dev'. The project must output to stdout/stderr."
-        );
-        return Ok(());
-    }
-
-    if follow {
-        // Use tail -f for live streaming
-        let status = Command::new("tail")
-            .args(["-f", "-n", &lines.to_string(), &log_path])
-            .stdin(std::process::Stdio::inherit())
-            .stdout(std::process::Stdio::inherit())
-            .stderr(std::process::Stdio::inherit())
-            .status()
-            .await?;
-
-        if !status.success() {
-            anyhow::bail!("Failed to tail log file");
-        }
-    } else {
-        // Use tail for efficient reading of last N lines (avoids loading entire file)
-        let output = Command::new("tail")
-            .args(["-n", &lines.to_string(), &log_path])
-            .output()
-            .await?;
-
-        if output.status.success() {
-            print!("{}", String::from_utf8_lossy(&output.stdout));
-        } else {
-            anyhow::bail!("Failed to read log file");
-        }
-    }
-
-    Ok(())
-}
-
-/// List available log files
-async fn list_available_logs(config: &Config) -> Result<()> {
-    println!("## Available Project Logs\n");
-
-    let mut has_logs = false;
-    for name in config.projects.keys() {
-        let log_path = format!(".meta/logs/{}.log", name);
-        if std::path::Path::new(&log_path).exists() {
-            if let Ok(metadata) = std::fs::metadata(&log_path) {
-                has_logs = true;
-                let size = metadata.len();
-                let size_str = if size >= 1024 * 1024 {
-                    format!("{:.1}MB", size as f64 / (1024.0 * 1024.0))
-                } else if size >= 1024 {
-                    format!("{}KB", size / 1024)
-                } else {
-                    format!("{}B", size)
-                };
-                println!("  {} ({}) → meta logs {}", name, size_str, name);
-            }
-        }
-    }
-
-    if !has_logs {
-        println!("  (no log files yet)\n");
-        println!("Logs are created when you run 'meta dev'.");
-        println!("Project stdout/stderr is captured to .meta/logs/<project>.log");
-    } else {
-        println!("\nUsage:");
-        println!("  meta logs <project>           View last 50 lines");
-        println!("  meta logs <project> -l 100    View last 100 lines");
-        println!("  meta logs <project> --follow  Stream logs in real-time");
-    }
-
-    Ok(())
-}
-
 /// Get the actual binary name from a Rust project's Cargo.toml
 /// Returns None if Cargo.toml doesn't exist or can't be parsed
 fn get_rust_binary_name(project_path: &str) -> Option<String> {
@@ -641,49 +583,6 @@ fn get_rust_binary_name(project_path: &str) -> Option<String> {
     None
 }
 
-/// Parse `ps -o etime` format: [[DD-]HH:]MM:SS
-/// Examples: "02:30" (2m30s), "01:02:30" (1h2m30s), "2-01:02:30" (2d1h2m30s)
-fn parse_etime(s: &str) -> Option<u64> {
-    let s = s.trim();
-    if s.is_empty() {
-        return None;
-    }
-
-    let mut total_secs: u64 = 0;
-
-    // Check for days (format: DD-HH:MM:SS)
-    let (days, rest) = if s.contains('-') {
-        let parts: Vec<&str> = s.splitn(2, '-').collect();
-        let days: u64 = parts[0].parse().ok()?;
-        (days, parts.get(1).copied().unwrap_or(""))
-    } else {
-        (0, s)
-    };
-
-    total_secs += days * 24 * 60 * 60;
-
-    // Parse HH:MM:SS or MM:SS
-    let time_parts: Vec<&str> = rest.split(':').collect();
-    match time_parts.len() {
-        2 => {
-            // MM:SS
-            let mins: u64 = time_parts[0].parse().ok()?;
-            let secs: u64 = time_parts[1].parse().ok()?;
-            total_secs += mins * 60 + secs;
-        }
-        3 => {
-            // HH:MM:SS
-            let hours: u64 = time_parts[0].parse().ok()?;
-            let mins: u64 = time_parts[1].parse().ok()?;
-            let secs: u64 = time_parts[2].parse().ok()?;
-            total_secs += hours * 60 * 60 + mins * 60 + secs;
-        }
-        _ => return None,
-    }
-
-    Some(total_secs)
-}
-
 /// Stop all running meta tmux sessions for this workspace
 pub async fn dev_stop() -> Result<()> {
     let session_name = get_session_name();
@@ -772,7 +671,94 @@ pub async fn sessions() -> Result<()> {
     Ok(())
 }
 
-pub async fn dev(config: &Config, projects: Option<Vec<String>>, detach: bool) -> Result<()> {
+/// A dev task resolved to the process to launch
+#[derive(Debug, Clone)]
+struct DevCommand {
+    /// Directory to run in, or `None` for the workspace root
+    dir: Option<String>,
+    argv: Vec<String>,
+    /// Variables from `[env]`/`env`/`env_file`, on top of the inherited
+    /// environment
+    env: BTreeMap<String, String>,
+    restart: RestartPolicy,
+    max_restarts: u32,
+    /// Seconds
+    restart_window: u64,
+    ready: Option<ready::Probe>,
+    /// Projects that must be ready before this one starts
+    after: Vec<String>,
+    /// Port the process listens on, also exported as `PORT`
+    port: Option<u16>,
+}
+
+impl DevCommand {
+    /// The command as a single line for a tmux pane, with arguments quoted so
+    /// the pane's shell sees exactly `argv`
+    fn shell_line(&self) -> String {
+        let command = shell_words::join(&self.argv);
+        match &self.dir {
+            Some(dir) => format!("cd {} && {}", shell_words::quote(dir), command),
+            None => command,
+        }
+    }
+}
+
+/// Resolve a project's `dev` task, or `None` if it has none
+fn dev_command(
+    config: &Config,
+    name: &str,
+    project: &crate::config::ProjectConfig,
+    auto_ports: &mut ports::AutoPorts,
+) -> Result<Option<DevCommand>> {
+    let Some(dev_task) = project.tasks.get("dev") else {
+        return Ok(None);
+    };
+    let tool = config
+        .tools
+        .get(&dev_task.tool)
+        .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", dev_task.tool))?;
+
+    let argv = dev_task
+        .argv(&tool.command)
+        .map_err(|e| anyhow::anyhow!("{}:dev: {}", name, e))?;
+
+    // Turborepo runs from workspace root, other tools (bacon, cargo)
+    // run from the project directory
+    let dir = (tool.command != "turbo").then(|| project.path.clone());
+    let port = match dev_task.port {
+        Some(PortConfig::Fixed(port)) => Some(port),
+        Some(PortConfig::Auto) => Some(auto_ports.get(name)?),
+        None => None,
+    };
+    let mut env = config.task_env(name, "dev")?;
+    if let Some(port) = port {
+        env.insert("PORT".to_string(), port.to_string());
+    }
+    Ok(Some(DevCommand {
+        dir,
+        argv,
+        env,
+        restart: dev_task.restart,
+        max_restarts: dev_task.max_restarts,
+        restart_window: dev_task.restart_window,
+        ready: dev_task
+            .ready
+            .as_ref()
+            .map(ready::Probe::new)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("{}:dev: {}", name, e))?,
+        after: dev_task.after.clone(),
+        port,
+    }))
+}
+
+pub async fn dev(
+    config: &Config,
+    projects: Option<Vec<String>>,
+    detach: bool,
+    no_tmux: bool,
+    add: bool,
+) -> Result<()> {
     // When no projects specified, use default_dev_projects (respects dev_default flag)
     // When projects are explicitly specified with -p, use those regardless of dev_default
     let projects_to_run = if projects.is_some() {
@@ -782,31 +768,29 @@ pub async fn dev(config: &Config, projects: Option<Vec<String>>, detach: bool) -
     };
 
     let mut commands = Vec::new();
+    // --add joins the running session, so its projects keep their ports
+    let mut auto_ports = if add {
+        ports::AutoPorts::session()
+    } else {
+        ports::AutoPorts::fresh()
+    };
 
     println!("🚀 Development Commands:\n");
 
     for (name, project) in &projects_to_run {
-        if let Some(dev_task) = project.tasks.get("dev") {
-            let tool = config
-                .tools
-                .get(&dev_task.tool)
-                .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", dev_task.tool))?;
-
-            let command_str = dev_task.command.clone();
-            let project_path = &project.path;
-
-            // Turborepo runs from workspace root, other tools run from project directory
-            let full_command = if tool.command == "turbo" {
-                // Turbo runs from workspace root
-                format!("{} {}", tool.command, command_str)
-            } else {
-                // Other tools (bacon, cargo) run from project directory
-                format!("cd {} && {} {}", project_path, tool.command, command_str)
-            };
-
-            commands.push((name.clone(), full_command.clone()));
-
-            println!("  {} [{}]: {}", name, dev_task.tool, full_command);
+        if let Some(dev_command) = dev_command(config, name, project, &mut auto_ports)? {
+            let port = dev_command
+                .port
+                .map(|port| format!(" (PORT={})", port))
+                .unwrap_or_default();
+            println!(
+                "  {} [{}]: {}{}",
+                name,
+                project.tasks["dev"].tool,
+                dev_command.shell_line(),
+                port
+            );
+            commands.push((name.clone(), dev_command));
         }
     }
 
@@ -814,10 +798,10 @@ pub async fn dev(config: &Config, projects: Option<Vec<String>>, detach: bool) -
         println!("\n⚠️  No dev tasks configured");
         return Ok(());
     }
-
-    println!("\n💡 Launch Options:");
-    println!("  1. Manual: Run each command in a separate terminal");
-    println!("  2. Tmux: meta will launch all commands in tmux panes (recommended)\n");
+    auto_ports.save()?;
+    let tools = commands.iter().map(|(name, _)| projects_to_run[name].tasks["dev"].tool.as_str());
+    runner::check_tool_versions(config, tools).await?;
+    let commands = ready::launch_order(commands)?;
 
     // Auto-launch with tmux if available
     let tmux_available = tokio::process::Command::new("tmux")
@@ -826,167 +810,280 @@ pub async fn dev(config: &Config, projects: Option<Vec<String>>, detach: bool) -
         .await
         .is_ok();
 
-    if tmux_available && commands.len() > 1 {
+    if !tmux_available && add {
+        anyhow::bail!("tmux not found, --add needs a running tmux session to add panes to");
+    } else if !tmux_available && detach && !no_tmux {
+        // The supervisor runs in the foreground, which --detach callers
+        // (CI, agents) would not expect
+        anyhow::bail!(
+            "tmux not found, cannot start in the background. Run 'meta dev --no-tmux' to \
+             supervise processes in the foreground, or run these commands manually in separate \
+             terminals."
+        );
+    } else if no_tmux || !tmux_available {
+        if !no_tmux {
+            println!("\n⚠️  tmux not found, supervising processes directly.");
+            println!("   Install tmux for one pane per project.");
+        }
+        println!();
+        ports::check_available(&commands).await?;
+        supervisor::supervise(&commands, &config.logs).await?;
+    } else if add {
+        let is_interactive = std::io::stdin().is_terminal();
+        println!();
+        launch_tmux_session(&commands, detach || !is_interactive, true).await?;
+    } else if commands.len() > 1 || detach || std::io::stdout().is_terminal() {
+        // A single project gets a pane too, so TUI tools like bacon keep a
+        // terminal and keyboard while the pane wrapper restarts and logs it
+        if commands.len() > 1 {
+            println!("\n💡 Launch Options:");
+            println!("  1. Manual: Run each command in a separate terminal");
+            println!("  2. Tmux: meta will launch all commands in tmux panes (recommended)\n");
+        }
+
         // Detect non-interactive shell (CI, agent, script)
         let is_interactive = std::io::stdin().is_terminal();
         let should_detach = detach || !is_interactive;
 
         println!(
-            "✨ Launching tmux session with {} panes...\n",
-            commands.len()
+            "✨ Launching tmux session with {} pane{}...\n",
+            commands.len(),
+            if commands.len() == 1 { "" } else { "s" }
         );
-        launch_tmux_session(&commands, should_detach).await?;
-    } else if !tmux_available {
-        println!("⚠️  tmux not found. Install tmux to automatically launch all commands.");
-        println!("   For now, run these commands manually in separate terminals.");
+        launch_tmux_session(&commands, should_detach, false).await?;
     } else {
-        // Only one command, just run it directly
-        println!("Running single command: {}\n", commands[0].1);
-        let parts: Vec<&str> = commands[0].1.split("&&").collect();
-        if parts.len() == 2 {
-            let dir = parts[0].trim().strip_prefix("cd ").unwrap_or(".");
-            let cmd_parts: Vec<&str> = parts[1].split_whitespace().collect();
-            if !cmd_parts.is_empty() {
-                let mut cmd = tokio::process::Command::new(cmd_parts[0]);
-                cmd.args(&cmd_parts[1..]);
-                cmd.current_dir(dir);
-                cmd.stdout(std::process::Stdio::inherit());
-                cmd.stderr(std::process::Stdio::inherit());
-                cmd.stdin(std::process::Stdio::inherit());
-                let status = cmd.status().await?;
-                if !status.success() {
-                    anyhow::bail!("Command failed");
-                }
-            }
-        }
+        // One project and no terminal to attach to: supervise it in the
+        // foreground, as with --no-tmux
+        println!();
+        ports::check_available(&commands).await?;
+        supervisor::supervise(&commands, &config.logs).await?;
     }
 
     Ok(())
 }
 
-async fn launch_tmux_session(commands: &[(String, String)], detach: bool) -> Result<()> {
-    let session_name = get_session_name();
-
-    // Kill existing session if it exists
-    let _ = Command::new("tmux")
-        .args(["kill-session", "-t", &session_name])
-        .output()
-        .await;
-
-    // Ensure log directory exists
-    let log_dir = std::path::Path::new(".meta/logs");
-    if !log_dir.exists() {
-        std::fs::create_dir_all(log_dir)?;
-    }
-
-    // Wrap commands in a shell that:
-    // 1. Logs process start/restart events to .meta/logs/dev.log
-    // 2. Keeps the pane alive after the command exits
-    // 3. Allows manual restart with Enter
-    //
-    // Log format (designed for easy parsing by Claude Code):
-    //   [TIMESTAMP] [PROJECT] EVENT: message (pid=PID)
-    //
-    // Example:
-    //   [2025-12-08T12:02:18] [api] START: Process started (pid=12345)
-    //   [2025-12-08T12:05:32] [api] RESTART: Process restarted after file change
-    // (pid=12346)   [2025-12-08T12:05:32] [api] EXIT: Process exited with code
-    // 0
-    let wrap_command = |name: &str, cmd: &str| -> String {
-        let dev_log = ".meta/logs/dev.log";
-        let project_log = format!(".meta/logs/{}.log", name);
-        // Bacon handles its own logging via tee in bacon.toml, so skip the outer tee
-        // to avoid capturing TUI escape codes. Other tools (turbo, cargo) use the tee
-        // wrapper.
-        let is_bacon = cmd.contains("bacon");
-        let run_cmd = if is_bacon {
-            // Bacon handles logging internally - just run the command
-            format!("{cmd}; EXIT_CODE=$?")
-        } else {
-            // Other tools: capture stdout/stderr to project log via tee
-            format!("{cmd} 2>&1 | tee -a \"$PROJECT_LOG\"; EXIT_CODE=${{PIPESTATUS[0]}}")
-        };
-        format!(
-            r#"DEV_LOG="{dev_log}"; \
-PROJECT_LOG="{project_log}"; \
+/// Wrap a dev command for its tmux pane in a shell that:
+/// 1. Logs process start/restart events to .meta/logs/dev.log
+/// 2. Restarts the command according to its restart policy, with
+///    exponential backoff and a limit per time window
+/// 3. Keeps the pane alive after the command exits
+/// 4. Allows manual restart with Enter
+///
+/// Log format (designed for easy parsing by Claude Code):
+///   [TIMESTAMP] [PROJECT] EVENT: message (pid=PID)
+///
+/// Example:
+///   [2025-12-08T12:02:18] [api] START: Process started (pid=12345)
+///   [2025-12-08T12:05:32] [api] EXIT: Process exited with code 1
+///   [2025-12-08T12:05:32] [api] RESTART: Restarting after exit code 1 (attempt 1/5, backoff 1s)
+fn pane_command(name: &str, command: &DevCommand) -> String {
+    let cmd = command.shell_line();
+    let dev_log = ".meta/logs/dev.log";
+    // Task environment ([env], env, env_file), exported before anything runs
+    let exports: String = command
+        .env
+        .iter()
+        .map(|(key, value)| format!("export {}={}; \\\n", key, shell_words::quote(value)))
+        .collect();
+    // The command runs in a subshell so its `cd` does not move the
+    // wrapper away from the workspace root (where the log paths point).
+    // Its output is written to the project log by the pane's `meta
+    // log-sink`, see launch_tmux_session.
+    let run_cmd = format!("({cmd}); EXIT_CODE=$?");
+    format!(
+        r#"{exports}DEV_LOG="{dev_log}"; \
 PROJECT="{name}"; \
 log_event() {{ echo "[$(date -u +%Y-%m-%dT%H:%M:%S)] [$PROJECT] $1" >> "$DEV_LOG"; }}; \
-rotate_log() {{ \
-  if [ -f "$PROJECT_LOG" ]; then \
-    SIZE=$(stat -f%z "$PROJECT_LOG" 2>/dev/null || stat -c%s "$PROJECT_LOG" 2>/dev/null || echo 0); \
-    if [ "$SIZE" -gt 10485760 ]; then \
-      mv "$PROJECT_LOG" "$PROJECT_LOG.1"; \
-    fi; \
-  fi; \
-}}; \
 run_with_logging() {{ \
-  rotate_log; \
   log_event "START: Process started (pid=$$)"; \
   {run_cmd}; \
   log_event "EXIT: Process exited with code $EXIT_CODE"; \
   return $EXIT_CODE; \
 }}; \
+RESTART_POLICY="{restart}"; \
+MAX_RESTARTS={max_restarts}; \
+RESTART_WINDOW={restart_window}; \
+reset_restarts() {{ ATTEMPT=0; BACKOFF=1; WINDOW_START=$(date +%s); }}; \
+reset_restarts; \
 run_with_logging; \
 while true; do \
+  if [ "$RESTART_POLICY" = "always" ] || {{ [ "$RESTART_POLICY" = "on-failure" ] && [ "$EXIT_CODE" -ne 0 ]; }}; then \
+if [ $(($(date +%s) - WINDOW_START)) -gt "$RESTART_WINDOW" ]; then reset_restarts; fi; \
+if [ "$ATTEMPT" -lt "$MAX_RESTARTS" ]; then \
+  ATTEMPT=$((ATTEMPT + 1)); \
+  log_event "RESTART: Restarting after exit code $EXIT_CODE (attempt $ATTEMPT/$MAX_RESTARTS, backoff ${{BACKOFF}}s)"; \
+  sleep "$BACKOFF"; \
+  BACKOFF=$((BACKOFF * 2)); \
+  if [ "$BACKOFF" -gt {max_backoff} ]; then BACKOFF={max_backoff}; fi; \
+  run_with_logging; \
+  continue; \
+fi; \
+log_event "STOPPED: Restart limit reached ($MAX_RESTARTS restarts in ${{RESTART_WINDOW}}s)"; \
+  fi; \
   echo '\n✓ Process exited. Press Enter to restart or Ctrl+C to close.'; \
   read -r; \
   log_event "RESTART: Manual restart triggered"; \
+  reset_restarts; \
   run_with_logging; \
 done"#,
-            dev_log = dev_log,
-            project_log = project_log,
-            name = name,
-            run_cmd = run_cmd
-        )
-    };
+        exports = exports,
+        restart = command.restart.as_str(),
+        max_restarts = command.max_restarts,
+        restart_window = command.restart_window,
+        max_backoff = supervisor::MAX_BACKOFF_SECS,
+        dev_log = dev_log,
+        name = name,
+        run_cmd = run_cmd
+    )
+}
 
-    // Create new session with first command
-    let first_cmd = &commands[0];
-    let wrapped_first = wrap_command(&first_cmd.0, &first_cmd.1);
-    Command::new("tmux")
-        .args(["new-session", "-d", "-s", &session_name, "-n", &first_cmd.0])
-        .arg(&wrapped_first)
-        .output()
-        .await?;
+/// Start one tmux pane per dev command.
+///
+/// Normally any existing session is replaced. With `add`, panes are added to
+/// the running session instead, skipping projects that already have a pane
+/// (matched by title).
+async fn launch_tmux_session(
+    commands: &[(String, DevCommand)],
+    detach: bool,
+    add: bool,
+) -> Result<()> {
+    let session_name = get_session_name();
 
-    // Add remaining commands as new panes
-    for (name, cmd) in commands.iter().skip(1) {
-        let wrapped_cmd = wrap_command(name, cmd);
-        Command::new("tmux")
-            .args(["split-window", "-t", &session_name, "-h"])
-            .arg(&wrapped_cmd)
+    let mut session_exists = false;
+    let mut existing: Vec<String> = vec![];
+    if add {
+        session_exists = Command::new("tmux")
+            .args(["has-session", "-t", &session_name])
+            .output()
+            .await
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        if session_exists {
+            existing = get_tmux_panes(&session_name)
+                .await?
+                .into_iter()
+                .map(|pane| pane.title)
+                .collect();
+        } else {
+            println!("ℹ️  No active {} session, starting a new one.\n", session_name);
+        }
+    } else {
+        // Kill existing session if it exists
+        let _ = Command::new("tmux")
+            .args(["kill-session", "-t", &session_name])
+            .output()
+            .await;
+    }
+
+    ports::check_available(commands.iter().filter(|(name, _)| !existing.contains(name))).await?;
+
+    // Ensure log directory exists
+    let log_dir = std::path::Path::new(".meta/logs");
+    if !log_dir.exists() {
+        std::fs::create_dir_all(log_dir)?;
+    }
+    // Each pane's log sink runs this binary from the workspace root, where it
+    // finds the `[logs]` config
+    let cwd = std::env::current_dir()?;
+    let meta_exe = std::env::current_exe()?;
+
+    // Panes are created one at a time so each project can wait for the
+    // projects in its `after` list to become ready
+    let mut readiness = ready::Readiness::default();
+    let mut added = vec![];
+    for (name, command) in commands {
+        if existing.contains(name) {
+            println!(
+                "  ✓ {} already running in {} (use 'meta restart {}' to restart it)",
+                name, session_name, name
+            );
+            continue;
+        }
+        readiness.wait_for(&command.after).await;
+        readiness.starting(name, command.ready.clone());
+
+        // The pane holds the command until its output is piped to the log
+        // sink, so nothing it prints first is lost
+        let logging = format!("{}-{}-logging", session_name, name);
+        let wrapped = format!(
+            "tmux wait-for {}; {}",
+            shell_words::quote(&logging),
+            pane_command(name, command)
+        );
+        let mut create = Command::new("tmux");
+        if !session_exists {
+            create.args(["new-session", "-d", "-s", &session_name, "-n", name]);
+            session_exists = true;
+        } else {
+            create.args(["split-window", "-t", &session_name, "-h"]);
+        }
+        let output = create
+            .args(["-P", "-F", "#{pane_id}"])
+            .arg(&wrapped)
             .output()
             .await?;
+        let pane_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || pane_id.is_empty() {
+            anyhow::bail!(
+                "Failed to create tmux pane for {}: {}",
+                name,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
 
         Command::new("tmux")
-            .args(["select-pane", "-t", &session_name, "-T", name])
+            .args(["select-pane", "-t", &pane_id, "-T", name])
             .output()
             .await?;
-    }
 
-    // Set up tmux pipe-pane for all panes to capture logs.
-    // Uses sed to strip ANSI escape codes (colors, cursor movement, erase commands)
-    // so logs are readable even from TUI tools like bacon.
-    // The sed pattern handles: SGR (m), cursor position (H/G), erase (J/K),
-    // and other CSI sequences.
-    for (i, (name, _)) in commands.iter().enumerate() {
-        let log_path = format!(".meta/logs/{}.log", name);
-        let pane_target = format!("{}:{}.{}", session_name, 0, i);
+        // Capture the pane's output to its project log. `meta log-sink`
+        // strips escape codes (colors, cursor movement, erase commands) so
+        // logs are readable even from TUI tools like bacon, and rotates the
+        // log per `[logs]` while the session runs.
+        let sink_env: Vec<String> = crate::config::child_env()
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, shell_words::quote(&value)))
+            .collect();
         let pipe_cmd = format!(
-            "exec cat - | sed -l 's/\x1b\\[[0-9;]*[mGKHJsu]//g' >> '{}'",
-            log_path
+            "cd {} && {} exec {} log-sink {}",
+            shell_words::quote(&cwd.to_string_lossy()),
+            sink_env.join(" "),
+            shell_words::quote(&meta_exe.to_string_lossy()),
+            shell_words::quote(name)
         );
         Command::new("tmux")
-            .args(["pipe-pane", "-t", &pane_target, &pipe_cmd])
+            .args(["pipe-pane", "-t", &pane_id, &pipe_cmd])
             .output()
             .await?;
+        Command::new("tmux")
+            .args(["wait-for", "-S", &logging])
+            .output()
+            .await?;
+
+        // Tile the panes evenly, which also leaves room for the next split
+        Command::new("tmux")
+            .args(["select-layout", "-t", &session_name, "tiled"])
+            .output()
+            .await?;
+        added.push(name.as_str());
     }
 
-    // Tile the panes evenly
-    Command::new("tmux")
-        .args(["select-layout", "-t", &session_name, "tiled"])
-        .output()
-        .await?;
+    if add && !existing.is_empty() {
+        if added.is_empty() {
+            println!("\n✅ Nothing to add, all projects already running.");
+        } else {
+            println!(
+                "\n✅ Added {} to tmux session '{}'.",
+                added.join(", "),
+                session_name
+            );
+        }
+        if detach {
+            println!("   Attach:  tmux attach -t {}", session_name);
+            println!("   Status:  meta status");
+            return Ok(());
+        }
+    }
 
     if detach {
         // Detached mode: session is running in background
@@ -1028,55 +1125,36 @@ done"#,
     Ok(())
 }
 
-pub async fn build(config: &Config, _prod: bool, projects: Option<Vec<String>>) -> Result<()> {
+pub async fn build(
+    config: &Config,
+    _prod: bool,
+    projects: Option<Vec<String>>,
+    affected_base: Option<&str>,
+    options: &RunOptions,
+) -> Result<()> {
     let projects_to_build = get_projects_to_run(config, projects)?;
 
     println!("🔨 Building projects...\n");
 
-    for (name, project) in projects_to_build {
-        if let Some(build_task) = project.tasks.get("build") {
-            let tool = config
-                .tools
-                .get(&build_task.tool)
-                .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", build_task.tool))?;
-
-            let adapter = ToolAdapter::new(build_task.tool.clone(), tool.command.clone());
-
-            println!("  → Building {} ({})", name, build_task.tool);
-
-            let parts: Vec<&str> = build_task.command.split_whitespace().collect();
-
-            // Use project path for execution
-            let project_path = std::path::Path::new(&project.path);
-            adapter.execute_in(&parts, project_path).await?;
-        }
-    }
+    let names: Vec<String> = projects_to_build.into_keys().collect();
+    let names = filter_affected(config, names, affected_base).await?;
+    run_task_graph(config, "build", &names, options).await?;
 
     println!("\n✅ Build complete!\n");
     Ok(())
 }
 
-pub async fn test(config: &Config, _watch: bool) -> Result<()> {
+pub async fn test(
+    config: &Config,
+    _watch: bool,
+    affected_base: Option<&str>,
+    options: &RunOptions,
+) -> Result<()> {
     println!("🧪 Running tests...\n");
 
-    for (name, project) in &config.projects {
-        if let Some(test_task) = project.tasks.get("test") {
-            let tool = config
-                .tools
-                .get(&test_task.tool)
-                .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", test_task.tool))?;
-
-            let adapter = ToolAdapter::new(test_task.tool.clone(), tool.command.clone());
-
-            println!("  → Testing {} ({})", name, test_task.tool);
-
-            let parts: Vec<&str> = test_task.command.split_whitespace().collect();
-
-            // Use project path for execution
-            let project_path = std::path::Path::new(&project.path);
-            adapter.execute_in(&parts, project_path).await?;
-        }
-    }
+    let names: Vec<String> = config.projects.keys().cloned().collect();
+    let names = filter_affected(config, names, affected_base).await?;
+    run_task_graph(config, "test", &names, options).await?;
 
     println!("\n✅ Tests complete!\n");
     Ok(())
@@ -1086,41 +1164,66 @@ pub async fn run_task(
     config: &Config,
     task_name: &str,
     projects: Option<Vec<String>>,
+    affected_base: Option<&str>,
+    options: &RunOptions,
 ) -> Result<()> {
     let projects_to_run = get_projects_to_run(config, projects)?;
 
     println!("🚀 Running task '{}'...\n", task_name);
 
-    for (name, project) in projects_to_run {
-        if let Some(task) = project.tasks.get(task_name) {
-            let tool = config
-                .tools
-                .get(&task.tool)
-                .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", task.tool))?;
+    let names: Vec<String> = projects_to_run.keys().cloned().collect();
+    let names = filter_affected(config, names, affected_base).await?;
 
-            let adapter = ToolAdapter::new(task.tool.clone(), tool.command.clone());
-
-            println!("  → {} ({})", name, task.tool);
-
-            let parts: Vec<&str> = task.command.split_whitespace().collect();
-
-            // Use project path for execution
-            let project_path = std::path::Path::new(&project.path);
-            adapter.execute_in(&parts, project_path).await?;
-        } else {
+    for name in &names {
+        if !config.projects[name].tasks.contains_key(task_name) {
             println!("  ⊘ {} (task '{}' not defined, skipping)", name, task_name);
         }
     }
 
+    run_task_graph(config, task_name, &names, options).await?;
+
     println!("\n✅ Task '{}' complete!\n", task_name);
     Ok(())
 }
 
+/// Run `task_name` for each project, plus everything it `depends_on`,
+/// in dependency order with up to `options.jobs` tasks at once
+async fn run_task_graph(
+    config: &Config,
+    task_name: &str,
+    projects: &[String],
+    options: &RunOptions,
+) -> Result<()> {
+    let graph = TaskGraph::build(config, task_name, projects)?;
+    runner::run_graph(config, &graph, options).await
+}
+
+/// With `--affected`, keep only the projects touched by changes since `base`
+/// (or depending on a project that was)
+async fn filter_affected(
+    config: &Config,
+    names: Vec<String>,
+    base: Option<&str>,
+) -> Result<Vec<String>> {
+    let Some(base) = base else {
+        return Ok(names);
+    };
+
+    let affected = affected::affected_projects(config, base).await?;
+    let names: Vec<String> = names.into_iter().filter(|n| affected.contains(n)).collect();
+    if names.is_empty() {
+        println!("  No projects affected by changes since {}", base);
+    } else {
+        println!("  Affected since {}: {}\n", base, names.join(", "));
+    }
+    Ok(names)
+}
+
 fn get_projects_to_run(
     config: &Config,
     projects: Option<Vec<String>>,
-) -> Result<HashMap<String, &crate::config::ProjectConfig>> {
-    let mut result = HashMap::new();
+) -> Result<BTreeMap<String, &crate::config::ProjectConfig>> {
+    let mut result = BTreeMap::new();
 
     match projects {
         Some(names) => {
@@ -1216,242 +1319,6 @@ pub fn is_library_crate(project_path: &str) -> bool {
     false
 }
 
-/// Validate that a bacon-based task has a valid bacon.toml configuration
-/// Returns true if `.mcp.json` at `path` contains an mcpServers entry whose
-/// `command` field is `"docker"`. Missing/invalid files return false.
-pub fn mcp_json_references_docker(path: &std::path::Path) -> bool {
-    let Ok(contents) = std::fs::read_to_string(path) else {
-        return false;
-    };
-    let Ok(value) = serde_json::from_str::<serde_json::Value>(&contents) else {
-        return false;
-    };
-    value
-        .get("mcpServers")
-        .and_then(|s| s.as_object())
-        .map(|servers| {
-            servers
-                .values()
-                .any(|entry| entry.get("command").and_then(|c| c.as_str()) == Some("docker"))
-        })
-        .unwrap_or(false)
-}
-
-pub fn validate_bacon_config(project_path: &str, command: &str) -> Vec<String> {
-    let mut warnings = Vec::new();
-
-    // Check for bacon.toml in the project directory
-    let bacon_toml = std::path::Path::new(project_path).join("bacon.toml");
-    let bacon_prefs = std::path::Path::new(project_path).join(".bacon.toml");
-
-    if !bacon_toml.exists() && !bacon_prefs.exists() {
-        // Also check workspace root
-        if let Some(ws_root) = detect_cargo_workspace(project_path) {
-            let ws_bacon = std::path::Path::new(&ws_root).join("bacon.toml");
-            let ws_bacon_prefs = std::path::Path::new(&ws_root).join(".bacon.toml");
-            if !ws_bacon.exists() && !ws_bacon_prefs.exists() {
-                warnings.push(format!(
-                    "bacon.toml not found in '{}' or workspace root — bacon may fail silently",
-                    project_path
-                ));
-            }
-        } else {
-            warnings.push(format!(
-                "bacon.toml not found in '{}' — bacon may fail silently",
-                project_path
-            ));
-        }
-    }
-
-    // Check if the referenced job exists in bacon.toml
-    let job_name = command.split_whitespace().next().unwrap_or(command);
-    if bacon_toml.exists() {
-        if let Ok(content) = std::fs::read_to_string(&bacon_toml) {
-            let job_header = format!("[jobs.{}]", job_name);
-            if !content.contains(&job_header) && !content.contains(&format!("[jobs.{job_name}]")) {
-                // Built-in jobs don't need to be in bacon.toml
-                let builtin_jobs = ["check", "clippy", "test", "doc", "run", "run-long"];
-                if !builtin_jobs.contains(&job_name) {
-                    warnings.push(format!(
-                        "job '{}' not found in bacon.toml — bacon will fail",
-                        job_name
-                    ));
-                }
-            }
-        }
-    }
-
-    warnings
-}
-
-pub async fn doctor(config: &Config) -> Result<()> {
-    println!("🏥 Meta Doctor - Configuration Diagnostics\n");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-
-    let mut errors = 0;
-    let mut warnings = 0;
-
-    // Check meta.toml exists and is valid
-    println!("📋 Configuration File:");
-    println!("  ✓ meta.toml loaded successfully");
-    println!("  ✓ Workspace: {}\n", config.workspace.name);
-
-    // Check tools
-    println!("🔧 Tool Availability:");
-    for (tool_name, tool_config) in &config.tools {
-        if !tool_config.enabled {
-            println!("  ⊘ {} (disabled)", tool_name);
-            continue;
-        }
-
-        match tokio::process::Command::new(&tool_config.command)
-            .arg("--version")
-            .output()
-            .await
-        {
-            Ok(output) if output.status.success() => {
-                let version_str = String::from_utf8_lossy(&output.stdout);
-                let version = version_str.lines().next().unwrap_or("unknown").trim();
-                println!("  ✓ {} → {} ({})", tool_name, tool_config.command, version);
-            }
-            Ok(_) => {
-                println!(
-                    "  ⚠ {} → {} (found but version check failed)",
-                    tool_name, tool_config.command
-                );
-                warnings += 1;
-            }
-            Err(_) => {
-                println!("  ✗ {} → {} (NOT FOUND)", tool_name, tool_config.command);
-                errors += 1;
-            }
-        }
-    }
-
-    // Check tmux for multi-process support
-    println!("\n🖥️  Terminal Multiplexer:");
-    match tokio::process::Command::new("tmux")
-        .arg("-V")
-        .output()
-        .await
-    {
-        Ok(output) if output.status.success() => {
-            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            println!("  ✓ tmux → {} (multi-process dev mode available)", version);
-        }
-        _ => {
-            println!("  ⚠ tmux not found (install for 'meta dev' multi-process mode)");
-            println!("    Install: brew install tmux (macOS) or apt install tmux (Linux)");
-            warnings += 1;
-        }
-    }
-
-    // Check MCP integration (docker required if .mcp.json uses it)
-    let mcp_path = std::path::Path::new(".mcp.json");
-    if mcp_path.exists() && mcp_json_references_docker(mcp_path) {
-        println!("\n🐳 MCP Integration:");
-        match tokio::process::Command::new("docker")
-            .arg("--version")
-            .output()
-            .await
-        {
-            Ok(output) if output.status.success() => {
-                let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                println!("  ✓ docker → {} (.mcp.json references docker)", version);
-            }
-            _ => {
-                println!("  ⚠ .mcp.json references docker but docker not found on PATH");
-                println!("    Install Docker to use mcp-log-server, or re-run 'meta init --no-mcp' to opt out");
-                warnings += 1;
-            }
-        }
-    }
-
-    // Check projects
-    println!("\n📦 Projects ({}):", config.projects.len());
-    for (name, project) in &config.projects {
-        let path = std::path::Path::new(&project.path);
-        if path.exists() {
-            println!("  ✓ {} → {} ({})", name, project.path, project.project_type);
-
-            // Check if project has dev task (per-project, not path-based)
-            if project_has_task(project, "dev") {
-                if !project.dev_default {
-                    println!("    • dev task configured (excluded from default 'meta dev', use -p to include)");
-                } else {
-                    println!("    • dev task configured");
-                }
-
-                // Issue #3: validate bacon config if tool is bacon
-                if let Some(dev_task) = project.tasks.get("dev") {
-                    if dev_task.tool == "bacon" {
-                        let bacon_warnings =
-                            validate_bacon_config(&project.path, &dev_task.command);
-                        for warning in bacon_warnings {
-                            println!("    ⚠ {}", warning);
-                            warnings += 1;
-                        }
-                    }
-                }
-            }
-        } else {
-            println!("  ✗ {} → {} (PATH NOT FOUND)", name, project.path);
-            errors += 1;
-        }
-    }
-
-    // Check for common issues
-    println!("\n🔍 Configuration Validation:");
-
-    // Validate turborepo commands
-    for (name, project) in &config.projects {
-        if let Some(dev_task) = project.tasks.get("dev") {
-            if dev_task.tool == "turborepo" {
-                let cmd = &dev_task.command;
-                if !cmd.starts_with("run ") {
-                    println!("  ⚠ {} dev task should start with 'run': '{}'", name, cmd);
-                    println!("    Suggested: 'run dev --filter=...'");
-                    warnings += 1;
-                }
-                if !cmd.contains("--filter=") {
-                    println!("  ⚠ {} turbo task missing --filter flag: '{}'", name, cmd);
-                    warnings += 1;
-                }
-            }
-        }
-    }
-
-    // Summary
-    println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("\n📊 Summary:");
-
-    if errors == 0 && warnings == 0 {
-        println!("  🎉 All checks passed! Meta is ready to use.");
-        println!("\n💡 Quick Start:");
-        println!("  • Run 'meta dev' to start all development servers");
-        println!("  • Run 'meta dev --projects api' to start specific project");
-        println!("  • Run 'meta run <task>' to execute any task across projects");
-    } else {
-        if errors > 0 {
-            println!("  ✗ {} error(s) found - these must be fixed", errors);
-        }
-        if warnings > 0 {
-            println!(
-                "  ⚠ {} warning(s) - meta will work but with reduced functionality",
-                warnings
-            );
-        }
-    }
-
-    println!();
-
-    if errors > 0 {
-        anyhow::bail!("Configuration has errors");
-    }
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1668,186 +1535,18 @@ version = "0.1.0"
         assert!(ws.is_none());
     }
 
-    // === Issue #3: bacon config validation ===
-
-    #[test]
-    fn test_validate_bacon_config_warns_when_no_bacon_toml() {
-        let temp_dir = tempfile::TempDir::new().unwrap();
-        let project_path = temp_dir.path().to_string_lossy().to_string();
-
-        let warnings = validate_bacon_config(&project_path, "run-long");
-        assert!(!warnings.is_empty());
-        assert!(warnings[0].contains("bacon.toml not found"));
-    }
-
-    #[test]
-    fn test_validate_bacon_config_no_warning_when_bacon_toml_exists() {
-        let temp_dir = tempfile::TempDir::new().unwrap();
-        let project_path = temp_dir.path();
-
-        // Create bacon.toml with the job
-        std::fs::write(
-            project_path.join("bacon.toml"),
-            r#"[jobs.run-long]
-command = ["cargo", "run"]
-"#,
-        )
-        .unwrap();
-
-        let warnings =
-            validate_bacon_config(&project_path.to_string_lossy(), "run-long");
-        assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
-    }
-
-    #[test]
-    fn test_validate_bacon_config_builtin_jobs_no_warning() {
-        let temp_dir = tempfile::TempDir::new().unwrap();
-        let project_path = temp_dir.path();
-
-        // Create empty bacon.toml (builtin jobs don't need config)
-        std::fs::write(project_path.join("bacon.toml"), "").unwrap();
-
-        // "run-long" is a builtin job
-        let warnings =
-            validate_bacon_config(&project_path.to_string_lossy(), "run-long");
-        assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
-    }
-
-    // === mcp_json_references_docker ===
-
-    #[test]
-    fn test_mcp_references_docker_true_when_command_is_docker() {
-        let tmp = tempfile::TempDir::new().unwrap();
-        let path = tmp.path().join(".mcp.json");
-        std::fs::write(
-            &path,
-            r#"{"mcpServers":{"mcp-log-server":{"command":"docker","args":[]}}}"#,
-        )
-        .unwrap();
-        assert!(mcp_json_references_docker(&path));
-    }
-
-    #[test]
-    fn test_mcp_references_docker_false_when_no_docker_entry() {
-        let tmp = tempfile::TempDir::new().unwrap();
-        let path = tmp.path().join(".mcp.json");
-        std::fs::write(
-            &path,
-            r#"{"mcpServers":{"other":{"command":"npx","args":[]}}}"#,
-        )
-        .unwrap();
-        assert!(!mcp_json_references_docker(&path));
-    }
-
-    #[test]
-    fn test_mcp_references_docker_false_when_file_missing() {
-        let tmp = tempfile::TempDir::new().unwrap();
-        assert!(!mcp_json_references_docker(&tmp.path().join("absent.json")));
-    }
-
-    #[test]
-    fn test_mcp_references_docker_false_when_malformed() {
-        let tmp = tempfile::TempDir::new().unwrap();
-        let path = tmp.path().join(".mcp.json");
-        std::fs::write(&path, "not json").unwrap();
-        assert!(!mcp_json_references_docker(&path));
-    }
-
-    // === Existing: parse_etime ===
-
-    #[test]
-    fn test_parse_etime_minutes_seconds() {
-        assert_eq!(parse_etime("02:30"), Some(150));
-    }
-
-    #[test]
-    fn test_parse_etime_hours_minutes_seconds() {
-        assert_eq!(parse_etime("01:02:30"), Some(3750));
-    }
-
-    #[test]
-    fn test_parse_etime_days() {
-        assert_eq!(parse_etime("2-01:02:30"), Some(2 * 86400 + 3750));
-    }
-
-    #[test]
-    fn test_parse_etime_empty() {
-        assert_eq!(parse_etime(""), None);
-    }
-
-    // === Issue #4: process tree building ===
-
-    #[test]
-    fn test_build_process_tree_basic() {
-        let ps_output = "  PID  PPID\n    1     0\n  100     1\n  200   100\n  300   100\n  400   200\n";
-        let tree = build_process_tree(ps_output);
-
-        assert_eq!(tree.get(&0), Some(&vec![1]));
-        assert_eq!(tree.get(&1), Some(&vec![100]));
-        assert!(tree.get(&100).unwrap().contains(&200));
-        assert!(tree.get(&100).unwrap().contains(&300));
-        assert_eq!(tree.get(&200), Some(&vec![400]));
-    }
-
-    #[test]
-    fn test_build_process_tree_empty() {
-        let ps_output = "  PID  PPID\n";
-        let tree = build_process_tree(ps_output);
-        assert!(tree.is_empty());
-    }
-
-    #[test]
-    fn test_collect_descendants_full_tree() {
-        let ps_output = "  PID  PPID\n    1     0\n  100     1\n  200   100\n  300   100\n  400   200\n";
-        let tree = build_process_tree(ps_output);
-        let mut descendants = collect_descendants(&tree, 100);
-        descendants.sort();
-        assert_eq!(descendants, vec![200, 300, 400]);
-    }
-
-    #[test]
-    fn test_collect_descendants_leaf_node() {
-        let ps_output = "  PID  PPID\n    1     0\n  100     1\n  200   100\n";
-        let tree = build_process_tree(ps_output);
-        let descendants = collect_descendants(&tree, 200);
-        assert!(descendants.is_empty());
-    }
-
-    #[test]
-    fn test_collect_descendants_nonexistent_pid() {
-        let ps_output = "  PID  PPID\n    1     0\n  100     1\n";
-        let tree = build_process_tree(ps_output);
-        let descendants = collect_descendants(&tree, 999);
-        assert!(descendants.is_empty());
-    }
-
-    // Simulates the bacon process tree:
-    // shell(100) → bacon(200) → cargo(300) → binary(400)
     #[test]
-    fn test_collect_descendants_bacon_tree() {
-        let ps_output =
-            "  PID  PPID\n  100     1\n  200   100\n  300   200\n  400   300\n";
-        let tree = build_process_tree(ps_output);
-        let descendants = collect_descendants(&tree, 100);
-        // Should find all: bacon, cargo, binary
-        assert_eq!(descendants.len(), 3);
-        assert!(descendants.contains(&200)); // bacon
-        assert!(descendants.contains(&300)); // cargo
-        assert!(descendants.contains(&400)); // binary
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(42), "42s");
+        assert_eq!(format_uptime(303), "5m 3s");
+        assert_eq!(format_uptime(4_980), "1h 23m");
+        assert_eq!(format_uptime(2 * 86_400 + 3 * 3_600), "2d 3h");
     }
 
-    // When shell exits and children are reparented to launchd (pid 1),
-    // the old shell PID has no children in the tree
     #[test]
-    fn test_collect_descendants_orphaned_to_launchd() {
-        // Shell (pid 100) exited. bacon (200) and cargo (300) reparented to pid 1.
-        let ps_output = "  PID  PPID\n    1     0\n  200     1\n  300   200\n  400   300\n";
-        let tree = build_process_tree(ps_output);
-        // Looking for descendants of the dead shell PID 100 — finds nothing
-        let descendants = collect_descendants(&tree, 100);
-        assert!(descendants.is_empty());
-        // But descendants of pid 1 would include the reparented processes
-        let launchd_children = collect_descendants(&tree, 1);
-        assert!(launchd_children.contains(&200));
+    fn test_format_memory() {
+        assert_eq!(format_memory(512), "512K");
+        assert_eq!(format_memory(12_595), "12.3M");
+        assert_eq!(format_memory(1_258_291), "1.2G");
     }
 }
@@ -0,0 +1,194 @@
+//! Dev task ports: checking they are free before launch, picking free ones
+//! for `port = "auto"` and naming the process that holds a taken one
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    net::TcpListener,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use super::{
+    process::{ProcessInfo, ProcessTable},
+    DevCommand,
+};
+
+/// How long a taken port may take to be released, e.g. by a session that
+/// `meta dev` just replaced
+const RELEASE_WAIT: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Ports picked for `port = "auto"` by the current dev session
+const AUTO_PORTS_FILE: &str = ".meta/ports.json";
+
+/// Whether something is listening on `port`. Binds on every address a dev
+/// server commonly listens on, since some platforms allow binding the
+/// wildcard address while a specific one is taken.
+pub fn in_use(port: u16) -> bool {
+    ["0.0.0.0", "127.0.0.1", "::"].iter().any(|host| {
+        matches!(
+            TcpListener::bind((*host, port)),
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse
+        )
+    })
+}
+
+/// A port nothing is listening on, for `port = "auto"`
+fn free_port() -> Result<u16> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// The port each `port = "auto"` project got for a dev session, so that
+/// `meta restart` and `meta dev --add` hand it the same one and whatever
+/// points at it (a browser tab, a proxy, a ready probe) keeps working
+pub struct AutoPorts {
+    path: PathBuf,
+    ports: BTreeMap<String, u16>,
+    changed: bool,
+}
+
+impl AutoPorts {
+    /// No ports picked yet, for a fresh `meta dev`
+    pub fn fresh() -> Self {
+        Self::at(PathBuf::from(AUTO_PORTS_FILE), false)
+    }
+
+    /// The ports the running session picked
+    pub fn session() -> Self {
+        Self::at(PathBuf::from(AUTO_PORTS_FILE), true)
+    }
+
+    fn at(path: PathBuf, recorded: bool) -> Self {
+        let ports = recorded
+            .then(|| fs::read_to_string(&path).ok())
+            .flatten()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            ports,
+            changed: !recorded,
+        }
+    }
+
+    /// `project`'s port, picking a free one the first time
+    pub fn get(&mut self, project: &str) -> Result<u16> {
+        if let Some(port) = self.ports.get(project) {
+            return Ok(*port);
+        }
+        let port = free_port()?;
+        self.ports.insert(project.to_string(), port);
+        self.changed = true;
+        Ok(port)
+    }
+
+    /// Record the session's ports if any were picked
+    pub fn save(&self) -> Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.ports)?)?;
+        Ok(())
+    }
+}
+
+/// Fail before anything is launched if a dev task's port is taken, naming
+/// the process that holds it
+pub async fn check_available<'a>(
+    commands: impl IntoIterator<Item = &'a (String, DevCommand)>,
+) -> Result<()> {
+    let mut conflicts = 0;
+    for (name, command) in commands {
+        let Some(port) = command.port else {
+            continue;
+        };
+        if !wait_until_free(port).await {
+            conflicts += 1;
+            match owner(port).await {
+                Some(owner) => println!(
+                    "✗ Port {} ({}) is already in use by pid {}: {}",
+                    port, name, owner.pid, owner.command
+                ),
+                None => println!("✗ Port {} ({}) is already in use", port, name),
+            }
+        }
+    }
+    if conflicts > 0 {
+        println!("   Stop that process, change `port` in meta.toml, or use `port = \"auto\"`.");
+        anyhow::bail!("{} dev port(s) already in use", conflicts);
+    }
+    Ok(())
+}
+
+async fn wait_until_free(port: u16) -> bool {
+    let started = Instant::now();
+    while in_use(port) {
+        if started.elapsed() >= RELEASE_WAIT {
+            return false;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    true
+}
+
+/// The process listening on `port`. On Linux only processes whose
+/// /proc/<pid>/fd is readable (usually the current user's) can be found.
+pub async fn owner(port: u16) -> Option<ProcessInfo> {
+    ProcessTable::snapshot().await.listening_on(port).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_use_and_owner_of_bound_port() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(in_use(port));
+
+        #[cfg(target_os = "linux")]
+        assert_eq!(owner(port).await.map(|o| o.pid), Some(std::process::id()));
+
+        drop(listener);
+        assert!(!in_use(port));
+    }
+
+    #[test]
+    fn test_free_port_is_not_in_use() {
+        let port = free_port().unwrap();
+        assert!(port > 0);
+        assert!(!in_use(port));
+    }
+
+    #[test]
+    fn test_auto_ports_are_kept_for_the_session() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(".meta/ports.json");
+
+        let mut fresh = AutoPorts::at(path.clone(), false);
+        let api = fresh.get("api").unwrap();
+        assert_eq!(fresh.get("api").unwrap(), api);
+        fresh.save().unwrap();
+
+        // Restarts and --add reuse the recorded port and record new projects
+        let mut session = AutoPorts::at(path.clone(), true);
+        assert_eq!(session.get("api").unwrap(), api);
+        let web = session.get("web").unwrap();
+        session.save().unwrap();
+        let mut session = AutoPorts::at(path.clone(), true);
+        assert_eq!(session.get("web").unwrap(), web);
+
+        // A fresh session ignores them
+        let fresh = AutoPorts::at(path.clone(), false);
+        assert!(fresh.ports.is_empty());
+        fresh.save().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().trim(), "{}");
+    }
+}
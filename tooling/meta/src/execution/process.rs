@@ -0,0 +1,637 @@
+//! Native process inspection for `meta status`: one snapshot of the process
+//! table with memory, CPU, threads, listening ports and command lines.
+//!
+//! Reads /proc where it exists (Linux) and falls back to a single `ps` call,
+//! plus `lsof` for listening ports, elsewhere.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use tokio::process::Command;
+
+use super::events::format_timestamp;
+
+/// Unit of the CPU and start times in /proc/<pid>/stat (`USER_HZ`), which is
+/// 100 on every mainstream architecture
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+/// TCP socket state for listening sockets in /proc/net/tcp{,6}
+const TCP_LISTEN: &str = "0A";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    /// Full command line
+    pub command: String,
+    /// Resident memory in KiB
+    pub rss_kb: u64,
+    /// CPU time used over the process's lifetime as a percentage of one
+    /// core, like `ps`
+    pub cpu_percent: f64,
+    /// Thread count, where the platform reports it
+    pub threads: Option<u32>,
+    /// TCP ports the process is listening on
+    pub ports: Vec<u16>,
+    pub uptime_seconds: u64,
+    /// Exited but not yet reaped by its parent
+    #[serde(skip)]
+    zombie: bool,
+}
+
+impl ProcessInfo {
+    /// Start time as `YYYY-MM-DDTHH:MM:SS` (UTC), like dev.log timestamps
+    pub fn started_at(&self) -> String {
+        format_timestamp(self.started_secs())
+    }
+
+    /// Start time in seconds since the epoch
+    pub fn started_secs(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        now.saturating_sub(self.uptime_seconds)
+    }
+}
+
+/// Totals over a process tree
+#[derive(Debug, Default, PartialEq)]
+pub struct TreeUsage {
+    pub rss_kb: u64,
+    pub cpu_percent: f64,
+    pub threads: Option<u32>,
+    pub ports: Vec<u16>,
+}
+
+impl TreeUsage {
+    pub fn of(tree: &[&ProcessInfo]) -> Self {
+        let ports: BTreeSet<u16> = tree.iter().flat_map(|p| p.ports.iter().copied()).collect();
+        Self {
+            rss_kb: tree.iter().map(|p| p.rss_kb).sum(),
+            cpu_percent: round_tenth(tree.iter().map(|p| p.cpu_percent).sum()),
+            threads: tree.iter().map(|p| p.threads).sum(),
+            ports: ports.into_iter().collect(),
+        }
+    }
+}
+
+/// Snapshot of every process on the system
+pub struct ProcessTable {
+    processes: HashMap<u32, ProcessInfo>,
+    children: HashMap<u32, Vec<u32>>,
+}
+
+impl ProcessTable {
+    pub async fn snapshot() -> Self {
+        let processes = if Path::new("/proc/self/stat").exists() {
+            read_proc()
+        } else {
+            read_ps().await
+        };
+        Self::new(processes)
+    }
+
+    fn new(processes: Vec<ProcessInfo>) -> Self {
+        let mut children = build_process_tree(processes.iter().map(|p| (p.pid, p.ppid)));
+        for pids in children.values_mut() {
+            pids.sort_unstable();
+        }
+        Self {
+            processes: processes.into_iter().map(|p| (p.pid, p)).collect(),
+            children,
+        }
+    }
+
+    /// A process that is still running (not a zombie)
+    pub fn get(&self, pid: u32) -> Option<&ProcessInfo> {
+        self.processes.get(&pid).filter(|p| !p.zombie)
+    }
+
+    pub fn is_alive(&self, pid: u32) -> bool {
+        self.get(pid).is_some()
+    }
+
+    /// All descendants of `pid`, depth-first
+    pub fn descendants(&self, pid: u32) -> Vec<u32> {
+        collect_descendants(&self.children, pid)
+    }
+
+    /// `pid` and its running descendants, parents first
+    pub fn tree(&self, pid: u32) -> Vec<&ProcessInfo> {
+        std::iter::once(pid)
+            .chain(self.descendants(pid))
+            .filter_map(|pid| self.get(pid))
+            .collect()
+    }
+
+    /// The process listening on `port` (the lowest pid if several share it)
+    pub fn listening_on(&self, port: u16) -> Option<&ProcessInfo> {
+        self.processes
+            .values()
+            .filter(|p| !p.zombie && p.ports.contains(&port))
+            .min_by_key(|p| p.pid)
+    }
+}
+
+/// Build a pid→children map from (pid, ppid) pairs.
+/// This is more reliable than `pgrep -P` because it captures the tree in a
+/// single snapshot, avoiding races where processes exit between calls.
+fn build_process_tree(pairs: impl IntoIterator<Item = (u32, u32)>) -> HashMap<u32, Vec<u32>> {
+    let mut tree: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (pid, ppid) in pairs {
+        tree.entry(ppid).or_default().push(pid);
+    }
+    tree
+}
+
+/// Recursively collect all descendant PIDs from a process tree map.
+fn collect_descendants(tree: &HashMap<u32, Vec<u32>>, pid: u32) -> Vec<u32> {
+    let mut result = Vec::new();
+    if let Some(children) = tree.get(&pid) {
+        for &child in children {
+            result.push(child);
+            result.extend(collect_descendants(tree, child));
+        }
+    }
+    result
+}
+
+fn round_tenth(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn read_proc() -> Vec<ProcessInfo> {
+    let uptime: f64 = fs::read_to_string("/proc/uptime")
+        .ok()
+        .and_then(|s| s.split_whitespace().next()?.parse().ok())
+        .unwrap_or(0.0);
+    let mut sockets = HashMap::new();
+    for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        if let Ok(contents) = fs::read_to_string(table) {
+            sockets.extend(listening_sockets(&contents));
+        }
+    }
+
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return vec![];
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_string_lossy().parse::<u32>().ok()?;
+            read_process(&entry.path(), pid, uptime, &sockets)
+        })
+        .collect()
+}
+
+fn read_process(
+    dir: &Path,
+    pid: u32,
+    uptime: f64,
+    sockets: &HashMap<u64, u16>,
+) -> Option<ProcessInfo> {
+    let stat = parse_stat(&fs::read_to_string(dir.join("stat")).ok()?)?;
+
+    let status = fs::read_to_string(dir.join("status")).unwrap_or_default();
+    let rss_kb = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.split_whitespace().next()?.parse().ok())
+        .unwrap_or(0);
+
+    let cmdline = fs::read(dir.join("cmdline")).unwrap_or_default();
+    let args: Vec<String> = cmdline
+        .split(|b| *b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+    // Kernel threads and zombies have no command line
+    let command = if args.is_empty() {
+        format!("[{}]", stat.name)
+    } else {
+        args.join(" ")
+    };
+
+    let mut ports: Vec<u16> = fs::read_dir(dir.join("fd"))
+        .map(|fds| {
+            fds.flatten()
+                .filter_map(|fd| {
+                    let target = fs::read_link(fd.path()).ok()?;
+                    let inode = target
+                        .to_str()?
+                        .strip_prefix("socket:[")?
+                        .strip_suffix(']')?
+                        .parse()
+                        .ok()?;
+                    sockets.get(&inode).copied()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    ports.sort_unstable();
+    ports.dedup();
+
+    let elapsed = (uptime - stat.start_ticks as f64 / CLOCK_TICKS_PER_SEC).max(0.0);
+    let cpu_seconds = stat.cpu_ticks as f64 / CLOCK_TICKS_PER_SEC;
+    let cpu_percent = if elapsed > 0.0 {
+        round_tenth(cpu_seconds / elapsed * 100.0)
+    } else {
+        0.0
+    };
+
+    Some(ProcessInfo {
+        pid,
+        ppid: stat.ppid,
+        command,
+        rss_kb,
+        cpu_percent,
+        threads: Some(stat.threads),
+        ports,
+        uptime_seconds: elapsed as u64,
+        zombie: stat.state == 'Z',
+    })
+}
+
+/// Fields of /proc/<pid>/stat used here
+#[derive(Debug, PartialEq)]
+struct Stat {
+    name: String,
+    state: char,
+    ppid: u32,
+    /// User plus system CPU time, in clock ticks
+    cpu_ticks: u64,
+    threads: u32,
+    /// Start time after boot, in clock ticks
+    start_ticks: u64,
+}
+
+/// Parse /proc/<pid>/stat: `pid (comm) state ppid ...`. The command name
+/// may contain spaces and parentheses, so fields are counted from the last
+/// `)`.
+fn parse_stat(contents: &str) -> Option<Stat> {
+    let open = contents.find('(')?;
+    let close = contents.rfind(')')?;
+    let name = contents.get(open + 1..close)?.to_string();
+    // Fields 3 onwards (state is field 3 in proc(5))
+    let fields: Vec<&str> = contents.get(close + 1..)?.split_whitespace().collect();
+    let field = |n: usize| fields.get(n - 3).copied();
+    Some(Stat {
+        name,
+        state: field(3)?.chars().next()?,
+        ppid: field(4)?.parse().ok()?,
+        cpu_ticks: field(14)?.parse::<u64>().ok()? + field(15)?.parse::<u64>().ok()?,
+        threads: field(20)?.parse().ok()?,
+        start_ticks: field(22)?.parse().ok()?,
+    })
+}
+
+/// Map of socket inode to port for listening sockets in a /proc/net/tcp{,6}
+/// table
+fn listening_sockets(table: &str) -> HashMap<u64, u16> {
+    // sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if *fields.get(3)? != TCP_LISTEN {
+                return None;
+            }
+            let port = u16::from_str_radix(fields.get(1)?.rsplit_once(':')?.1, 16).ok()?;
+            let inode = fields.get(9)?.parse().ok()?;
+            Some((inode, port))
+        })
+        .collect()
+}
+
+async fn read_ps() -> Vec<ProcessInfo> {
+    let Ok(output) = Command::new("ps")
+        .args(["-axo", "pid,ppid,stat,rss,%cpu,etime,command"])
+        .output()
+        .await
+    else {
+        return vec![];
+    };
+    let listening = read_lsof_ports().await;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let (fields, command) = split_fields(line, 6)?;
+            let pid = fields[0].parse().ok()?;
+            Some(ProcessInfo {
+                pid,
+                ppid: fields[1].parse().ok()?,
+                command: command.to_string(),
+                rss_kb: fields[3].parse().unwrap_or(0),
+                cpu_percent: fields[4].parse().unwrap_or(0.0),
+                threads: None,
+                ports: listening.get(&pid).cloned().unwrap_or_default(),
+                uptime_seconds: parse_etime(fields[5]).unwrap_or(0),
+                zombie: fields[2].starts_with('Z'),
+            })
+        })
+        .collect()
+}
+
+/// Split the first `n` whitespace-separated fields off `line`, returning
+/// them and the rest of the line
+fn split_fields(line: &str, n: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(n);
+    let mut rest = line.trim_start();
+    for _ in 0..n {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        if end == 0 {
+            return None;
+        }
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+    Some((fields, rest))
+}
+
+/// Listening TCP ports per pid from `lsof -F pn` output
+async fn read_lsof_ports() -> HashMap<u32, Vec<u16>> {
+    let Ok(output) = Command::new("lsof")
+        .args(["-nP", "-iTCP", "-sTCP:LISTEN", "-Fpn"])
+        .output()
+        .await
+    else {
+        return HashMap::new();
+    };
+    parse_lsof_ports(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_lsof_ports(output: &str) -> HashMap<u32, Vec<u16>> {
+    let mut ports: HashMap<u32, Vec<u16>> = HashMap::new();
+    let mut pid = None;
+    for line in output.lines() {
+        if let Some(value) = line.strip_prefix('p') {
+            pid = value.parse().ok();
+        } else if let (Some(pid), Some(name)) = (pid, line.strip_prefix('n')) {
+            // `*:4400`, `127.0.0.1:4400` or `[::1]:4400`
+            if let Some(port) = name.rsplit_once(':').and_then(|(_, p)| p.parse().ok()) {
+                let entry = ports.entry(pid).or_default();
+                if !entry.contains(&port) {
+                    entry.push(port);
+                }
+            }
+        }
+    }
+    ports
+}
+
+/// Parse `ps -o etime` format: [[DD-]HH:]MM:SS
+/// Examples: "02:30" (2m30s), "01:02:30" (1h2m30s), "2-01:02:30" (2d1h2m30s)
+fn parse_etime(s: &str) -> Option<u64> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut total_secs: u64 = 0;
+
+    // Check for days (format: DD-HH:MM:SS)
+    let (days, rest) = if s.contains('-') {
+        let parts: Vec<&str> = s.splitn(2, '-').collect();
+        let days: u64 = parts[0].parse().ok()?;
+        (days, parts.get(1).copied().unwrap_or(""))
+    } else {
+        (0, s)
+    };
+
+    total_secs += days * 24 * 60 * 60;
+
+    // Parse HH:MM:SS or MM:SS
+    let time_parts: Vec<&str> = rest.split(':').collect();
+    match time_parts.len() {
+        2 => {
+            // MM:SS
+            let mins: u64 = time_parts[0].parse().ok()?;
+            let secs: u64 = time_parts[1].parse().ok()?;
+            total_secs += mins * 60 + secs;
+        }
+        3 => {
+            // HH:MM:SS
+            let hours: u64 = time_parts[0].parse().ok()?;
+            let mins: u64 = time_parts[1].parse().ok()?;
+            let secs: u64 = time_parts[2].parse().ok()?;
+            total_secs += hours * 60 * 60 + mins * 60 + secs;
+        }
+        _ => return None,
+    }
+
+    Some(total_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tree from `ps -axo pid,ppid` style output
+    fn ps_tree(ps_output: &str) -> HashMap<u32, Vec<u32>> {
+        build_process_tree(ps_output.lines().skip(1).filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            Some((parts.first()?.parse().ok()?, parts.get(1)?.parse().ok()?))
+        }))
+    }
+
+    fn process(pid: u32, ppid: u32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            ppid,
+            command: format!("cmd{}", pid),
+            rss_kb: 1024,
+            cpu_percent: 1.5,
+            threads: Some(2),
+            ports: vec![],
+            uptime_seconds: 60,
+            zombie: false,
+        }
+    }
+
+    // === Existing: parse_etime ===
+
+    #[test]
+    fn test_parse_etime_minutes_seconds() {
+        assert_eq!(parse_etime("02:30"), Some(150));
+    }
+
+    #[test]
+    fn test_parse_etime_hours_minutes_seconds() {
+        assert_eq!(parse_etime("01:02:30"), Some(3750));
+    }
+
+    #[test]
+    fn test_parse_etime_days() {
+        assert_eq!(parse_etime("2-01:02:30"), Some(2 * 86400 + 3750));
+    }
+
+    #[test]
+    fn test_parse_etime_empty() {
+        assert_eq!(parse_etime(""), None);
+    }
+
+    // === Issue #4: process tree building ===
+
+    #[test]
+    fn test_build_process_tree_basic() {
+        let ps_output = "  PID  PPID\n    1     0\n  100     1\n  200   100\n  300   100\n  400   200\n";
+        let tree = ps_tree(ps_output);
+
+        assert_eq!(tree.get(&0), Some(&vec![1]));
+        assert_eq!(tree.get(&1), Some(&vec![100]));
+        assert!(tree.get(&100).unwrap().contains(&200));
+        assert!(tree.get(&100).unwrap().contains(&300));
+        assert_eq!(tree.get(&200), Some(&vec![400]));
+    }
+
+    #[test]
+    fn test_build_process_tree_empty() {
+        let ps_output = "  PID  PPID\n";
+        let tree = ps_tree(ps_output);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_collect_descendants_full_tree() {
+        let ps_output = "  PID  PPID\n    1     0\n  100     1\n  200   100\n  300   100\n  400   200\n";
+        let tree = ps_tree(ps_output);
+        let mut descendants = collect_descendants(&tree, 100);
+        descendants.sort();
+        assert_eq!(descendants, vec![200, 300, 400]);
+    }
+
+    #[test]
+    fn test_collect_descendants_leaf_node() {
+        let ps_output = "  PID  PPID\n    1     0\n  100     1\n  200   100\n";
+        let tree = ps_tree(ps_output);
+        let descendants = collect_descendants(&tree, 200);
+        assert!(descendants.is_empty());
+    }
+
+    #[test]
+    fn test_collect_descendants_nonexistent_pid() {
+        let ps_output = "  PID  PPID\n    1     0\n  100     1\n";
+        let tree = ps_tree(ps_output);
+        let descendants = collect_descendants(&tree, 999);
+        assert!(descendants.is_empty());
+    }
+
+    // Simulates the bacon process tree:
+    // shell(100) → bacon(200) → cargo(300) → binary(400)
+    #[test]
+    fn test_collect_descendants_bacon_tree() {
+        let ps_output =
+            "  PID  PPID\n  100     1\n  200   100\n  300   200\n  400   300\n";
+        let tree = ps_tree(ps_output);
+        let descendants = collect_descendants(&tree, 100);
+        // Should find all: bacon, cargo, binary
+        assert_eq!(descendants.len(), 3);
+        assert!(descendants.contains(&200)); // bacon
+        assert!(descendants.contains(&300)); // cargo
+        assert!(descendants.contains(&400)); // binary
+    }
+
+    // When shell exits and children are reparented to launchd (pid 1),
+    // the old shell PID has no children in the tree
+    #[test]
+    fn test_collect_descendants_orphaned_to_launchd() {
+        // Shell (pid 100) exited. bacon (200) and cargo (300) reparented to pid 1.
+        let ps_output = "  PID  PPID\n    1     0\n  200     1\n  300   200\n  400   300\n";
+        let tree = ps_tree(ps_output);
+        // Looking for descendants of the dead shell PID 100 — finds nothing
+        let descendants = collect_descendants(&tree, 100);
+        assert!(descendants.is_empty());
+        // But descendants of pid 1 would include the reparented processes
+        let launchd_children = collect_descendants(&tree, 1);
+        assert!(launchd_children.contains(&200));
+    }
+
+    #[test]
+    fn test_parse_stat_handles_spaces_in_name() {
+        let stat = "4242 (tokio (worker) 1) S 4200 4242 4200 0 -1 4194560 1000 0 0 0 250 50 0 0 20 0 7 0 12345 123456789 2000 18446744073709551615";
+        assert_eq!(
+            parse_stat(stat),
+            Some(Stat {
+                name: "tokio (worker) 1".to_string(),
+                state: 'S',
+                ppid: 4200,
+                cpu_ticks: 300,
+                threads: 7,
+                start_ticks: 12345,
+            })
+        );
+        assert_eq!(parse_stat("garbage"), None);
+    }
+
+    #[test]
+    fn test_listening_sockets_matches_listen_state() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1130 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41234 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:1130 0100007F:D3A2 01 00000000:00000000 00:00000000 00000000  1000        0 41299 1 0000000000000000 20 4 30 10 -1
+   2: 00000000:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 40001 1 0000000000000000 100 0 0 10 0
+";
+        // 0x1130 = 4400, 0x0BB8 = 3000; the established connection is ignored
+        assert_eq!(
+            listening_sockets(table),
+            HashMap::from([(41234, 4400), (40001, 3000)])
+        );
+    }
+
+    #[test]
+    fn test_parse_lsof_ports() {
+        let output = "p100\nf5\nn*:4400\nf6\nn[::1]:4400\np200\nf7\nn127.0.0.1:3000\n";
+        let ports = parse_lsof_ports(output);
+        assert_eq!(ports[&100], vec![4400]);
+        assert_eq!(ports[&200], vec![3000]);
+    }
+
+    #[test]
+    fn test_split_fields_keeps_command_spacing() {
+        let (fields, rest) =
+            split_fields("  100     1 S    2048  0.5    01:02 node  server.js --port 1", 6).unwrap();
+        assert_eq!(fields, ["100", "1", "S", "2048", "0.5", "01:02"]);
+        assert_eq!(rest, "node  server.js --port 1");
+        assert_eq!(split_fields("100 1", 6), None);
+    }
+
+    #[test]
+    fn test_tree_skips_zombies_and_sums_usage() {
+        let mut zombie = process(300, 100);
+        zombie.zombie = true;
+        let mut server = process(400, 200);
+        server.ports = vec![4400];
+        let table = ProcessTable::new(vec![
+            process(100, 1),
+            process(200, 100),
+            zombie,
+            server,
+        ]);
+
+        let tree: Vec<u32> = table.tree(100).iter().map(|p| p.pid).collect();
+        assert_eq!(tree, vec![100, 200, 400]);
+        assert!(!table.is_alive(300));
+        assert_eq!(table.listening_on(4400).map(|p| p.pid), Some(400));
+
+        let usage = TreeUsage::of(&table.tree(100));
+        assert_eq!(usage.rss_kb, 3 * 1024);
+        assert_eq!(usage.cpu_percent, 4.5);
+        assert_eq!(usage.threads, Some(6));
+        assert_eq!(usage.ports, vec![4400]);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_includes_current_process() {
+        let table = ProcessTable::snapshot().await;
+        let me = table.get(std::process::id()).expect("current process listed");
+        assert!(me.rss_kb > 0);
+        assert!(!me.command.is_empty());
+    }
+}
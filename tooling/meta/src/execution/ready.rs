@@ -0,0 +1,407 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use regex::Regex;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use super::{events::log_event, DevCommand};
+use crate::config::ReadyProbe;
+
+/// Time between probe attempts while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Limit for a single connection attempt
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How much of the end of a project log `meta status` searches for a log
+/// probe match
+const STATUS_LOG_WINDOW: u64 = 64 * 1024;
+
+/// A validated [`ReadyProbe`]
+#[derive(Debug, Clone)]
+pub struct Probe {
+    port: Option<u16>,
+    http: Option<HttpTarget>,
+    log: Option<Regex>,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct HttpTarget {
+    url: String,
+    /// `host:port` to connect to
+    address: String,
+    host: String,
+    path: String,
+}
+
+impl Probe {
+    pub fn new(config: &ReadyProbe) -> Result<Self> {
+        if config.port.is_none() && config.http.is_none() && config.log.is_none() {
+            anyhow::bail!("ready probe needs at least one of port, http or log");
+        }
+        let http = config.http.as_deref().map(HttpTarget::parse).transpose()?;
+        let log = config
+            .log
+            .as_deref()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .map_err(|e| anyhow::anyhow!("Invalid ready log pattern '{}': {}", pattern, e))
+            })
+            .transpose()?;
+        Ok(Self {
+            port: config.port,
+            http,
+            log,
+            timeout: Duration::from_secs(config.timeout),
+        })
+    }
+
+    /// Human-readable description, e.g. `port 4400, http://localhost:4400/health`
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(port) = self.port {
+            parts.push(format!("port {}", port));
+        }
+        if let Some(http) = &self.http {
+            parts.push(http.url.clone());
+        }
+        if let Some(log) = &self.log {
+            parts.push(format!("log /{}/", log.as_str()));
+        }
+        parts.join(", ")
+    }
+
+    /// Check every probe once. Log lines are read from `log_path` starting
+    /// at byte `log_offset`.
+    pub async fn check(&self, log_path: &Path, log_offset: u64) -> bool {
+        if let Some(port) = self.port {
+            if !port_open(port).await {
+                return false;
+            }
+        }
+        if let Some(http) = &self.http {
+            if !http.check().await {
+                return false;
+            }
+        }
+        if let Some(log) = &self.log {
+            if !log_matches(log, log_path, log_offset) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Check the probe for `meta status`, searching the end of the project log
+    pub async fn check_status(&self, project: &str) -> bool {
+        let log_path = project_log(project);
+        let len = std::fs::metadata(&log_path).map(|m| m.len()).unwrap_or(0);
+        self.check(&log_path, len.saturating_sub(STATUS_LOG_WINDOW)).await
+    }
+
+    /// Poll until the probe passes or `timeout` expires. Returns how long it
+    /// took.
+    pub async fn wait(&self, project: &str, log_offset: u64) -> Result<Duration> {
+        let started = Instant::now();
+        let log_path = project_log(project);
+        loop {
+            if self.check(&log_path, log_offset).await {
+                return Ok(started.elapsed());
+            }
+            if started.elapsed() >= self.timeout {
+                anyhow::bail!(
+                    "{} not ready after {}s ({})",
+                    project,
+                    self.timeout.as_secs(),
+                    self.describe()
+                );
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl HttpTarget {
+    fn parse(url: &str) -> Result<Self> {
+        let Some(rest) = url.strip_prefix("http://") else {
+            anyhow::bail!(
+                "Unsupported ready URL '{}': only http:// is supported (use a port probe for TLS)",
+                url
+            );
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        if authority.is_empty() {
+            anyhow::bail!("Invalid ready URL '{}': missing host", url);
+        }
+        let address = if authority.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok()) {
+            authority.to_string()
+        } else {
+            format!("{}:80", authority)
+        };
+        Ok(Self {
+            url: url.to_string(),
+            address,
+            host: authority.to_string(),
+            path: path.to_string(),
+        })
+    }
+
+    /// Send a GET and accept any 2xx/3xx status
+    async fn check(&self) -> bool {
+        let request = async {
+            let mut stream = TcpStream::connect(&self.address).await?;
+            let request = format!(
+                "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+                self.path, self.host
+            );
+            stream.write_all(request.as_bytes()).await?;
+            let mut response = Vec::new();
+            let mut buf = [0u8; 256];
+            // Only the status line is needed
+            while !response.contains(&b'\n') {
+                let n = stream.read(&mut buf).await?;
+                if n == 0 {
+                    break;
+                }
+                response.extend_from_slice(&buf[..n]);
+            }
+            Ok::<_, std::io::Error>(response)
+        };
+        let Ok(Ok(response)) = tokio::time::timeout(CONNECT_TIMEOUT, request).await else {
+            return false;
+        };
+        let status_line = String::from_utf8_lossy(&response);
+        status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse::<u16>().ok())
+            .is_some_and(|code| (200..400).contains(&code))
+    }
+}
+
+async fn port_open(port: u16) -> bool {
+    let connect = TcpStream::connect(("localhost", port));
+    matches!(tokio::time::timeout(CONNECT_TIMEOUT, connect).await, Ok(Ok(_)))
+}
+
+fn log_matches(pattern: &Regex, path: &Path, offset: u64) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let mut contents = Vec::new();
+    if file.seek(SeekFrom::Start(offset)).is_err() || file.read_to_end(&mut contents).is_err() {
+        return false;
+    }
+    String::from_utf8_lossy(&contents)
+        .lines()
+        .any(|line| pattern.is_match(line))
+}
+
+pub fn project_log(project: &str) -> PathBuf {
+    PathBuf::from(format!(".meta/logs/{}.log", project))
+}
+
+/// Current size of a project's log, so a probe only looks at output written
+/// after this point
+pub fn log_offset(project: &str) -> u64 {
+    std::fs::metadata(project_log(project))
+        .map(|m| m.len())
+        .unwrap_or(0)
+}
+
+/// Projects started so far by `meta dev` and whether they became ready
+#[derive(Default)]
+pub struct Readiness {
+    started: BTreeMap<String, Started>,
+}
+
+struct Started {
+    probe: Option<Probe>,
+    /// Size of the project log when it started; log probes look after it
+    log_offset: u64,
+    ready: bool,
+}
+
+impl Readiness {
+    /// Record that `project` is about to start
+    pub fn starting(&mut self, project: &str, probe: Option<Probe>) {
+        let started = Started {
+            ready: probe.is_none(),
+            probe,
+            log_offset: log_offset(project),
+        };
+        self.started.insert(project.to_string(), started);
+    }
+
+    /// Wait until the started projects in `after` pass their ready probes.
+    /// A probe that times out is reported but does not block the launch.
+    pub async fn wait_for(&mut self, after: &[String]) {
+        for dep in after {
+            let Some(started) = self.started.get_mut(dep) else {
+                continue;
+            };
+            let Some(probe) = started.probe.as_ref().filter(|_| !started.ready) else {
+                continue;
+            };
+            println!("⏳ Waiting for {} to be ready ({})...", dep, probe.describe());
+            match probe.wait(dep, started.log_offset).await {
+                Ok(elapsed) => {
+                    println!("  ✓ {} ready after {:.1}s", dep, elapsed.as_secs_f64());
+                    log_event(
+                        dep,
+                        &format!("READY: {} (after {:.1}s)", probe.describe(), elapsed.as_secs_f64()),
+                    );
+                }
+                Err(e) => {
+                    println!("  ⚠ {}, starting dependents anyway", e);
+                    log_event(dep, &format!("TIMEOUT: {}", e));
+                }
+            }
+            started.ready = true;
+        }
+    }
+}
+
+/// Reorder dev commands so every project starts after the projects in its
+/// `after` list. Entries naming projects that are not being started are
+/// ignored. Otherwise the original order is kept.
+pub fn launch_order(commands: Vec<(String, DevCommand)>) -> Result<Vec<(String, DevCommand)>> {
+    let names: BTreeSet<String> = commands.iter().map(|(name, _)| name.clone()).collect();
+    let mut pending = commands;
+    let mut ordered = Vec::with_capacity(pending.len());
+    let mut started: BTreeSet<String> = BTreeSet::new();
+
+    while !pending.is_empty() {
+        let Some(next) = pending.iter().position(|(_, command)| {
+            command
+                .after
+                .iter()
+                .all(|dep| started.contains(dep) || !names.contains(dep))
+        }) else {
+            let stuck: Vec<&str> = pending.iter().map(|(name, _)| name.as_str()).collect();
+            anyhow::bail!("Cycle in dev task `after` dependencies: {}", stuck.join(", "));
+        };
+        let (name, command) = pending.remove(next);
+        started.insert(name.clone());
+        ordered.push((name, command));
+    }
+
+    Ok(ordered)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::config::RestartPolicy;
+
+    fn command(after: &[&str]) -> DevCommand {
+        DevCommand {
+            dir: None,
+            argv: vec!["true".to_string()],
+            env: BTreeMap::new(),
+            restart: RestartPolicy::Never,
+            max_restarts: 5,
+            restart_window: 60,
+            ready: None,
+            after: after.iter().map(|s| s.to_string()).collect(),
+            port: None,
+        }
+    }
+
+    fn probe(toml: &str) -> Result<Probe> {
+        Probe::new(&toml::from_str(toml).unwrap())
+    }
+
+    #[test]
+    fn test_launch_order_respects_after() {
+        let commands = vec![
+            ("app".to_string(), command(&["api"])),
+            ("api".to_string(), command(&["db"])),
+            ("web".to_string(), command(&[])),
+            ("db".to_string(), command(&["not-started"])),
+        ];
+        let order: Vec<String> = launch_order(commands)
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(order, ["web", "db", "api", "app"]);
+    }
+
+    #[test]
+    fn test_launch_order_detects_cycles() {
+        let commands = vec![
+            ("a".to_string(), command(&["b"])),
+            ("b".to_string(), command(&["a"])),
+        ];
+        let err = launch_order(commands).unwrap_err().to_string();
+        assert!(err.contains("Cycle"), "{}", err);
+    }
+
+    #[test]
+    fn test_probe_validation() {
+        assert!(probe("timeout = 5").is_err());
+        assert!(probe(r#"http = "https://localhost/health""#).is_err());
+        assert!(probe(r#"log = "(unclosed""#).is_err());
+        let probe = probe(r#"http = "http://localhost:4400/health""#).unwrap();
+        assert_eq!(probe.describe(), "http://localhost:4400/health");
+        assert_eq!(probe.timeout, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_http_target_defaults() {
+        let target = HttpTarget::parse("http://localhost").unwrap();
+        assert_eq!(target.address, "localhost:80");
+        assert_eq!(target.path, "/");
+        let target = HttpTarget::parse("http://127.0.0.1:3000/api/health?full=1").unwrap();
+        assert_eq!(target.address, "127.0.0.1:3000");
+        assert_eq!(target.path, "/api/health?full=1");
+    }
+
+    #[tokio::test]
+    async fn test_log_probe_reads_from_offset() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("api.log");
+        std::fs::write(&log, "Listening on :4400\n").unwrap();
+        let offset = std::fs::metadata(&log).unwrap().len();
+
+        let probe = probe(r#"log = "Listening on""#).unwrap();
+        assert!(probe.check(&log, 0).await);
+        assert!(!probe.check(&log, offset).await);
+    }
+
+    #[tokio::test]
+    async fn test_port_and_http_probes() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").await;
+            }
+        });
+
+        let probe = probe(&format!(
+            "port = {}\nhttp = \"http://127.0.0.1:{}/health\"",
+            port, port
+        ))
+        .unwrap();
+        assert!(probe.check(Path::new("missing.log"), 0).await);
+    }
+}
@@ -0,0 +1,347 @@
+//! Project log files: a writer that rotates `.meta/logs/<project>.log` and
+//! `<project>.jsonl` by size and deletes old rotations according to
+//! `[logs]`, the `meta log-sink` end of tmux `pipe-pane`, and `meta logs
+//! --clean`
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+
+use super::{
+    structured::{jsonl_path, JsonRecord},
+    supervisor::strip_ansi,
+};
+use crate::config::LogsConfig;
+
+pub const LOG_DIR: &str = ".meta/logs";
+
+/// Events log, kept by `meta logs --clean` since `meta status` reads
+/// restarts and stops from it
+pub const DEV_LOG: &str = "dev.log";
+
+pub fn log_path(project: &str) -> PathBuf {
+    Path::new(LOG_DIR).join(format!("{}.log", project))
+}
+
+/// Appends lines to a project log and their parsed records to the
+/// project's `.jsonl`, rotating each once it would grow past `max_size`
+pub struct LogWriter {
+    text: RotatingFile,
+    records: RotatingFile,
+}
+
+impl LogWriter {
+    pub fn open(project: &str, config: &LogsConfig) -> io::Result<LogWriter> {
+        fs::create_dir_all(LOG_DIR)?;
+        Ok(LogWriter {
+            text: RotatingFile::open(log_path(project), config)?,
+            records: RotatingFile::open(jsonl_path(project), config)?,
+        })
+    }
+
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        self.text.write_line(line)?;
+        let written = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let record = serde_json::to_string(&JsonRecord::new(line, written))?;
+        self.records.write_line(&record)
+    }
+}
+
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    config: LogsConfig,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, config: &LogsConfig) -> io::Result<RotatingFile> {
+        let file = append(&path)?;
+        let size = file.metadata()?.len();
+        prune(&path, config);
+        Ok(RotatingFile {
+            path,
+            file,
+            size,
+            config: config.clone(),
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.size > 0 && self.size + len > self.config.max_size.0 {
+            // The file may have been cleaned since it was opened
+            self.size = fs::metadata(&self.path).map_or(0, |m| m.len());
+            if self.size > 0 && self.size + len > self.config.max_size.0 {
+                rotate(&self.path, &self.config)?;
+                self.file = append(&self.path)?;
+                self.size = 0;
+            }
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += len;
+        Ok(())
+    }
+}
+
+fn append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Rotated copies of `log` (`api.log.1`, `api.log.2.gz`, ...) by number
+fn rotations(log: &Path) -> Vec<(u32, PathBuf)> {
+    let Some(name) = log.file_name().and_then(|n| n.to_str()) else {
+        return vec![];
+    };
+    let dir = log.parent().unwrap_or(Path::new("."));
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut found: Vec<(u32, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let suffix = file_name.to_str()?.strip_prefix(name)?.strip_prefix('.')?;
+            let number = suffix.strip_suffix(".gz").unwrap_or(suffix).parse().ok()?;
+            Some((number, entry.path()))
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+/// `api.log.3` for `api.log.2`, keeping a `.gz` extension
+fn renumbered(log: &Path, rotation: &Path, number: u32) -> PathBuf {
+    let gz = rotation.extension().is_some_and(|ext| ext == "gz");
+    let mut name = log.as_os_str().to_owned();
+    name.push(format!(".{}{}", number, if gz { ".gz" } else { "" }));
+    PathBuf::from(name)
+}
+
+/// Move `log` to `log.1`, shifting older rotations up and dropping those
+/// past `max_files`, then compress it if `gzip` is set
+fn rotate(log: &Path, config: &LogsConfig) -> io::Result<()> {
+    for (number, rotation) in rotations(log).into_iter().rev() {
+        if number >= config.max_files {
+            fs::remove_file(&rotation)?;
+        } else {
+            fs::rename(&rotation, renumbered(log, &rotation, number + 1))?;
+        }
+    }
+    if config.max_files == 0 {
+        return fs::remove_file(log);
+    }
+
+    let first = renumbered(log, log, 1);
+    fs::rename(log, &first)?;
+    if config.gzip {
+        // Without gzip the rotation is simply kept uncompressed
+        let _ = std::process::Command::new("gzip")
+            .args(["-f", "-q"])
+            .arg(&first)
+            .status();
+    }
+    prune(log, config);
+    Ok(())
+}
+
+/// Delete rotations past `max_files` or older than `max_age`
+fn prune(log: &Path, config: &LogsConfig) {
+    let max_age = config.max_age.map(|age| Duration::from_secs(age.0));
+    for (number, rotation) in rotations(log) {
+        let expired = max_age.is_some_and(|max_age| {
+            fs::metadata(&rotation)
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| {
+                    SystemTime::now()
+                        .duration_since(modified)
+                        .unwrap_or_default()
+                        > max_age
+                })
+        });
+        if number > config.max_files || expired {
+            let _ = fs::remove_file(&rotation);
+        }
+    }
+}
+
+/// Append lines from stdin to a project log until it closes. tmux
+/// `pipe-pane` runs this as `meta log-sink <project>`, so meta is the only
+/// writer of the log and rotates it while the session runs.
+pub fn log_sink(project: &str, config: &LogsConfig) -> Result<()> {
+    let mut writer = LogWriter::open(project, config)?;
+    let mut stdin = io::stdin().lock();
+    let mut raw = Vec::new();
+    loop {
+        raw.clear();
+        if stdin.read_until(b'\n', &mut raw)? == 0 {
+            return Ok(());
+        }
+        // Pane output ends lines with \r\n
+        let line = String::from_utf8_lossy(&raw);
+        writer.write_line(&strip_ansi(line.trim_end_matches(['\n', '\r'])))?;
+    }
+}
+
+/// What `meta logs --clean` removed
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Cleaned {
+    /// Rotated files deleted
+    pub removed: usize,
+    /// Current logs emptied
+    pub emptied: usize,
+    pub bytes: u64,
+}
+
+/// Delete the rotations of the given projects' logs (text and `.jsonl`) and
+/// empty the logs themselves, or of every project log if `projects` is
+/// empty. Logs are truncated rather than deleted so running dev processes
+/// keep writing to them.
+pub fn clean(projects: &[String]) -> io::Result<Cleaned> {
+    let logs: Vec<PathBuf> = if projects.is_empty() {
+        let Ok(entries) = fs::read_dir(LOG_DIR) else {
+            return Ok(Cleaned::default());
+        };
+        let mut logs: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "log" || ext == "jsonl"))
+            .filter(|path| path.file_name().is_some_and(|name| name != DEV_LOG))
+            .collect();
+        logs.sort();
+        logs
+    } else {
+        projects
+            .iter()
+            .flat_map(|project| [log_path(project), jsonl_path(project)])
+            .collect()
+    };
+
+    let mut cleaned = Cleaned::default();
+    for log in &logs {
+        for (_, rotation) in rotations(log) {
+            cleaned.bytes += fs::metadata(&rotation).map_or(0, |m| m.len());
+            fs::remove_file(&rotation)?;
+            cleaned.removed += 1;
+        }
+        match fs::metadata(log) {
+            Ok(metadata) if metadata.len() > 0 => {
+                cleaned.bytes += metadata.len();
+                File::create(log)?;
+                cleaned.emptied += 1;
+            }
+            _ => {}
+        }
+    }
+    Ok(cleaned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Age, ByteSize};
+
+    fn logs_config(max_size: u64, max_files: u32) -> LogsConfig {
+        LogsConfig {
+            max_size: ByteSize(max_size),
+            max_files,
+            max_age: None,
+            gzip: false,
+        }
+    }
+
+    fn names(log: &Path) -> Vec<String> {
+        rotations(log)
+            .iter()
+            .map(|(_, path)| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_rotate_shifts_and_drops_old_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log = temp_dir.path().join("api.log");
+        let config = logs_config(10, 2);
+
+        for content in ["one", "two", "three"] {
+            fs::write(&log, content).unwrap();
+            rotate(&log, &config).unwrap();
+        }
+        assert!(!log.exists());
+        assert_eq!(names(&log), ["api.log.1", "api.log.2"]);
+        assert_eq!(fs::read_to_string(temp_dir.path().join("api.log.1")).unwrap(), "three");
+        assert_eq!(fs::read_to_string(temp_dir.path().join("api.log.2")).unwrap(), "two");
+
+        // Rotations of other logs are left alone
+        fs::write(temp_dir.path().join("api.log.10.bak"), "").unwrap();
+        fs::write(temp_dir.path().join("web.log.1"), "").unwrap();
+        assert_eq!(names(&log), ["api.log.1", "api.log.2"]);
+
+        fs::write(&log, "four").unwrap();
+        rotate(&log, &logs_config(10, 0)).unwrap();
+        assert!(!log.exists());
+        assert!(names(&log).is_empty());
+    }
+
+    #[test]
+    fn test_file_rotates_when_a_line_would_pass_max_size() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log = temp_dir.path().join("api.log");
+        let mut writer = RotatingFile::open(log.clone(), &logs_config(13, 1)).unwrap();
+        for line in ["first", "second", "third"] {
+            writer.write_line(line).unwrap();
+        }
+        assert_eq!(fs::read_to_string(&log).unwrap(), "third\n");
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("api.log.1")).unwrap(),
+            "first\nsecond\n"
+        );
+
+        // An emptied log is not rotated again
+        File::create(&log).unwrap();
+        writer.write_line("fourth").unwrap();
+        writer.write_line("fifth").unwrap();
+        assert_eq!(fs::read_to_string(&log).unwrap(), "fourth\nfifth\n");
+        assert_eq!(names(&log), ["api.log.1"]);
+    }
+
+    #[test]
+    fn test_rotate_keeps_gz_extension_when_shifting() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log = temp_dir.path().join("api.log");
+        fs::write(temp_dir.path().join("api.log.1.gz"), "old").unwrap();
+        fs::write(&log, "new").unwrap();
+        rotate(&log, &logs_config(10, 3)).unwrap();
+        assert_eq!(names(&log), ["api.log.1", "api.log.2.gz"]);
+    }
+
+    #[test]
+    fn test_prune_removes_expired_and_extra_rotations() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log = temp_dir.path().join("api.log");
+        for n in 1..=3 {
+            fs::write(temp_dir.path().join(format!("api.log.{}", n)), "").unwrap();
+        }
+        prune(&log, &logs_config(10, 2));
+        assert_eq!(names(&log), ["api.log.1", "api.log.2"]);
+
+        let mut expiring = logs_config(10, 2);
+        expiring.max_age = Some(Age(3600));
+        let old = SystemTime::now() - Duration::from_secs(7200);
+        File::options()
+            .write(true)
+            .open(temp_dir.path().join("api.log.2"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        prune(&log, &expiring);
+        assert_eq!(names(&log), ["api.log.1"]);
+    }
+}
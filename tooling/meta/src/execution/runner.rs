@@ -0,0 +1,416 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use tokio::task::JoinSet;
+
+use super::{
+    cache::{self, HashInputs, TaskCache},
+    graph::{TaskGraph, TaskId},
+};
+use crate::{
+    adapters::{ExecFailure, ToolAdapter},
+    config::Config,
+    version::Version,
+};
+
+/// Default for `--jobs`: one task per available CPU
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Options shared by `meta build`, `meta test` and `meta run`
+pub struct RunOptions {
+    /// Maximum number of tasks running at once
+    pub jobs: usize,
+    /// Skip tasks whose inputs match a previous successful run
+    pub use_cache: bool,
+    /// Keep starting independent tasks after a failure instead of stopping
+    pub continue_on_error: bool,
+}
+
+/// Everything needed to run one task, owned so it can move into a tokio task
+struct PreparedTask {
+    id: TaskId,
+    tool: String,
+    /// The tool's configured executable, which differs from the adapter's
+    /// for `shell = true` tasks
+    tool_command: String,
+    adapter: ToolAdapter,
+    /// Arguments joined back into a shell line, part of the cache key
+    command: String,
+    args: Vec<String>,
+    dir: PathBuf,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    /// First line of `<tool> --version`, only resolved for cacheable tasks
+    tool_version: String,
+}
+
+/// Result of a task that completed successfully
+struct Completed {
+    /// Cache key, or `None` if the task is not cacheable
+    hash: Option<String>,
+    cached: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    Passed,
+    /// Skipped because its inputs matched a previous successful run
+    Cached,
+    Failed,
+    /// Never started because a dependency failed or the run was aborted
+    Skipped,
+}
+
+#[derive(Debug)]
+pub struct TaskReport {
+    pub id: TaskId,
+    pub state: TaskState,
+    pub duration: Option<Duration>,
+    /// Exit code of a failed task (`None` if killed by a signal or it never
+    /// got to run)
+    pub exit_code: Option<i32>,
+    /// Last lines a failed task wrote to stderr
+    pub stderr_tail: Vec<String>,
+}
+
+impl TaskReport {
+    fn new(id: TaskId, state: TaskState, duration: Option<Duration>) -> Self {
+        Self {
+            id,
+            state,
+            duration,
+            exit_code: None,
+            stderr_tail: vec![],
+        }
+    }
+}
+
+/// Run every task in `graph`, up to `jobs` at a time.
+///
+/// A task starts as soon as all of its dependencies have passed. After the
+/// first failure no new tasks are started unless `continue_on_error` is set,
+/// in which case only dependents of failed tasks are skipped. Tasks already
+/// running are always allowed to finish. A summary table is printed at the
+/// end and the run fails if any task failed.
+pub async fn run_graph(config: &Config, graph: &TaskGraph, options: &RunOptions) -> Result<()> {
+    let order = graph.topological_order()?;
+    let mut versions: BTreeMap<String, String> = BTreeMap::new();
+    let mut pending: BTreeMap<TaskId, PreparedTask> = BTreeMap::new();
+    for id in &order {
+        let mut task = prepare(config, id)?;
+        if options.use_cache && !task.inputs.is_empty() {
+            if !versions.contains_key(&task.tool_command) {
+                let version = tool_version(&task.tool_command).await;
+                versions.insert(task.tool_command.clone(), version);
+            }
+            task.tool_version = versions[&task.tool_command].clone();
+        }
+        pending.insert(id.clone(), task);
+    }
+    check_tool_versions(config, pending.values().map(|task| task.tool.as_str())).await?;
+
+    let jobs = options.jobs.max(1);
+    let use_cache = options.use_cache;
+    let mut passed: BTreeSet<TaskId> = BTreeSet::new();
+    // Cache key of each completed task, folded into the keys of its
+    // dependents. `None` for tasks that are not cached: their outputs can
+    // change on every run, so nothing downstream is cached either.
+    let mut hashes: BTreeMap<TaskId, Option<String>> = BTreeMap::new();
+    let mut reports: BTreeMap<TaskId, TaskReport> = BTreeMap::new();
+    let mut running = JoinSet::new();
+    let mut failed = false;
+
+    loop {
+        if !failed || options.continue_on_error {
+            // Start ready tasks in topological order until the job limit is hit
+            for id in &order {
+                if running.len() >= jobs {
+                    break;
+                }
+                if !pending.contains_key(id) || !graph.dependencies(id).all(|d| passed.contains(d))
+                {
+                    continue;
+                }
+                let task = pending.remove(id).expect("checked above");
+                let dependency_hashes: Option<Vec<String>> =
+                    graph.dependencies(id).map(|d| hashes[d].clone()).collect();
+                println!("  → {} ({})", task.id, task.tool);
+                running.spawn(async move {
+                    let started = Instant::now();
+                    let id = task.id.clone();
+                    let result = execute(task, dependency_hashes, use_cache).await;
+                    (id, result, started.elapsed())
+                });
+            }
+        }
+
+        let Some(joined) = running.join_next().await else {
+            break;
+        };
+        let (id, result, duration) = joined?;
+        let report = match result {
+            Ok(completed) => {
+                passed.insert(id.clone());
+                hashes.insert(id.clone(), completed.hash);
+                let state = if completed.cached {
+                    TaskState::Cached
+                } else {
+                    TaskState::Passed
+                };
+                TaskReport::new(id.clone(), state, Some(duration))
+            }
+            Err(e) => {
+                eprintln!("  ✗ {}: {}", id, e);
+                failed = true;
+                let mut report = TaskReport::new(id.clone(), TaskState::Failed, Some(duration));
+                if let Some(failure) = e.downcast_ref::<ExecFailure>() {
+                    report.exit_code = failure.exit_code;
+                    report.stderr_tail = failure.stderr_tail.clone();
+                }
+                report
+            }
+        };
+        reports.insert(id, report);
+    }
+
+    for id in pending.into_keys() {
+        reports.insert(id.clone(), TaskReport::new(id, TaskState::Skipped, None));
+    }
+
+    let reports: Vec<TaskReport> = order
+        .iter()
+        .filter_map(|id| reports.remove(id))
+        .collect();
+    print_summary(&reports);
+
+    let failures: Vec<String> = reports
+        .iter()
+        .filter(|r| r.state == TaskState::Failed)
+        .map(|r| r.id.to_string())
+        .collect();
+    if !failures.is_empty() {
+        anyhow::bail!("{} task(s) failed: {}", failures.len(), failures.join(", "));
+    }
+
+    Ok(())
+}
+
+fn prepare(config: &Config, id: &TaskId) -> Result<PreparedTask> {
+    let project = &config.projects[&id.project];
+    let task = &project.tasks[&id.task];
+    let tool = config
+        .tools
+        .get(&task.tool)
+        .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", task.tool))?;
+
+    let mut args = task
+        .argv(&tool.command)
+        .map_err(|e| anyhow::anyhow!("{}: {}", id, e))?;
+    let program = args.remove(0);
+    let mut adapter = ToolAdapter::new(task.tool.clone(), program);
+    adapter.env.extend(config.task_env(&id.project, &id.task)?);
+
+    Ok(PreparedTask {
+        id: id.clone(),
+        tool: task.tool.clone(),
+        tool_command: tool.command.clone(),
+        adapter,
+        command: shell_words::join(&args),
+        args,
+        // Use project path for execution
+        dir: PathBuf::from(&project.path),
+        inputs: task.inputs.clone(),
+        outputs: task.outputs.clone(),
+        tool_version: String::new(),
+    })
+}
+
+/// Run a single task, or replay it from `.meta/cache` when its inputs match
+/// a previous successful run. `dependency_hashes` is `None` when a
+/// dependency was not cached, which makes this task uncacheable too.
+async fn execute(
+    task: PreparedTask,
+    dependency_hashes: Option<Vec<String>>,
+    use_cache: bool,
+) -> Result<Completed> {
+    let prefix = task.id.to_string();
+    let cache = TaskCache::new();
+
+    let hash = match dependency_hashes {
+        Some(dependency_hashes) if use_cache && !task.inputs.is_empty() => {
+            Some(cache::compute_hash(&HashInputs {
+                project_dir: &task.dir,
+                inputs: &task.inputs,
+                command: &task.command,
+                tool_command: &task.tool_command,
+                tool_version: &task.tool_version,
+                env: &task.adapter.env,
+                dependency_hashes: &dependency_hashes,
+            })?)
+        }
+        _ => None,
+    };
+
+    if let Some(hash) = &hash {
+        if let Some(log) = cache.restore(&task.id, hash, &task.dir)? {
+            println!("  ✓ {} (cache hit, replaying output)", task.id);
+            for line in log.lines() {
+                println!("[{}] {}", prefix, line);
+            }
+            return Ok(Completed {
+                hash: Some(hash.clone()),
+                cached: true,
+            });
+        }
+    }
+
+    let args: Vec<&str> = task.args.iter().map(String::as_str).collect();
+    let log = task.adapter.execute_in(&args, &task.dir, &prefix).await?;
+
+    if let Some(hash) = &hash {
+        cache.store(&task.id, hash, &task.dir, &task.outputs, &log)?;
+    }
+
+    Ok(Completed {
+        hash,
+        cached: false,
+    })
+}
+
+/// Fail unless each of `tools` is a version its `[tools.<name>]` allows.
+/// `meta run` and `meta dev` check this before starting anything.
+pub async fn check_tool_versions<'a>(
+    config: &Config,
+    tools: impl IntoIterator<Item = &'a str>,
+) -> Result<()> {
+    let tools: BTreeSet<&str> = tools.into_iter().collect();
+    for name in tools {
+        let Some((tool, required)) = config
+            .tools
+            .get(name)
+            .and_then(|tool| Some((tool, tool.version.as_ref()?)))
+        else {
+            continue;
+        };
+        let output = tool_version(&tool.command).await;
+        match Version::find(&output) {
+            Some(version) if required.matches(version) => {}
+            Some(version) => anyhow::bail!(
+                "{} {} does not satisfy version = \"{}\" under [tools.{}] in meta.toml \
+                 (run 'meta doctor' to check every tool)",
+                tool.command,
+                version,
+                required,
+                name
+            ),
+            None => anyhow::bail!(
+                "Could not tell which {} version is installed ('{} --version': {}), but \
+                 [tools.{}] requires version = \"{}\"",
+                tool.command,
+                tool.command,
+                output,
+                name,
+                required
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// First line of `<command> --version`, part of the cache key so that tool
+/// upgrades invalidate cached results
+async fn tool_version(command: &str) -> String {
+    match tokio::process::Command::new(command).arg("--version").output().await {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or("unknown")
+            .trim()
+            .to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn print_summary(reports: &[TaskReport]) {
+    if reports.is_empty() {
+        return;
+    }
+
+    println!("\n## Summary");
+    println!("{:<30} {:<10} {:<6} DURATION", "TASK", "STATUS", "EXIT");
+    println!("{}", "-".repeat(58));
+    for report in reports {
+        let status = match report.state {
+            TaskState::Passed => "✓ passed",
+            TaskState::Cached => "✓ cached",
+            TaskState::Failed => "✗ failed",
+            TaskState::Skipped => "⊘ skipped",
+        };
+        let exit = match (report.state, report.exit_code) {
+            (TaskState::Passed, _) => "0".to_string(),
+            (TaskState::Failed, Some(code)) => code.to_string(),
+            (TaskState::Failed, None) => "signal".to_string(),
+            _ => "-".to_string(),
+        };
+        let duration = report
+            .duration
+            .map(format_duration)
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<30} {:<10} {:<6} {}",
+            report.id.to_string(),
+            status,
+            exit,
+            duration
+        );
+    }
+
+    // Show the end of stderr for each failure so the cause is visible without
+    // scrolling back through interleaved output
+    for report in reports.iter().filter(|r| r.state == TaskState::Failed) {
+        if report.stderr_tail.is_empty() {
+            continue;
+        }
+        println!("\n### {} (stderr, last {} lines)", report.id, report.stderr_tail.len());
+        for line in &report.stderr_tail {
+            println!("  {}", line);
+        }
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs_f64();
+    if secs >= 60.0 {
+        format!("{}m {}s", d.as_secs() / 60, d.as_secs() % 60)
+    } else {
+        format!("{:.1}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration_seconds() {
+        assert_eq!(format_duration(Duration::from_millis(1500)), "1.5s");
+    }
+
+    #[test]
+    fn test_format_duration_minutes() {
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 5s");
+    }
+
+    #[test]
+    fn test_default_jobs_is_at_least_one() {
+        assert!(default_jobs() >= 1);
+    }
+}
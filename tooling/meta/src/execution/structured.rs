@@ -0,0 +1,438 @@
+//! Structured parsing of dev log lines. Recognises tracing-subscriber's fmt
+//! output, JSON lines (tracing, pino, bunyan and similar), cargo/rustc
+//! diagnostics and Next.js output, falling back to level words in plain
+//! text. Log sinks write the result to `.meta/logs/<project>.jsonl`.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{events, rotation::LOG_DIR};
+
+/// Severity of a log line. A `--level` filter also matches the levels above
+/// it.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// A level name as loggers write it: `ERROR`, `warning`, `fatal`, ...
+    fn from_name(name: &str) -> Option<LogLevel> {
+        match name.to_ascii_lowercase().as_str() {
+            "trace" | "verbose" => Some(LogLevel::Trace),
+            "debug" => Some(LogLevel::Debug),
+            "info" | "notice" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" | "err" | "fatal" | "critical" | "panic" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+
+    /// pino and bunyan numeric levels
+    fn from_number(level: i64) -> Option<LogLevel> {
+        match level {
+            ..=10 => Some(LogLevel::Trace),
+            11..=20 => Some(LogLevel::Debug),
+            21..=30 => Some(LogLevel::Info),
+            31..=40 => Some(LogLevel::Warn),
+            _ => Some(LogLevel::Error),
+        }
+    }
+
+    /// The level of a plain-text line, from the words it contains
+    fn from_words(line: &str) -> Option<LogLevel> {
+        static ERROR: OnceLock<Regex> = OnceLock::new();
+        static WARN: OnceLock<Regex> = OnceLock::new();
+        let error = ERROR.get_or_init(|| {
+            Regex::new(r"(?i)\b(error|fatal|panic|panicked|critical|exception|traceback)\b")
+                .expect("valid error pattern")
+        });
+        let warn = WARN
+            .get_or_init(|| Regex::new(r"(?i)\b(warn|warning)\b").expect("valid warn pattern"));
+        if error.is_match(line) {
+            Some(LogLevel::Error)
+        } else if warn.is_match(line) {
+            Some(LogLevel::Warn)
+        } else {
+            None
+        }
+    }
+}
+
+/// A log line split into its parts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedLine {
+    /// Seconds since the epoch, if the line carries a timestamp
+    pub timestamp: Option<u64>,
+    pub level: Option<LogLevel>,
+    /// Module, logger or tool that wrote the line, e.g. `api::db` or `next`
+    pub target: Option<String>,
+    pub message: String,
+}
+
+/// One line of `.meta/logs/<project>.jsonl`
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonRecord {
+    /// When the line was logged, or written to the log if it has no
+    /// timestamp (`YYYY-MM-DDTHH:MM:SS`, UTC)
+    pub timestamp: String,
+    pub level: Option<LogLevel>,
+    pub target: Option<String>,
+    pub message: String,
+}
+
+impl JsonRecord {
+    /// `written` is the time the line reached the log
+    pub fn new(line: &str, written: u64) -> JsonRecord {
+        let parsed = parse_line(line);
+        JsonRecord {
+            timestamp: events::format_timestamp(parsed.timestamp.unwrap_or(written)),
+            level: parsed.level,
+            target: parsed.target,
+            message: parsed.message,
+        }
+    }
+}
+
+pub fn jsonl_path(project: &str) -> PathBuf {
+    Path::new(LOG_DIR).join(format!("{}.jsonl", project))
+}
+
+/// Parse a log line, already stripped of escape codes
+pub fn parse_line(line: &str) -> ParsedLine {
+    parse_json(line)
+        .or_else(|| parse_tracing(line))
+        .or_else(|| parse_rustc(line))
+        .or_else(|| parse_next(line))
+        .unwrap_or_else(|| ParsedLine {
+            timestamp: events::parse_timestamp(line),
+            level: LogLevel::from_words(line),
+            target: None,
+            message: line.trim().to_string(),
+        })
+}
+
+/// A JSON object with the usual field names: tracing-subscriber's json
+/// format (`fields.message`), pino/bunyan (`msg`, numeric `level`) and most
+/// others (`message`, `level`/`severity`, `timestamp`/`time`)
+fn parse_json(line: &str) -> Option<ParsedLine> {
+    let trimmed = line.trim();
+    if !trimmed.starts_with('{') {
+        return None;
+    }
+    let Value::Object(object) = serde_json::from_str::<Value>(trimmed).ok()? else {
+        return None;
+    };
+    let field = |names: &[&str]| names.iter().find_map(|name| object.get(*name));
+
+    let level = match field(&["level", "severity", "lvl", "log.level"]) {
+        Some(Value::String(name)) => LogLevel::from_name(name),
+        Some(Value::Number(number)) => number.as_i64().and_then(LogLevel::from_number),
+        _ => None,
+    };
+    let message = field(&["message", "msg"])
+        .or_else(|| object.get("fields").and_then(|fields| fields.get("message")))
+        .map(|message| match message {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        })
+        .unwrap_or_else(|| trimmed.to_string());
+    let timestamp = match field(&["timestamp", "time", "ts", "@timestamp"]) {
+        Some(Value::String(text)) => events::parse_timestamp(text),
+        // Epoch milliseconds (pino) or seconds
+        Some(Value::Number(number)) => number
+            .as_u64()
+            .map(|n| if n > 100_000_000_000 { n / 1000 } else { n }),
+        _ => None,
+    };
+    let target = field(&["target", "logger", "name", "module"])
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    Some(ParsedLine {
+        timestamp,
+        level,
+        target,
+        message,
+    })
+}
+
+/// tracing-subscriber's default fmt output:
+/// `2025-12-08T12:02:18.123456Z  INFO request{id=1}: api::db: connected`
+fn parse_tracing(line: &str) -> Option<ParsedLine> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"^\s*(\d{4}-\d{2}-\d{2}T\S+)?\s*(TRACE|DEBUG|INFO|WARN|ERROR)\s+(.*)$")
+            .expect("valid tracing pattern")
+    });
+    let caps = pattern.captures(line)?;
+    let mut rest = caps.get(3).map_or("", |m| m.as_str());
+    let mut target = None;
+
+    // Span contexts (`name{fields}:`) come first, then the target if it's
+    // enabled
+    while let Some((segment, remainder)) = rest.split_once(": ") {
+        let is_span = segment
+            .find('{')
+            .is_some_and(|open| is_path(&segment[..open]) && segment.ends_with('}'));
+        if is_span {
+            rest = remainder;
+        } else {
+            if is_path(segment) && segment.contains("::") {
+                target = Some(segment.to_string());
+                rest = remainder;
+            }
+            break;
+        }
+    }
+
+    Some(ParsedLine {
+        timestamp: caps.get(1).and_then(|m| events::parse_timestamp(m.as_str())),
+        level: LogLevel::from_name(&caps[2]),
+        target,
+        message: rest.trim().to_string(),
+    })
+}
+
+/// `api`, `api::db` or `tower_http::trace`
+fn is_path(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .split("::")
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_'))
+}
+
+/// cargo and rustc output: `error[E0308]: mismatched types`, `warning:
+/// unused variable`, `   Compiling api v0.1.0`
+fn parse_rustc(line: &str) -> Option<ParsedLine> {
+    static DIAGNOSTIC: OnceLock<Regex> = OnceLock::new();
+    static PROGRESS: OnceLock<Regex> = OnceLock::new();
+    let diagnostic = DIAGNOSTIC.get_or_init(|| {
+        Regex::new(r"^(error|warning)(\[[A-Z]\d{4}\])?: (.+)$").expect("valid diagnostic pattern")
+    });
+    let progress = PROGRESS.get_or_init(|| {
+        Regex::new(r"^\s+(Compiling|Checking|Finished|Running|Blocking|Fresh|Building) \S")
+            .expect("valid cargo pattern")
+    });
+
+    if let Some(caps) = diagnostic.captures(line) {
+        let message = match caps.get(2) {
+            Some(code) => format!("{} {}", code.as_str(), &caps[3]),
+            None => caps[3].to_string(),
+        };
+        return Some(ParsedLine {
+            timestamp: None,
+            level: LogLevel::from_name(&caps[1]),
+            target: Some("rustc".to_string()),
+            message,
+        });
+    }
+    if progress.is_match(line) {
+        return Some(ParsedLine {
+            timestamp: None,
+            level: Some(LogLevel::Info),
+            target: Some("cargo".to_string()),
+            message: line.trim().to_string(),
+        });
+    }
+    None
+}
+
+/// Next.js dev server output: ` ✓ Ready in 2.3s`, ` ⚠ ...`, ` ⨯ ...`, and
+/// the `error - ...` / `wait  - ...` prefixes of older versions
+fn parse_next(line: &str) -> Option<ParsedLine> {
+    static PREFIXED: OnceLock<Regex> = OnceLock::new();
+    let prefixed = PREFIXED.get_or_init(|| {
+        Regex::new(r"^(error|warn|info|event|wait|ready)\s+- (.*)$").expect("valid next pattern")
+    });
+
+    let trimmed = line.trim_start();
+    let (level, message) = if let Some(caps) = prefixed.captures(trimmed) {
+        let level = match &caps[1] {
+            "error" => LogLevel::Error,
+            "warn" => LogLevel::Warn,
+            _ => LogLevel::Info,
+        };
+        (level, caps[2].to_string())
+    } else {
+        let mut chars = trimmed.chars();
+        let level = match chars.next()? {
+            '⨯' => LogLevel::Error,
+            '⚠' => LogLevel::Warn,
+            '✓' | '○' | '▲' | '◐' => LogLevel::Info,
+            _ => return None,
+        };
+        (level, chars.as_str().trim().to_string())
+    };
+    Some(ParsedLine {
+        timestamp: None,
+        level: Some(level),
+        target: Some("next".to_string()),
+        message,
+    })
+}
+
+/// Errors and warnings in a project's structured log
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LevelCounts {
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+/// Count the errors and warnings a project logged at or after `since`
+/// (seconds since the epoch), from its current `.jsonl` file
+pub fn count_levels(project: &str, since: u64) -> LevelCounts {
+    count_levels_in(&jsonl_path(project), since)
+}
+
+fn count_levels_in(path: &Path, since: u64) -> LevelCounts {
+    let mut counts = LevelCounts::default();
+    let Ok(file) = File::open(path) else {
+        return counts;
+    };
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        let Ok(record) = serde_json::from_str::<JsonRecord>(&line) else {
+            continue;
+        };
+        if events::parse_timestamp(&record.timestamp).map_or(true, |t| t < since) {
+            continue;
+        }
+        match record.level {
+            Some(LogLevel::Error) => counts.errors += 1,
+            Some(LogLevel::Warn) => counts.warnings += 1,
+            _ => {}
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(level: Option<LogLevel>, target: Option<&str>, message: &str) -> ParsedLine {
+        ParsedLine {
+            timestamp: None,
+            level,
+            target: target.map(str::to_string),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_tracing_fmt() {
+        let line = "2024-12-08T12:02:18.123456Z  INFO api::db: connected to postgres";
+        let mut expected = parsed(Some(LogLevel::Info), Some("api::db"), "connected to postgres");
+        expected.timestamp = Some(1_733_659_338);
+        assert_eq!(parse_line(line), expected);
+
+        // with_target(false), as apps/api logs, and span contexts
+        let line = "2024-12-08T12:02:18Z  WARN request{id=7 path=/}: slow query: 2s";
+        let parsed_line = parse_line(line);
+        assert_eq!(parsed_line.level, Some(LogLevel::Warn));
+        assert_eq!(parsed_line.target, None);
+        assert_eq!(parsed_line.message, "slow query: 2s");
+
+        assert_eq!(
+            parse_line("ERROR tower_http::trace: request failed"),
+            parsed(Some(LogLevel::Error), Some("tower_http::trace"), "request failed")
+        );
+    }
+
+    #[test]
+    fn test_parse_json_lines() {
+        let tracing = r#"{"timestamp":"2024-12-08T12:02:18.1Z","level":"ERROR","fields":{"message":"db down"},"target":"api::db"}"#;
+        let mut expected = parsed(Some(LogLevel::Error), Some("api::db"), "db down");
+        expected.timestamp = Some(1_733_659_338);
+        assert_eq!(parse_line(tracing), expected);
+
+        let pino = r#"{"level":40,"time":1733659338000,"name":"web","msg":"slow render"}"#;
+        let mut expected = parsed(Some(LogLevel::Warn), Some("web"), "slow render");
+        expected.timestamp = Some(1_733_659_338);
+        assert_eq!(parse_line(pino), expected);
+
+        assert_eq!(parse_line("{not json").target, None);
+    }
+
+    #[test]
+    fn test_parse_rustc_diagnostics() {
+        assert_eq!(
+            parse_line("error[E0308]: mismatched types"),
+            parsed(Some(LogLevel::Error), Some("rustc"), "[E0308] mismatched types")
+        );
+        assert_eq!(
+            parse_line("warning: unused variable: `x`"),
+            parsed(Some(LogLevel::Warn), Some("rustc"), "unused variable: `x`")
+        );
+        assert_eq!(
+            parse_line("   Compiling api v0.1.0 (/repo/apps/api)"),
+            parsed(Some(LogLevel::Info), Some("cargo"), "Compiling api v0.1.0 (/repo/apps/api)")
+        );
+    }
+
+    #[test]
+    fn test_parse_next_output() {
+        assert_eq!(
+            parse_line(" ✓ Ready in 2.3s"),
+            parsed(Some(LogLevel::Info), Some("next"), "Ready in 2.3s")
+        );
+        assert_eq!(
+            parse_line(" ⨯ ./app/page.tsx:3:1 Module not found"),
+            parsed(Some(LogLevel::Error), Some("next"), "./app/page.tsx:3:1 Module not found")
+        );
+        assert_eq!(
+            parse_line("warn  - Fast Refresh had to perform a full reload"),
+            parsed(Some(LogLevel::Warn), Some("next"), "Fast Refresh had to perform a full reload")
+        );
+    }
+
+    #[test]
+    fn test_parse_plain_text_falls_back_to_words() {
+        assert_eq!(
+            parse_line("thread 'main' panicked at src/main.rs:3:5"),
+            parsed(Some(LogLevel::Error), None, "thread 'main' panicked at src/main.rs:3:5")
+        );
+        assert_eq!(parse_line("[WARN] deprecated option").level, Some(LogLevel::Warn));
+        assert_eq!(parse_line("0 errors, listening on :3000").level, None);
+    }
+
+    #[test]
+    fn test_count_levels_since() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("api.jsonl");
+        let lines: Vec<String> = [
+            ("2024-12-08T12:00:00 ERROR old", 0),
+            ("ERROR db down", 1_733_659_338),
+            ("warning: unused import", 1_733_659_338),
+            ("listening", 1_733_659_338),
+        ]
+        .iter()
+        .map(|(line, written)| serde_json::to_string(&JsonRecord::new(line, *written)).unwrap())
+        .collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        assert_eq!(
+            count_levels_in(&path, 1_733_659_300),
+            LevelCounts {
+                errors: 1,
+                warnings: 1
+            }
+        );
+        assert_eq!(count_levels_in(&path, 0).errors, 2);
+    }
+}
@@ -0,0 +1,409 @@
+use std::{
+    io::IsTerminal,
+    process::{ExitStatus, Stdio},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    process::Command,
+    sync::watch,
+    task::JoinSet,
+    time::Instant,
+};
+
+use super::{events::log_event, ready::Readiness, rotation::LogWriter, DevCommand};
+use crate::config::{LogsConfig, RestartPolicy};
+
+/// ANSI colors cycled through for `[project]` prefixes
+const COLORS: &[&str] = &["36", "35", "33", "32", "34", "31"];
+
+/// How long children get to exit after Ctrl+C before they are killed
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+/// Upper bound for the delay between automatic restarts
+pub const MAX_BACKOFF_SECS: u64 = 30;
+
+/// Run every dev command as a child process without tmux.
+///
+/// Output is streamed to the terminal with a `[project]` prefix and appended
+/// to `.meta/logs/<project>.log`, rotated per `[logs]`; START/EXIT/RESTART
+/// events go to `dev.log` in the same format as the tmux wrapper. Processes
+/// start once the projects in their `after` list are ready and are restarted
+/// according to their `restart` policy. Each child gets its own process group
+/// so Ctrl+C can be forwarded to everything it spawned; anything still running
+/// after a grace period, or after a second Ctrl+C, is killed. Returns once all
+/// children have exited.
+pub async fn supervise(commands: &[(String, DevCommand)], logs: &LogsConfig) -> Result<()> {
+    let color = std::io::stdout().is_terminal();
+
+    println!("✨ Supervising {} processes (Ctrl+C to stop)\n", commands.len());
+
+    // Signal to forward to every process group, set on Ctrl+C
+    let (shutdown, _) = watch::channel(None);
+    let mut running = JoinSet::new();
+    let mut readiness = Readiness::default();
+    let mut interrupts = 0;
+    for (i, (name, command)) in commands.iter().enumerate() {
+        tokio::select! {
+            _ = readiness.wait_for(&command.after) => {}
+            _ = tokio::signal::ctrl_c() => {
+                interrupts = 1;
+                break;
+            }
+        }
+        readiness.starting(name, command.ready.clone());
+
+        let prefix = prefix(name, i, color);
+        let log = LogWriter::open(name, logs)?;
+
+        let process = Supervised {
+            name: name.clone(),
+            command: command.clone(),
+            prefix,
+            log: Arc::new(Mutex::new(log)),
+        };
+        let shutdown = shutdown.subscribe();
+        running.spawn(async move {
+            let result = process.run(shutdown).await;
+            (process.name, result)
+        });
+    }
+
+    // Processes still running this long after Ctrl+C are killed
+    let grace = tokio::time::sleep(SHUTDOWN_GRACE);
+    tokio::pin!(grace);
+    let mut grace_armed = false;
+    if interrupts > 0 {
+        // Ctrl+C while waiting for a dependency: stop what already started
+        println!("\n⏹  Stopping dev processes (INT)...");
+        shutdown.send_replace(Some("-INT"));
+        grace_armed = true;
+    }
+    let mut failures = Vec::new();
+    loop {
+        tokio::select! {
+            joined = running.join_next() => {
+                let Some(joined) = joined else {
+                    break;
+                };
+                let (name, code) = joined?;
+                if code? != Some(0) && interrupts == 0 {
+                    failures.push(name);
+                }
+            }
+            _ = tokio::signal::ctrl_c() => {
+                interrupts += 1;
+                let signal = if interrupts == 1 { "-INT" } else { "-KILL" };
+                println!("\n⏹  Stopping dev processes ({})...", signal.trim_start_matches('-'));
+                shutdown.send_replace(Some(signal));
+                if !grace_armed {
+                    grace.as_mut().reset(Instant::now() + SHUTDOWN_GRACE);
+                    grace_armed = true;
+                }
+            }
+            _ = &mut grace, if grace_armed => {
+                shutdown.send_replace(Some("-KILL"));
+                grace_armed = false;
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        anyhow::bail!("{} dev process(es) failed: {}", failures.len(), failures.join(", "));
+    }
+    Ok(())
+}
+
+/// One dev process and where its output goes
+struct Supervised {
+    name: String,
+    command: DevCommand,
+    prefix: String,
+    log: Arc<Mutex<LogWriter>>,
+}
+
+impl Supervised {
+    /// Run the process until it stays down, restarting it according to its
+    /// policy. Returns the last exit code.
+    async fn run(&self, mut shutdown: watch::Receiver<Option<&'static str>>) -> Result<Option<i32>> {
+        let mut tracker = RestartTracker::new(
+            self.command.restart,
+            self.command.max_restarts,
+            Duration::from_secs(self.command.restart_window),
+        );
+        loop {
+            let code = self.run_once(&mut shutdown).await?;
+            if shutdown.borrow().is_some() {
+                return Ok(code);
+            }
+
+            let exit = code.map_or_else(|| "unknown".to_string(), |c| c.to_string());
+            match tracker.next(code, std::time::Instant::now()) {
+                Restart::No => return Ok(code),
+                Restart::LimitReached => {
+                    log_event(
+                        &self.name,
+                        &format!(
+                            "STOPPED: Restart limit reached ({} restarts in {}s)",
+                            self.command.max_restarts, self.command.restart_window
+                        ),
+                    );
+                    return Ok(code);
+                }
+                Restart::After { attempt, delay } => {
+                    log_event(
+                        &self.name,
+                        &format!(
+                            "RESTART: Restarting after exit code {} (attempt {}/{}, backoff {}s)",
+                            exit,
+                            attempt,
+                            self.command.max_restarts,
+                            delay.as_secs()
+                        ),
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = shutdown.changed() => return Ok(code),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Start the process once and wait for it to exit, forwarding shutdown
+    /// signals to its process group
+    async fn run_once(
+        &self,
+        shutdown: &mut watch::Receiver<Option<&'static str>>,
+    ) -> Result<Option<i32>> {
+        let command = &self.command;
+        let mut cmd = Command::new(&command.argv[0]);
+        cmd.args(&command.argv[1..]);
+        cmd.envs(&command.env);
+        if let Some(dir) = &command.dir {
+            cmd.current_dir(dir);
+        }
+        cmd.stdin(Stdio::null());
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+        // The terminal's Ctrl+C only reaches meta; it is forwarded to the
+        // whole group so dev servers spawned by the tool stop too
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Failed to start {}: {}", self.name, e))?;
+        let pid = child.id().unwrap_or_default();
+        log_event(&self.name, &format!("START: Process started (pid={})", pid));
+
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
+        let exited = async {
+            let (status, _, _) = tokio::join!(
+                child.wait(),
+                stream_lines(stdout, &self.prefix, false, &self.log),
+                stream_lines(stderr, &self.prefix, true, &self.log),
+            );
+            status
+        };
+        tokio::pin!(exited);
+
+        let status = loop {
+            tokio::select! {
+                status = &mut exited => break status,
+                Ok(()) = shutdown.changed() => {
+                    let signal = *shutdown.borrow();
+                    if let Some(signal) = signal {
+                        signal_group(pid, signal).await;
+                    }
+                }
+            }
+        };
+
+        let code = status.ok().and_then(exit_code);
+        let exit = code.map_or_else(|| "unknown".to_string(), |c| c.to_string());
+        log_event(&self.name, &format!("EXIT: Process exited with code {}", exit));
+        Ok(code)
+    }
+}
+
+/// What to do after a supervised process exits
+#[derive(Debug, PartialEq, Eq)]
+enum Restart {
+    No,
+    After { attempt: u32, delay: Duration },
+    LimitReached,
+}
+
+/// Applies a restart policy: exponential backoff from 1s (capped at 30s) and
+/// at most `max_restarts` restarts within `window`
+struct RestartTracker {
+    policy: RestartPolicy,
+    max_restarts: u32,
+    window: Duration,
+    attempts: u32,
+    window_start: std::time::Instant,
+}
+
+impl RestartTracker {
+    fn new(policy: RestartPolicy, max_restarts: u32, window: Duration) -> Self {
+        Self {
+            policy,
+            max_restarts,
+            window,
+            attempts: 0,
+            window_start: std::time::Instant::now(),
+        }
+    }
+
+    fn next(&mut self, exit_code: Option<i32>, now: std::time::Instant) -> Restart {
+        if !self.policy.should_restart(exit_code) {
+            return Restart::No;
+        }
+        if now.duration_since(self.window_start) > self.window {
+            self.attempts = 0;
+            self.window_start = now;
+        }
+        if self.attempts >= self.max_restarts {
+            return Restart::LimitReached;
+        }
+        self.attempts += 1;
+        let delay = Duration::from_secs(2u64.saturating_pow(self.attempts - 1).min(MAX_BACKOFF_SECS));
+        Restart::After {
+            attempt: self.attempts,
+            delay,
+        }
+    }
+}
+
+/// Print each line with `prefix` and append it (without escape codes) to `log`
+async fn stream_lines(
+    reader: impl AsyncRead + Unpin,
+    prefix: &str,
+    to_stderr: bool,
+    log: &Mutex<LogWriter>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if to_stderr {
+            eprintln!("{} {}", prefix, line);
+        } else {
+            println!("{} {}", prefix, line);
+        }
+        if let Ok(mut log) = log.lock() {
+            let _ = log.write_line(&strip_ansi(&line));
+        }
+    }
+}
+
+/// Send `signal` (e.g. `-INT`) to the process group led by `pid`
+async fn signal_group(pid: u32, signal: &str) {
+    let _ = Command::new("kill")
+        .args([signal, "--", &format!("-{}", pid)])
+        .output()
+        .await;
+}
+
+/// Exit code as a shell reports it: 128 + signal number for killed processes
+fn exit_code(status: ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        status.code().or_else(|| status.signal().map(|s| 128 + s))
+    }
+    #[cfg(not(unix))]
+    {
+        status.code()
+    }
+}
+
+/// `[name]`, colored by the project's position when `color` is set
+pub fn prefix(name: &str, index: usize, color: bool) -> String {
+    if color {
+        format!("\x1b[{}m[{}]\x1b[0m", COLORS[index % COLORS.len()], name)
+    } else {
+        format!("[{}]", name)
+    }
+}
+
+/// Remove CSI escape sequences (colors, cursor movement, erase) so log files
+/// stay readable
+pub fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            chars.next();
+            // Parameter bytes up to and including the final byte (@ to ~)
+            for next in chars.by_ref() {
+                if ('@'..='~').contains(&next) {
+                    break;
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_tracker_backs_off_and_gives_up() {
+        let start = std::time::Instant::now();
+        let mut tracker = RestartTracker::new(RestartPolicy::OnFailure, 3, Duration::from_secs(60));
+
+        assert_eq!(tracker.next(Some(0), start), Restart::No);
+        let delays: Vec<Restart> = (0..3).map(|_| tracker.next(Some(1), start)).collect();
+        assert_eq!(
+            delays,
+            [1, 2, 4]
+                .iter()
+                .enumerate()
+                .map(|(i, secs)| Restart::After {
+                    attempt: i as u32 + 1,
+                    delay: Duration::from_secs(*secs),
+                })
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(tracker.next(Some(1), start), Restart::LimitReached);
+
+        // A new window starts once the old one has passed
+        let later = start + Duration::from_secs(61);
+        assert_eq!(
+            tracker.next(Some(1), later),
+            Restart::After {
+                attempt: 1,
+                delay: Duration::from_secs(1),
+            }
+        );
+    }
+
+    #[test]
+    fn test_restart_tracker_caps_backoff() {
+        let start = std::time::Instant::now();
+        let mut tracker = RestartTracker::new(RestartPolicy::Always, 10, Duration::from_secs(600));
+        let last = (0..10).map(|_| tracker.next(Some(0), start)).last().unwrap();
+        assert_eq!(
+            last,
+            Restart::After {
+                attempt: 10,
+                delay: Duration::from_secs(MAX_BACKOFF_SECS),
+            }
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1;32mCompiling\x1b[0m api"), "Compiling api");
+        assert_eq!(strip_ansi("\x1b[2K\x1b[1Gdone"), "done");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+}
@@ -0,0 +1,305 @@
+//! `meta status --watch`: a compact status table redrawn in place every few
+//! seconds, for keeping an eye on dev processes without attaching to tmux
+
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use tokio::process::Command;
+
+use super::{
+    events, find_active_pid, format_uptime, get_session_name, get_tmux_panes,
+    process::{ProcessInfo, ProcessTable},
+    rust_binary_path, stale_by,
+    supervisor::strip_ansi,
+};
+use crate::config::Config;
+
+/// How much of the end of a project log is read to find its last line
+const LAST_LINE_WINDOW: u64 = 8 * 1024;
+/// Terminal width used when it cannot be detected
+const DEFAULT_WIDTH: usize = 120;
+
+/// One refresh of the dashboard
+struct Dashboard {
+    session: String,
+    session_active: bool,
+    rows: Vec<Row>,
+}
+
+struct Row {
+    name: String,
+    state: &'static str,
+    pid: Option<u32>,
+    uptime_seconds: Option<u64>,
+    restarts: u32,
+    /// Rebuild age or staleness of a Rust project's binary
+    binary: Option<String>,
+    last_log: Option<String>,
+}
+
+/// Redraw the status table every `interval` until Ctrl+C
+pub async fn watch(config: &Config, project: Option<String>, interval: Duration) -> Result<()> {
+    loop {
+        let dashboard = collect(config, project.as_deref()).await;
+        draw(&render(&dashboard, interval, terminal_width().await))?;
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => {
+                println!();
+                return Ok(());
+            }
+        }
+    }
+}
+
+async fn collect(config: &Config, project: Option<&str>) -> Dashboard {
+    let session = get_session_name();
+    let session_active = Command::new("tmux")
+        .args(["has-session", "-t", &session])
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    let panes = if session_active {
+        get_tmux_panes(&session).await.unwrap_or_default()
+    } else {
+        vec![]
+    };
+
+    let processes = ProcessTable::snapshot().await;
+    let dev_log = std::fs::read_to_string(".meta/logs/dev.log").unwrap_or_default();
+    let mut rows = Vec::new();
+    for (name, proj) in &config.projects_with_dev_task() {
+        if project.is_some_and(|filter| *name != filter) {
+            continue;
+        }
+
+        let pane = panes.iter().find(|p| &p.title == name);
+        let stopped = pane.is_some() && events::is_stopped(&dev_log, name);
+        let info = match pane {
+            Some(pane) if !stopped => {
+                find_active_pid(&processes, pane.pid, &session, Some(pane.index))
+                    .await
+                    .and_then(|pid| processes.get(pid))
+            }
+            _ => None,
+        };
+
+        rows.push(Row {
+            name: name.clone(),
+            state: match (stopped, info) {
+                (true, _) => "stopped",
+                (false, Some(_)) => "running",
+                (false, None) => "not running",
+            },
+            pid: info.map(|i| i.pid),
+            uptime_seconds: info.map(|i| i.uptime_seconds),
+            restarts: events::restart_stats(&dev_log, name).count,
+            binary: rust_binary_path(name, proj).map(|path| binary_state(&path, info)),
+            last_log: last_line(Path::new(&format!(".meta/logs/{}.log", name))),
+        });
+    }
+    rows.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Dashboard {
+        session,
+        session_active,
+        rows,
+    }
+}
+
+/// `built 5m ago`, or a staleness warning if the binary was rebuilt after
+/// the running process started
+fn binary_state(path: &str, info: Option<&ProcessInfo>) -> String {
+    let Ok(modified) = std::fs::metadata(path).and_then(|m| m.modified()) else {
+        return "not built".to_string();
+    };
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default()
+        .as_secs();
+    match info.and_then(|i| stale_by(age, i.uptime_seconds)) {
+        Some(diff_secs) => format!("⚠ STALE +{}m", diff_secs / 60),
+        None => format!("built {} ago", format_uptime(age)),
+    }
+}
+
+/// The last non-empty line of a log file, without reading all of it
+fn last_line(path: &Path) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(LAST_LINE_WINDOW))).ok()?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).ok()?;
+    // Progress output redraws a line with \r, so its last segment is what a
+    // terminal would show
+    String::from_utf8_lossy(&tail)
+        .split(['\n', '\r'])
+        .map(|line| strip_ansi(line).trim().to_string())
+        .rev()
+        .find(|line| !line.is_empty())
+}
+
+fn render(dashboard: &Dashboard, interval: Duration, width: usize) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let mut lines = vec![
+        format!(
+            "meta status — {} · every {} · {} · Ctrl+C to quit",
+            dashboard.session,
+            format_interval(interval),
+            events::format_timestamp(now)
+        ),
+        String::new(),
+    ];
+    if !dashboard.session_active {
+        lines.push(format!(
+            "⚠️  No active {} session. Run 'meta dev' to start.",
+            dashboard.session
+        ));
+        lines.push(String::new());
+    }
+
+    lines.push(format!(
+        "{:<15} {:<11} {:<8} {:<9} {:<8} {:<18} LAST LOG",
+        "PROJECT", "STATE", "PID", "UPTIME", "RESTARTS", "BINARY"
+    ));
+    for row in &dashboard.rows {
+        let prefix = format!(
+            "{:<15} {:<11} {:<8} {:<9} {:<8} {:<18} ",
+            row.name,
+            row.state,
+            row.pid.map_or("-".to_string(), |pid| pid.to_string()),
+            row.uptime_seconds.map_or("-".to_string(), format_uptime),
+            row.restarts,
+            row.binary.as_deref().unwrap_or("-"),
+        );
+        let room = width.saturating_sub(prefix.chars().count());
+        lines.push(prefix + &truncate(row.last_log.as_deref().unwrap_or("-"), room));
+    }
+    if dashboard.rows.is_empty() {
+        lines.push("(no projects with a dev task)".to_string());
+    }
+    lines.join("\n")
+}
+
+/// `2s`, or `500ms` for sub-second intervals
+fn format_interval(interval: Duration) -> String {
+    if interval.subsec_millis() == 0 {
+        format_uptime(interval.as_secs())
+    } else {
+        format!("{}ms", interval.as_millis())
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(max.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
+/// Draw a frame over the previous one: home the cursor, clear each line
+/// after its text and everything below, so the screen doesn't flicker
+fn draw(frame: &str) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    write!(stdout, "\x1b[H")?;
+    for line in frame.lines() {
+        writeln!(stdout, "{}\x1b[K", line)?;
+    }
+    write!(stdout, "\x1b[J")?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Columns of the controlling terminal, from `$COLUMNS` or `stty size`
+async fn terminal_width() -> usize {
+    if let Some(columns) = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) {
+        return columns;
+    }
+    let Ok(output) = Command::new("stty")
+        .arg("size")
+        .stdin(std::process::Stdio::inherit())
+        .output()
+        .await
+    else {
+        return DEFAULT_WIDTH;
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .nth(1)
+        .and_then(|c| c.parse().ok())
+        .filter(|c| *c > 0)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_line_skips_blank_lines_and_escapes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("api.log");
+        std::fs::write(
+            &path,
+            "starting\n\x1b[32mListening on :3000\x1b[0m\nbuilding 10%\rbuilding 100%\n\n",
+        )
+        .unwrap();
+        assert_eq!(last_line(&path).as_deref(), Some("building 100%"));
+        assert_eq!(last_line(&temp_dir.path().join("missing.log")), None);
+    }
+
+    #[test]
+    fn test_render_truncates_last_log_to_width() {
+        let dashboard = Dashboard {
+            session: "meta-app".to_string(),
+            session_active: true,
+            rows: vec![
+                Row {
+                    name: "api".to_string(),
+                    state: "running",
+                    pid: Some(4242),
+                    uptime_seconds: Some(303),
+                    restarts: 2,
+                    binary: Some("⚠ STALE +3m".to_string()),
+                    last_log: Some("x".repeat(200)),
+                },
+                Row {
+                    name: "web".to_string(),
+                    state: "not running",
+                    pid: None,
+                    uptime_seconds: None,
+                    restarts: 0,
+                    binary: None,
+                    last_log: None,
+                },
+            ],
+        };
+        let frame = render(&dashboard, Duration::from_secs(2), 100);
+        let lines: Vec<&str> = frame.lines().collect();
+
+        assert!(lines[0].starts_with("meta status — meta-app · every 2s · "));
+        assert!(lines[3].starts_with("api             running     4242     5m 3s     2        ⚠ STALE +3m"));
+        assert_eq!(lines[3].chars().count(), 100);
+        assert!(lines[3].ends_with("x…"));
+        assert!(lines[4].starts_with("web             not running -        -         0        -"));
+        assert!(lines[4].ends_with(" -"));
+    }
+
+    #[test]
+    fn test_format_interval() {
+        assert_eq!(format_interval(Duration::from_secs(2)), "2s");
+        assert_eq!(format_interval(Duration::from_millis(500)), "500ms");
+    }
+}
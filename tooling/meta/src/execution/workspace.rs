@@ -0,0 +1,493 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::config::Config;
+
+/// Project → projects it depends on.
+///
+/// Combines the explicit `depends_on` from meta.toml with dependencies
+/// inferred from the projects themselves:
+/// - Cargo path dependencies (`foo = { path = "../foo" }`, including
+///   `workspace = true` entries that resolve to a path in the workspace root)
+/// - package.json dependencies naming another project's package
+pub fn project_dependencies(config: &Config) -> BTreeMap<String, BTreeSet<String>> {
+    let by_path: BTreeMap<PathBuf, Vec<&String>> =
+        config
+            .projects
+            .iter()
+            .fold(BTreeMap::new(), |mut map, (name, project)| {
+                map.entry(normalize(Path::new(&project.path)))
+                    .or_insert_with(Vec::new)
+                    .push(name);
+                map
+            });
+    let by_package: BTreeMap<String, &String> = config
+        .projects
+        .iter()
+        .filter_map(|(name, project)| package_json_name(&project.path).map(|pkg| (pkg, name)))
+        .collect();
+
+    let mut deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (name, project) in &config.projects {
+        let entry = deps.entry(name.clone()).or_default();
+        entry.extend(project.depends_on.iter().cloned());
+
+        for dep_path in cargo_path_dependencies(&project.path) {
+            for &dep in by_path.get(&dep_path).into_iter().flatten() {
+                entry.insert(dep.clone());
+            }
+        }
+
+        for package in package_json_dependencies(&project.path) {
+            if let Some(&dep) = by_package.get(&package) {
+                entry.insert(dep.clone());
+            }
+        }
+
+        // Projects sharing a path (e.g. an app and its android target)
+        // would otherwise depend on themselves
+        entry.remove(name);
+    }
+
+    deps
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !result.pop() {
+                    result.push("..");
+                }
+            }
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+/// Normalized paths of every `path = "..."` dependency in a Cargo.toml
+fn cargo_path_dependencies(project_path: &str) -> Vec<PathBuf> {
+    let project_dir = Path::new(project_path);
+    let Some(manifest) = read_toml(&project_dir.join("Cargo.toml")) else {
+        return vec![];
+    };
+
+    // `dep = { workspace = true }` points into [workspace.dependencies]
+    let workspace = super::detect_cargo_workspace(project_path)
+        .and_then(|root| read_toml(&Path::new(&root).join("Cargo.toml")).map(|t| (root, t)));
+    let workspace_deps = workspace.as_ref().and_then(|(root, manifest)| {
+        manifest
+            .get("workspace")
+            .and_then(|w| w.get("dependencies"))
+            .and_then(|d| d.as_table())
+            .map(|table| (root, table))
+    });
+
+    let mut tables: Vec<&toml::Table> = Vec::new();
+    for key in ["dependencies", "dev-dependencies", "build-dependencies"] {
+        if let Some(table) = manifest.get(key).and_then(|t| t.as_table()) {
+            tables.push(table);
+        }
+        if let Some(targets) = manifest.get("target").and_then(|t| t.as_table()) {
+            for target in targets.values() {
+                if let Some(table) = target.get(key).and_then(|t| t.as_table()) {
+                    tables.push(table);
+                }
+            }
+        }
+    }
+
+    let mut paths = Vec::new();
+    for (dep_name, spec) in tables.into_iter().flatten() {
+        if let Some(path) = spec.get("path").and_then(|p| p.as_str()) {
+            paths.push(normalize(&project_dir.join(path)));
+        } else if spec.get("workspace").and_then(|w| w.as_bool()) == Some(true) {
+            let resolved = workspace_deps.and_then(|(root, table)| {
+                let path = table.get(dep_name)?.get("path")?.as_str()?;
+                Some(normalize(&Path::new(root).join(path)))
+            });
+            paths.extend(resolved);
+        }
+    }
+    paths
+}
+
+/// Directory holding turbo.json at or above `dir`
+pub fn turbo_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|ancestor| ancestor.join("turbo.json").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Tasks a turbo.json defines, under `tasks` (turbo 2) or `pipeline`
+/// (turbo 1): plain names such as `dev` and package tasks such as
+/// `web#dev`. None if there is no readable turbo.json in `dir`.
+pub fn turbo_tasks(dir: &Path) -> Option<BTreeSet<String>> {
+    let contents = fs::read_to_string(dir.join("turbo.json")).ok()?;
+    let turbo: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    let tasks = turbo.get("tasks").or_else(|| turbo.get("pipeline"));
+    Some(
+        tasks
+            .and_then(|t| t.as_object())
+            .map(|tasks| tasks.keys().cloned().collect())
+            .unwrap_or_default(),
+    )
+}
+
+/// Packages of the JavaScript workspace at `root` by name, from the
+/// `workspaces` in its package.json or the `packages` in
+/// pnpm-workspace.yaml
+pub fn js_packages(root: &Path) -> BTreeMap<String, PathBuf> {
+    let mut patterns: Vec<String> = read_package_json(&root.to_string_lossy())
+        .and_then(|package| {
+            let workspaces = package.get("workspaces")?;
+            // Yarn also accepts `{ "packages": [...] }`
+            let list = workspaces.get("packages").unwrap_or(workspaces);
+            Some(
+                list.as_array()?
+                    .iter()
+                    .filter_map(|p| p.as_str().map(str::to_string))
+                    .collect(),
+            )
+        })
+        .unwrap_or_default();
+    if let Ok(contents) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        patterns.extend(pnpm_workspace_packages(&contents));
+    }
+
+    member_dirs(root, &patterns)
+        .into_iter()
+        .filter_map(|dir| Some((package_json_name(&dir.to_string_lossy())?, dir)))
+        .collect()
+}
+
+/// The `packages:` list of a pnpm-workspace.yaml
+fn pnpm_workspace_packages(contents: &str) -> Vec<String> {
+    let mut packages = Vec::new();
+    let mut in_packages = false;
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed == "packages:";
+        } else if let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            let item = item.split(" #").next().unwrap_or(item).trim();
+            packages.push(item.trim_matches(['"', '\'']).to_string());
+        }
+    }
+    packages
+}
+
+/// Directories matching workspace member globs relative to `root`, less
+/// those matching a `!` pattern
+fn member_dirs(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let (excludes, includes): (Vec<&String>, Vec<&String>) =
+        patterns.iter().partition(|p| p.starts_with('!'));
+    let excludes: Vec<glob::Pattern> = excludes
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p.trim_start_matches('!').trim_end_matches('/')).ok())
+        .collect();
+
+    let mut dirs = BTreeSet::new();
+    for pattern in includes {
+        let full = root.join(pattern.trim_end_matches('/'));
+        let Ok(paths) = glob::glob(&full.to_string_lossy()) else {
+            continue;
+        };
+        for dir in paths.flatten().filter(|path| path.is_dir()) {
+            let relative = dir.strip_prefix(root).unwrap_or(&dir);
+            if !excludes.iter().any(|exclude| exclude.matches_path(relative)) {
+                dirs.insert(dir);
+            }
+        }
+    }
+    dirs.into_iter().collect()
+}
+
+/// A package's `scripts` from its package.json
+pub fn package_scripts(dir: &Path) -> BTreeSet<String> {
+    read_package_json(&dir.to_string_lossy())
+        .and_then(|package| {
+            let scripts = package.get("scripts")?.as_object()?;
+            Some(scripts.keys().cloned().collect())
+        })
+        .unwrap_or_default()
+}
+
+/// Cargo packages `cargo` can select when run in a project directory, and
+/// the binary targets of each
+#[derive(Debug, Default, PartialEq)]
+pub struct CargoTargets {
+    /// Package name → binary target names
+    pub packages: BTreeMap<String, BTreeSet<String>>,
+    /// The package at the project path itself, if it is not a virtual
+    /// workspace root
+    pub current: Option<String>,
+}
+
+impl CargoTargets {
+    /// Binaries `--bin` can name: those of `package`, of the current
+    /// package, or of every package from a virtual workspace root
+    pub fn bins(&self, package: Option<&str>) -> BTreeSet<&str> {
+        let selected: Vec<&BTreeSet<String>> = match package.or(self.current.as_deref()) {
+            Some(package) => self.packages.get(package).into_iter().collect(),
+            None => self.packages.values().collect(),
+        };
+        selected.into_iter().flatten().map(String::as_str).collect()
+    }
+}
+
+/// Packages and binaries of the Cargo workspace a project belongs to, or
+/// of the project alone. None without a Cargo.toml.
+pub fn cargo_targets(project_path: &str) -> Option<CargoTargets> {
+    let project_dir = Path::new(project_path);
+    let manifest = read_toml(&project_dir.join("Cargo.toml"))?;
+    let mut targets = CargoTargets {
+        current: package_targets(project_dir, &manifest).map(|(name, _)| name),
+        ..CargoTargets::default()
+    };
+
+    let root = super::detect_cargo_workspace(project_path).map(PathBuf::from);
+    let mut dirs = vec![project_dir.to_path_buf()];
+    if let Some(root) = &root {
+        let workspace = read_toml(&root.join("Cargo.toml"));
+        let workspace = workspace.as_ref().and_then(|m| m.get("workspace"));
+        let list = |key: &str| -> Vec<String> {
+            workspace
+                .and_then(|w| w.get(key))
+                .and_then(|l| l.as_array())
+                .map(|l| l.iter().filter_map(|p| p.as_str().map(str::to_string)).collect())
+                .unwrap_or_default()
+        };
+        let mut patterns = list("members");
+        patterns.extend(list("exclude").into_iter().map(|p| format!("!{}", p)));
+        dirs.push(root.clone());
+        dirs.extend(member_dirs(root, &patterns));
+    }
+
+    for dir in dirs {
+        if let Some((name, bins)) =
+            read_toml(&dir.join("Cargo.toml")).and_then(|m| package_targets(&dir, &m))
+        {
+            targets.packages.insert(name, bins);
+        }
+    }
+    Some(targets)
+}
+
+/// A package's name and binary targets: `[[bin]]` entries plus, unless
+/// `autobins = false`, `src/main.rs` and `src/bin/*`
+fn package_targets(dir: &Path, manifest: &toml::Table) -> Option<(String, BTreeSet<String>)> {
+    let package = manifest.get("package")?;
+    let name = package.get("name")?.as_str()?.to_string();
+    let mut bins: BTreeSet<String> = manifest
+        .get("bin")
+        .and_then(|b| b.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|bin| bin.get("name")?.as_str().map(str::to_string))
+        .collect();
+
+    if package.get("autobins").and_then(|a| a.as_bool()) != Some(false) {
+        if dir.join("src/main.rs").is_file() {
+            bins.insert(name.clone());
+        }
+        for entry in fs::read_dir(dir.join("src/bin")).into_iter().flatten().flatten() {
+            let path = entry.path();
+            let bin = if path.extension().is_some_and(|ext| ext == "rs") {
+                path.file_stem()
+            } else if path.join("main.rs").is_file() {
+                path.file_name()
+            } else {
+                None
+            };
+            bins.extend(bin.map(|b| b.to_string_lossy().into_owned()));
+        }
+    }
+    Some((name, bins))
+}
+
+fn package_json_name(project_path: &str) -> Option<String> {
+    let package = read_package_json(project_path)?;
+    package.get("name")?.as_str().map(str::to_string)
+}
+
+/// Names of all packages a package.json depends on
+fn package_json_dependencies(project_path: &str) -> Vec<String> {
+    let Some(package) = read_package_json(project_path) else {
+        return vec![];
+    };
+    ["dependencies", "devDependencies", "peerDependencies"]
+        .iter()
+        .filter_map(|key| package.get(key).and_then(|d| d.as_object()))
+        .flat_map(|deps| deps.keys().cloned())
+        .collect()
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    fs::read_to_string(path).ok()?.parse().ok()
+}
+
+fn read_package_json(project_path: &str) -> Option<serde_json::Value> {
+    let contents = fs::read_to_string(Path::new(project_path).join("package.json")).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_resolves_parent_dirs() {
+        assert_eq!(
+            normalize(Path::new("apps/api/../../crates/shared")),
+            PathBuf::from("crates/shared")
+        );
+        assert_eq!(normalize(Path::new("./apps/api/")), PathBuf::from("apps/api"));
+    }
+
+    #[test]
+    fn test_project_dependencies_from_cargo_and_package_json() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let path = |p: &str| root.join(p).to_string_lossy().to_string();
+
+        fs::create_dir_all(root.join("crates/shared")).unwrap();
+        fs::write(
+            root.join("crates/shared/Cargo.toml"),
+            "[package]\nname = \"shared\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("apps/api")).unwrap();
+        fs::write(
+            root.join("apps/api/Cargo.toml"),
+            "[package]\nname = \"api\"\n\n[dependencies]\nshared = { path = \"../../crates/shared\" }\nserde = \"1\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("packages/ui")).unwrap();
+        fs::write(root.join("packages/ui/package.json"), r#"{"name": "@org/ui"}"#).unwrap();
+        fs::create_dir_all(root.join("apps/web")).unwrap();
+        fs::write(
+            root.join("apps/web/package.json"),
+            r#"{"name": "@org/web", "dependencies": {"@org/ui": "workspace:*", "next": "14"}}"#,
+        )
+        .unwrap();
+
+        let config = crate::config::parse(&format!(
+            r#"
+[workspace]
+name = "Test"
+root = "."
+
+[tools]
+
+[projects.shared]
+type = "rust"
+path = "{}"
+[projects.shared.tasks]
+
+[projects.api]
+type = "rust"
+path = "{}"
+[projects.api.tasks]
+
+[projects.ui]
+type = "node"
+path = "{}"
+[projects.ui.tasks]
+
+[projects.web]
+type = "next"
+path = "{}"
+depends_on = ["api"]
+[projects.web.tasks]
+"#,
+            path("crates/shared"),
+            path("apps/api"),
+            path("packages/ui"),
+            path("apps/web"),
+        ))
+        .unwrap();
+
+        let deps = project_dependencies(&config);
+        assert_eq!(deps["api"], BTreeSet::from(["shared".to_string()]));
+        assert_eq!(
+            deps["web"],
+            BTreeSet::from(["api".to_string(), "ui".to_string()])
+        );
+        assert!(deps["shared"].is_empty());
+    }
+
+    #[test]
+    fn test_js_packages_from_workspaces_and_pnpm() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let packages = [("apps/web", "@org/web"), ("apps/old", "old"), ("packages/ui", "@org/ui")];
+        for (dir, name) in packages {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join("package.json"), format!(r#"{{"name": "{}"}}"#, name))
+                .unwrap();
+        }
+        fs::write(
+            root.join("package.json"),
+            r#"{"workspaces": {"packages": ["apps/*", "!apps/old"]}}"#,
+        )
+        .unwrap();
+        let packages = js_packages(root);
+        assert_eq!(packages.keys().collect::<Vec<_>>(), ["@org/web"]);
+        assert_eq!(packages["@org/web"], root.join("apps/web"));
+
+        fs::write(
+            root.join("pnpm-workspace.yaml"),
+            "packages:\n  - 'packages/*' # shared\ncatalog:\n  - apps/old\n",
+        )
+        .unwrap();
+        assert_eq!(js_packages(root).keys().collect::<Vec<_>>(), ["@org/ui", "@org/web"]);
+    }
+
+    #[test]
+    fn test_cargo_targets_of_workspace_members() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let path = |p: &str| root.join(p).to_string_lossy().to_string();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/skip\"]\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("crates/api/src/bin/worker")).unwrap();
+        fs::write(root.join("crates/api/Cargo.toml"), "[package]\nname = \"api\"\n").unwrap();
+        fs::write(root.join("crates/api/src/main.rs"), "").unwrap();
+        fs::write(root.join("crates/api/src/bin/migrate.rs"), "").unwrap();
+        fs::write(root.join("crates/api/src/bin/worker/main.rs"), "").unwrap();
+        fs::create_dir_all(root.join("crates/cli")).unwrap();
+        fs::write(
+            root.join("crates/cli/Cargo.toml"),
+            "[package]\nname = \"cli\"\nautobins = false\n\n[[bin]]\nname = \"meta-cli\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("crates/skip")).unwrap();
+        fs::write(root.join("crates/skip/Cargo.toml"), "[package]\nname = \"skip\"\n").unwrap();
+
+        let targets = cargo_targets(&path("crates/api")).unwrap();
+        assert_eq!(targets.current.as_deref(), Some("api"));
+        assert_eq!(targets.packages.keys().collect::<Vec<_>>(), ["api", "cli"]);
+        assert_eq!(targets.bins(None), BTreeSet::from(["api", "migrate", "worker"]));
+        assert_eq!(targets.bins(Some("cli")), BTreeSet::from(["meta-cli"]));
+
+        // From the virtual root every member's binaries can be named
+        let targets = cargo_targets(&path("")).unwrap();
+        assert_eq!(targets.current, None);
+        assert!(targets.bins(None).contains("meta-cli"));
+        assert!(cargo_targets(&path("crates/missing")).is_none());
+    }
+}
@@ -1,3 +1,5 @@
+use std::io::IsTerminal;
+
 use anyhow::Result;
 use clap::Parser;
 use tracing::info;
@@ -6,15 +8,24 @@ mod adapters;
 mod cli;
 mod config;
 mod execution;
+mod version;
 
 use cli::{Cli, Commands};
 use config::Config;
+use execution::{DoctorOptions, LogOptions, RunOptions};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing (write to stderr to keep stdout clean for --json)
+    // Initialize tracing (write to stderr to keep stdout clean for --json).
+    // META_LOG=warn hides progress messages, e.g. for `meta mcp` tools.
+    let level = std::env::var("META_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(tracing::Level::INFO);
     tracing_subscriber::fmt()
         .with_target(false)
+        .with_max_level(level)
+        .with_ansi(std::io::stderr().is_terminal())
         .with_writer(std::io::stderr)
         .init();
 
@@ -22,61 +33,154 @@ async fn main() -> Result<()> {
 
     info!("Meta orchestrator starting...");
 
+    // `meta init` writes meta.toml where it runs; everything else works
+    // from the workspace root
+    if !matches!(cli.command, Commands::Init { .. }) {
+        config::enter_workspace(cli.config.as_deref())?;
+    }
+    config::select_profile(cli.profile.as_deref());
+
     match cli.command {
-        Commands::Init { no_mcp } => {
+        Commands::Init { no_mcp, mcp } => {
             info!("Initializing meta configuration...");
-            let with_mcp = !no_mcp;
-            config::init(with_mcp)?;
+            let mcp = (!no_mcp).then_some(mcp);
+            config::init(mcp)?;
             println!("✅ Created meta.toml configuration file");
-            if with_mcp {
-                println!("✅ Added mcp-log-server entry to .mcp.json");
+            if let Some(server) = mcp {
+                println!("✅ Added {} entry to .mcp.json", server.entry_name());
             }
             Ok(())
         }
-        Commands::Dev { projects, detach } => {
+        Commands::Dev {
+            projects,
+            detach,
+            no_tmux,
+            add,
+        } => {
             info!("Starting development servers...");
             let config = Config::load()?;
-            execution::dev(&config, projects, detach).await
+            execution::dev(&config, projects, detach, no_tmux, add).await
         }
         Commands::DevStop => {
             info!("Stopping development servers...");
             execution::dev_stop().await
         }
-        Commands::Build { prod, projects } => {
+        Commands::Restart { project } => {
+            info!("Restarting {}...", project);
+            let config = Config::load()?;
+            execution::restart(&config, &project).await
+        }
+        Commands::Stop { project } => {
+            info!("Stopping {}...", project);
+            let config = Config::load()?;
+            execution::stop(&config, &project).await
+        }
+        Commands::Build {
+            prod,
+            projects,
+            jobs,
+            no_cache,
+            continue_on_error,
+            affected,
+            base,
+        } => {
             info!("Building projects...");
             let config = Config::load()?;
-            execution::build(&config, prod, projects).await
+            let options = run_options(jobs, no_cache, continue_on_error);
+            let base = affected.then_some(base.as_str());
+            execution::build(&config, prod, projects, base, &options).await
         }
-        Commands::Test { watch } => {
+        Commands::Test {
+            watch,
+            jobs,
+            no_cache,
+            continue_on_error,
+            affected,
+            base,
+        } => {
             info!("Running tests...");
             let config = Config::load()?;
-            execution::test(&config, watch).await
+            let options = run_options(jobs, no_cache, continue_on_error);
+            let base = affected.then_some(base.as_str());
+            execution::test(&config, watch, base, &options).await
         }
-        Commands::Run { task, projects } => {
+        Commands::Run {
+            task,
+            projects,
+            jobs,
+            no_cache,
+            continue_on_error,
+            affected,
+            base,
+        } => {
             info!("Running task: {}", task);
             let config = Config::load()?;
-            execution::run_task(&config, &task, projects).await
+            let options = run_options(jobs, no_cache, continue_on_error);
+            let base = affected.then_some(base.as_str());
+            execution::run_task(&config, &task, projects, base, &options).await
         }
-        Commands::Doctor => {
+        Commands::Doctor {
+            json,
+            strict,
+            fix,
+            yes,
+        } => {
             info!("Running diagnostics...");
+            let options = DoctorOptions {
+                json,
+                strict,
+                fix,
+                yes,
+            };
+            execution::doctor(Config::load(), &options).await
+        }
+        Commands::Mcp => {
             let config = Config::load()?;
-            execution::doctor(&config).await
+            execution::mcp(&config).await
         }
         Commands::Status {
             project,
             lines,
             json,
+            watch,
+            interval,
         } => {
             let config = Config::load()?;
-            execution::status(&config, project, lines, json).await
+            if watch {
+                execution::watch(&config, project, interval).await
+            } else {
+                execution::status(&config, project, lines, json).await
+            }
         }
         Commands::Logs {
-            project,
+            projects,
+            all,
             follow,
             lines,
+            grep,
+            level,
+            since,
+            json,
+            clean,
         } => {
             let config = Config::load()?;
-            execution::logs(&config, project, follow, lines).await
+            let options = LogOptions {
+                projects,
+                all,
+                follow,
+                lines,
+                grep,
+                level,
+                since,
+                json,
+                clean,
+            };
+            execution::logs(&config, options).await
+        }
+        Commands::LogSink { project } => {
+            // Logs are still captured if meta.toml is broken mid-session
+            let logs = Config::load().map(|c| c.logs).unwrap_or_default();
+            execution::log_sink(&project, &logs)
         }
         Commands::Sessions => {
             info!("Listing active meta sessions...");
@@ -84,3 +188,11 @@ async fn main() -> Result<()> {
         }
     }
 }
+
+fn run_options(jobs: Option<usize>, no_cache: bool, continue_on_error: bool) -> RunOptions {
+    RunOptions {
+        jobs: jobs.unwrap_or_else(execution::default_jobs),
+        use_cache: !no_cache,
+        continue_on_error,
+    }
+}
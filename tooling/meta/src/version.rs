@@ -0,0 +1,221 @@
+//! Tool versions: `version = ">=1.20, <2"` under `[tools.<name>]`, checked
+//! against what `<command> --version` prints
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A `major.minor.patch` version; parts a tool doesn't print are 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(pub [u64; 3]);
+
+impl Version {
+    /// The first version number in a tool's `--version` output, such as
+    /// `1.80.1` in `cargo 1.80.1 (3f5fd8dd4 2024-08-06)` or `3.3` in
+    /// `tmux 3.3a`
+    pub fn find(output: &str) -> Option<Version> {
+        output
+            .split(|c: char| !c.is_ascii_digit() && c != '.')
+            .filter(|word| word.contains('.'))
+            .find_map(|word| {
+                let (parts, _) = parse_parts(word.trim_matches('.')).ok()?;
+                Some(Version(parts))
+            })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [major, minor, patch] = self.0;
+        write!(f, "{}.{}.{}", major, minor, patch)
+    }
+}
+
+/// A version requirement like Cargo's: comma-separated comparisons that
+/// must all hold. `1.2` alone means `^1.2`, and `*` matches anything.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct VersionReq {
+    text: String,
+    comparators: Vec<Comparator>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    version: [u64; 3],
+    /// How many of major, minor and patch were written
+    parts: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+impl VersionReq {
+    pub fn matches(&self, version: Version) -> bool {
+        self.comparators.iter().all(|c| c.matches(version.0))
+    }
+}
+
+impl Comparator {
+    fn matches(&self, version: [u64; 3]) -> bool {
+        let min = self.version;
+        // The first version past those matching the first `parts` parts:
+        // 1.2 → 1.3.0
+        let next = |parts: usize| {
+            let mut next = [0; 3];
+            next[..parts].copy_from_slice(&min[..parts]);
+            next[parts - 1] += 1;
+            next
+        };
+        match self.op {
+            Op::Exact => version >= min && version < next(self.parts),
+            Op::Greater => version >= next(self.parts),
+            Op::GreaterEq => version >= min,
+            Op::Less => version < min,
+            Op::LessEq => version < next(self.parts),
+            Op::Tilde => version >= min && version < next(self.parts.min(2)),
+            Op::Caret => {
+                // Changes left of the first non-zero part are breaking
+                let parts = min[..self.parts]
+                    .iter()
+                    .position(|&part| part != 0)
+                    .map_or(self.parts, |i| i + 1);
+                version >= min && version < next(parts)
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for VersionReq {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let invalid = |reason: &str| {
+            format!(
+                "invalid version requirement '{}': {} (expected something like \">=1.20\")",
+                text, reason
+            )
+        };
+        let mut comparators = Vec::new();
+        for comparison in text.split(',').map(str::trim) {
+            if comparison == "*" {
+                continue;
+            }
+            let (op, rest) = [
+                (">=", Op::GreaterEq),
+                ("<=", Op::LessEq),
+                (">", Op::Greater),
+                ("<", Op::Less),
+                ("=", Op::Exact),
+                ("~", Op::Tilde),
+                ("^", Op::Caret),
+            ]
+            .iter()
+            .find_map(|(prefix, op)| comparison.strip_prefix(prefix).map(|rest| (*op, rest)))
+            .unwrap_or((Op::Caret, comparison));
+            let rest = rest.trim().trim_start_matches('v');
+            if rest.is_empty() {
+                return Err(invalid("missing version"));
+            }
+            let (version, parts) = parse_parts(rest).map_err(|reason| invalid(&reason))?;
+            comparators.push(Comparator { op, version, parts });
+        }
+        Ok(VersionReq { text, comparators })
+    }
+}
+
+impl From<VersionReq> for String {
+    fn from(req: VersionReq) -> Self {
+        req.text
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// `1`, `1.2` or `1.2.3` as three parts and how many were given
+fn parse_parts(text: &str) -> Result<([u64; 3], usize), String> {
+    let mut version = [0; 3];
+    let parts: Vec<&str> = text.split('.').collect();
+    if parts.len() > 3 {
+        return Err(format!("'{}' has more than three parts", text));
+    }
+    for (slot, part) in version.iter_mut().zip(&parts) {
+        *slot = part
+            .parse()
+            .map_err(|_| format!("'{}' is not a number", part))?;
+    }
+    Ok((version, parts.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(text: &str) -> VersionReq {
+        VersionReq::try_from(text.to_string()).unwrap()
+    }
+
+    fn matches(text: &str, version: &str) -> bool {
+        req(text).matches(Version::find(version).unwrap())
+    }
+
+    #[test]
+    fn test_version_find_in_tool_output() {
+        let find = |output: &str| Version::find(output).map(|v| v.to_string());
+        assert_eq!(find("cargo 1.80.1 (3f5fd8dd4 2024-08-06)").as_deref(), Some("1.80.1"));
+        assert_eq!(find("tmux 3.3a").as_deref(), Some("3.3.0"));
+        assert_eq!(find("bacon v3.1.2").as_deref(), Some("3.1.2"));
+        assert_eq!(find("2.1.3\n").as_deref(), Some("2.1.3"));
+        assert_eq!(find("Docker version 27.0.3, build 7d4bcd8").as_deref(), Some("27.0.3"));
+        assert_eq!(find("no version here"), None);
+    }
+
+    #[test]
+    fn test_version_req_operators() {
+        assert!(matches(">=1.20", "1.20.0"));
+        assert!(matches(">=1.20", "2.0.0"));
+        assert!(!matches(">=1.20", "1.19.9"));
+        assert!(matches(">1.2", "1.3.0"));
+        assert!(!matches(">1.2", "1.2.9"));
+        assert!(matches("<=1.2", "1.2.9"));
+        assert!(!matches("<1.2", "1.2.0"));
+        assert!(matches("=1.2", "1.2.7"));
+        assert!(!matches("=1.2.3", "1.2.4"));
+        assert!(matches("~1.2.3", "1.2.9"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(matches("*", "0.0.1"));
+    }
+
+    #[test]
+    fn test_version_req_caret_is_the_default() {
+        assert!(matches("1.2", "1.9.0"));
+        assert!(!matches("1.2", "2.0.0"));
+        assert!(matches("^0.2.3", "0.2.9"));
+        assert!(!matches("^0.2.3", "0.3.0"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches(">=1.20, <2", "1.99.0"));
+        assert!(!matches(">=1.20, <2", "2.0.0"));
+    }
+
+    #[test]
+    fn test_version_req_rejects_invalid() {
+        for text in [">=", ">=1.x", "1.2.3.4", ">= 1,"] {
+            let err = VersionReq::try_from(text.to_string()).unwrap_err();
+            assert!(err.contains("invalid version requirement"), "{}", err);
+        }
+        assert_eq!(req(" >= v1.20 ").to_string(), " >= v1.20 ");
+    }
+}
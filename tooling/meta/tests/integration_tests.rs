@@ -317,6 +317,64 @@ fn test_meta_dev_detach_flag_accepted() {
         .stdout(predicate::str::contains("--detach"));
 }
 
+// meta dev --add extends the tmux session, so it cannot be combined with the
+// foreground supervisor
+#[test]
+fn test_meta_dev_add_flag_requires_tmux_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["dev", "--help"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--add"));
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["dev", "--add", "--no-tmux"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+// meta dev -d fails without tmux instead of starting nothing and exiting 0
+#[cfg(unix)]
+#[test]
+fn test_meta_dev_detach_without_tmux_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("apps/api")).unwrap();
+    fs::create_dir_all(temp_dir.path().join("bin")).unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.sh]
+enabled = true
+command = "sh"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+dev = { tool = "sh", command = ["-c", "echo started"] }
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    // A PATH without tmux on it
+    cmd.env("PATH", temp_dir.path().join("bin"));
+    cmd.args(["dev", "-d"]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("[api] started").not())
+        .stderr(predicate::str::contains("tmux not found, cannot start in the background"));
+}
+
 // Issue #9: meta status --json outputs valid JSON
 #[test]
 fn test_meta_status_json_output() {
@@ -374,6 +432,159 @@ dev = { tool = "bacon", command = "run-long" }
     assert_eq!(projects[0]["tool"], "bacon");
 }
 
+// meta status --watch is an interactive dashboard: --interval only applies to
+// it and it has no JSON form
+#[test]
+fn test_meta_status_watch_flags() {
+    let temp_dir = TempDir::new().unwrap();
+    for (args, error) in [
+        (vec!["status", "--interval", "1s"], "--watch"),
+        (vec!["status", "--watch", "--json"], "cannot be used with"),
+        (vec!["status", "--watch", "--interval", "soon"], "invalid duration"),
+    ] {
+        let mut cmd = cargo_bin_cmd!("meta");
+        cmd.current_dir(&temp_dir);
+        cmd.args(&args);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains(error));
+    }
+}
+
+// meta logs merges several project logs and filters them
+#[test]
+fn test_meta_logs_all_with_filters() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"version = "1"
+
+[workspace]
+name = "Test"
+root = "."
+
+[tools.npm]
+enabled = true
+command = "npm"
+
+[projects.api]
+type = "node"
+path = "api"
+
+[projects.api.tasks]
+dev = { tool = "npm", command = "run dev" }
+
+[projects.web]
+type = "node"
+path = "web"
+
+[projects.web.tasks]
+dev = { tool = "npm", command = "run dev" }
+"#,
+    )
+    .unwrap();
+    let logs = temp_dir.path().join(".meta/logs");
+    fs::create_dir_all(&logs).unwrap();
+    fs::write(
+        logs.join("api.log"),
+        "2025-01-01T10:00:02Z ERROR api::db: db down\n2025-01-01T10:00:03Z ready\n",
+    )
+    .unwrap();
+    fs::write(logs.join("web.log"), "2025-01-01T10:00:01Z WARN slow build\n").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["logs", "--all"]);
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "[web] 2025-01-01T10:00:01Z WARN slow build",
+            "[api] 2025-01-01T10:00:02Z ERROR api::db: db down",
+            "[api] 2025-01-01T10:00:03Z ready",
+        ]
+    );
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["logs", "api", "web", "--level", "error", "--json"]);
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let records: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["project"], "api");
+    assert_eq!(records[0]["level"], "error");
+    assert_eq!(records[0]["timestamp"], "2025-01-01T10:00:02");
+    assert_eq!(records[0]["target"], "api::db");
+    assert_eq!(records[0]["message"], "db down");
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["logs", "--all", "--since", "10m"]);
+    cmd.assert().success().stdout(predicate::str::is_empty());
+}
+
+// meta logs --clean deletes rotated logs and empties project logs, but
+// keeps dev.log
+#[test]
+fn test_meta_logs_clean() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"version = "1"
+
+[workspace]
+name = "Test"
+root = "."
+
+[tools.npm]
+enabled = true
+command = "npm"
+
+[projects.api]
+type = "node"
+path = "api"
+
+[projects.api.tasks]
+dev = { tool = "npm", command = "run dev" }
+
+[logs]
+max_size = "1MB"
+max_files = 2
+"#,
+    )
+    .unwrap();
+    let logs = temp_dir.path().join(".meta/logs");
+    fs::create_dir_all(&logs).unwrap();
+    for file in ["api.log", "api.log.1", "api.log.2.gz", "dev.log"] {
+        fs::write(logs.join(file), "some output\n").unwrap();
+    }
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["logs", "--clean"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 2 rotated log(s) and emptied 1 log(s)"));
+
+    assert_eq!(fs::read_to_string(logs.join("api.log")).unwrap(), "");
+    assert!(!logs.join("api.log.1").exists());
+    assert!(!logs.join("api.log.2.gz").exists());
+    assert_eq!(fs::read_to_string(logs.join("dev.log")).unwrap(), "some output\n");
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["logs", "--clean", "-f"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
 // Issue #9: meta status --json excludes projects without dev task
 #[test]
 fn test_meta_status_json_excludes_libraries() {
@@ -469,6 +680,89 @@ fn test_init_writes_mcp_json_by_default() {
     assert!(args.iter().any(|v| v == "LOG_DIR=/logs"));
 }
 
+// `meta init --mcp meta` adds a `meta mcp` entry instead of the Docker one
+#[test]
+fn test_init_mcp_meta_writes_native_entry() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["init", "--mcp", "meta"]);
+    cmd.assert().success();
+
+    let value: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(temp_dir.path().join(".mcp.json")).unwrap())
+            .unwrap();
+    assert_eq!(value["mcpServers"]["meta"]["command"], "meta");
+    assert_eq!(value["mcpServers"]["meta"]["args"], serde_json::json!(["mcp"]));
+    assert!(value["mcpServers"].get("mcp-log-server").is_none());
+}
+
+// meta mcp answers JSON-RPC on stdin: tools run meta commands and project
+// logs are resources
+#[test]
+fn test_meta_mcp_tools_and_resources() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"version = "1"
+
+[workspace]
+name = "Test"
+root = "."
+
+[tools.npm]
+enabled = true
+command = "npm"
+
+[projects.api]
+type = "node"
+path = "api"
+
+[projects.api.tasks]
+dev = { tool = "npm", command = "run dev" }
+"#,
+    )
+    .unwrap();
+    let logs = temp_dir.path().join(".meta/logs");
+    fs::create_dir_all(&logs).unwrap();
+    fs::write(logs.join("api.log"), "2025-01-01T10:00:02Z ERROR db down\nready\n").unwrap();
+
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18"}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"logs","arguments":{"level":"error"}}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"resources/list"}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"resources/read","params":{"uri":"meta://logs/api.log"}}"#,
+    ];
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.arg("mcp").write_stdin(requests.join("\n") + "\n");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let responses: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    // The notification gets no response
+    assert_eq!(responses.len(), 4);
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-06-18");
+
+    let logs = &responses[1]["result"];
+    assert_eq!(logs["isError"], false);
+    let record: serde_json::Value =
+        serde_json::from_str(logs["content"][0]["text"].as_str().unwrap().trim()).unwrap();
+    assert_eq!(record["project"], "api");
+    assert_eq!(record["message"], "db down");
+
+    assert_eq!(responses[2]["result"]["resources"][0]["uri"], "meta://logs/api.log");
+    assert_eq!(
+        responses[3]["result"]["contents"][0]["text"],
+        "2025-01-01T10:00:02Z ERROR db down\nready\n"
+    );
+}
+
 // `meta init --no-mcp` skips writing .mcp.json
 #[test]
 fn test_init_no_mcp_skips_mcp_json() {
@@ -485,3 +779,1174 @@ fn test_init_no_mcp_skips_mcp_json() {
         ".mcp.json should not be created with --no-mcp"
     );
 }
+
+fn write_task_workspace(dir: &TempDir) {
+    for project in ["api", "app", "web"] {
+        fs::create_dir_all(dir.path().join("apps").join(project)).unwrap();
+    }
+    fs::write(
+        dir.path().join("meta.toml"),
+        r#"version = "1"
+
+[workspace]
+name = "Test"
+root = "."
+
+[tools.echo]
+enabled = true
+command = "echo"
+
+[tools.false]
+enabled = true
+command = "false"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+build = { tool = "echo", command = "built-api" }
+check = { tool = "false", command = "" }
+
+[projects.app]
+type = "next"
+path = "apps/app"
+
+[projects.app.tasks]
+build = { tool = "echo", command = "built-app", depends_on = ["api:build"] }
+check = { tool = "echo", command = "checked-app" }
+
+[projects.web]
+type = "next"
+path = "apps/web"
+
+[projects.web.tasks]
+check = { tool = "echo", command = "checked-web", depends_on = ["api:check"] }
+"#,
+    )
+    .unwrap();
+}
+
+// Tasks run after their dependencies, output is prefixed, and a summary
+// table is printed
+#[cfg(unix)]
+#[test]
+fn test_run_orders_dependencies_and_prefixes_output() {
+    let temp_dir = TempDir::new().unwrap();
+    write_task_workspace(&temp_dir);
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["run", "build", "-p", "app", "--jobs", "4"]);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let api = stdout.find("[api:build] built-api").expect("api output");
+    let app = stdout.find("[app:build] built-app").expect("app output");
+    assert!(api < app, "api:build must finish before app:build starts");
+    assert!(stdout.contains("## Summary"));
+}
+
+// A failed task fails the run, and its dependents are skipped
+#[cfg(unix)]
+#[test]
+fn test_run_failure_skips_dependents() {
+    let temp_dir = TempDir::new().unwrap();
+    write_task_workspace(&temp_dir);
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["run", "check", "-p", "web", "-j", "1"]);
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("api:check"));
+    assert!(!stdout.contains("checked-web"));
+    let web_row = stdout
+        .lines()
+        .find(|l| l.starts_with("web:check"))
+        .expect("web:check in summary");
+    assert!(web_row.contains("skipped"), "{}", web_row);
+}
+
+// --keep-going runs independent tasks after a failure and reports exit codes
+#[cfg(unix)]
+#[test]
+fn test_run_continue_on_error_runs_independent_tasks() {
+    let temp_dir = TempDir::new().unwrap();
+    write_task_workspace(&temp_dir);
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["run", "check", "-j", "1", "--keep-going"]);
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[app:check] checked-app"));
+    let row = |task: &str| {
+        stdout
+            .lines()
+            .find(|l| l.starts_with(task))
+            .unwrap_or_else(|| panic!("{} in summary", task))
+            .split_whitespace()
+            .skip(1)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    assert!(row("api:check").starts_with("✗ failed 1"), "{}", row("api:check"));
+    assert!(row("app:check").starts_with("✓ passed 0"), "{}", row("app:check"));
+    assert!(row("web:check").starts_with("⊘ skipped -"), "{}", row("web:check"));
+}
+
+// Quoted arguments reach the tool intact; `shell = true` runs through sh
+#[cfg(unix)]
+#[test]
+fn test_run_parses_quoted_and_shell_commands() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("apps/api")).unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.echo]
+enabled = true
+command = "echo"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+quoted = { tool = "echo", command = 'one "two  spaced"' }
+array = { tool = "echo", command = ["a", "b  c"] }
+piped = { tool = "echo", command = "shout | tr a-z A-Z", shell = true }
+broken = { tool = "echo", command = "x && y" }
+"#,
+    )
+    .unwrap();
+
+    let run = |task: &str| {
+        let mut cmd = cargo_bin_cmd!("meta");
+        cmd.current_dir(&temp_dir);
+        cmd.args(["run", task]);
+        cmd.output().unwrap()
+    };
+
+    let stdout = String::from_utf8_lossy(&run("quoted").stdout).to_string();
+    assert!(stdout.contains("[api:quoted] one two  spaced"), "{}", stdout);
+    let stdout = String::from_utf8_lossy(&run("array").stdout).to_string();
+    assert!(stdout.contains("[api:array] a b  c"), "{}", stdout);
+    let stdout = String::from_utf8_lossy(&run("piped").stdout).to_string();
+    assert!(stdout.contains("[api:piped] SHOUT"), "{}", stdout);
+
+    let output = run("broken");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("shell = true"));
+}
+
+// Workspace, project and task env (including env files) reach the tool
+#[cfg(unix)]
+#[test]
+fn test_run_injects_task_env() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("apps/api")).unwrap();
+    fs::write(temp_dir.path().join("apps/api/.env.local"), "PORT=4001\n").unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[env]
+PORT = "3000"
+ALLFRAME_API_URL = "http://localhost:3000"
+
+[tools.printenv]
+enabled = true
+command = "printenv"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+env_file = ".env.local"
+
+[projects.api.tasks]
+show = { tool = "printenv", command = "PORT ALLFRAME_API_URL", env = { ALLFRAME_API_URL = "http://localhost:4001" } }
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["run", "show"]);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[api:show] 4001"), "{}", stdout);
+    assert!(stdout.contains("[api:show] http://localhost:4001"), "{}", stdout);
+}
+
+// --no-tmux supervises dev processes directly, prefixing their output and
+// writing project logs and dev.log events
+#[cfg(unix)]
+#[test]
+fn test_dev_no_tmux_supervises_processes() {
+    let temp_dir = TempDir::new().unwrap();
+    for project in ["api", "web"] {
+        fs::create_dir_all(temp_dir.path().join("apps").join(project)).unwrap();
+    }
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.echo]
+enabled = true
+command = "echo"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+dev = { tool = "echo", command = "api listening" }
+
+[projects.web]
+type = "next"
+path = "apps/web"
+
+[projects.web.tasks]
+dev = { tool = "echo", command = "web ready" }
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["dev", "--no-tmux"]);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[api] api listening"), "{}", stdout);
+    assert!(stdout.contains("[web] web ready"), "{}", stdout);
+
+    let logs = temp_dir.path().join(".meta/logs");
+    let api_log = fs::read_to_string(logs.join("api.log")).unwrap();
+    assert_eq!(api_log, "api listening\n");
+    let dev_log = fs::read_to_string(logs.join("dev.log")).unwrap();
+    assert!(dev_log.contains("] [api] START: Process started (pid="), "{}", dev_log);
+    assert!(dev_log.contains("] [web] EXIT: Process exited with code 0"), "{}", dev_log);
+}
+
+// restart = "on-failure" restarts a crashed process until the limit is hit,
+// and `meta status` reports the restarts
+#[cfg(unix)]
+#[test]
+fn test_dev_no_tmux_restarts_on_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("apps/api")).unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.sh]
+enabled = true
+command = "sh"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+dev = { tool = "sh", command = ["-c", "echo crashing; exit 2"], restart = "on-failure", max_restarts = 1 }
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["dev", "--no-tmux"]);
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("[api] crashing").count(), 2, "{}", stdout);
+
+    let dev_log = fs::read_to_string(temp_dir.path().join(".meta/logs/dev.log")).unwrap();
+    assert!(
+        dev_log.contains("[api] RESTART: Restarting after exit code 2 (attempt 1/1, backoff 1s)"),
+        "{}",
+        dev_log
+    );
+    assert!(dev_log.contains("[api] STOPPED: Restart limit reached"), "{}", dev_log);
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["status", "--json"]);
+    let output = cmd.output().unwrap();
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(status["projects"][0]["restarts"], 1);
+}
+
+// Without a terminal, a single dev project runs in the foreground under the
+// supervisor even when tmux is available, so its restart policy and logs apply
+#[test]
+fn test_dev_single_project_is_supervised() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("apps/api")).unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.sh]
+enabled = true
+command = "sh"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+dev = { tool = "sh", command = ["-c", "echo crashing; exit 2"], restart = "on-failure", max_restarts = 1 }
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["dev"]);
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("[api] crashing").count(), 2, "{}", stdout);
+
+    let dev_log = fs::read_to_string(temp_dir.path().join(".meta/logs/dev.log")).unwrap();
+    assert!(dev_log.contains("[api] START: Process started"), "{}", dev_log);
+    assert!(dev_log.contains("[api] STOPPED: Restart limit reached"), "{}", dev_log);
+    let project_log = fs::read_to_string(temp_dir.path().join(".meta/logs/api.log")).unwrap();
+    assert_eq!(project_log.matches("crashing").count(), 2, "{}", project_log);
+}
+
+// `after` holds a project back until its dependency passes its ready probe
+#[cfg(unix)]
+#[test]
+fn test_dev_no_tmux_waits_for_ready_dependencies() {
+    let temp_dir = TempDir::new().unwrap();
+    for project in ["api", "app"] {
+        fs::create_dir_all(temp_dir.path().join("apps").join(project)).unwrap();
+    }
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.sh]
+enabled = true
+command = "sh"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+dev = { tool = "sh", command = ["-c", "sleep 1; echo listening; sleep 1"], ready = { log = "^listening$", timeout = 10 } }
+
+[projects.app]
+type = "next"
+path = "apps/app"
+
+[projects.app.tasks]
+dev = { tool = "sh", command = ["-c", "echo app started"], after = ["api"] }
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["dev", "--no-tmux"]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let listening = stdout.find("[api] listening").expect("api output");
+    let started = stdout.find("[app] app started").expect("app output");
+    assert!(listening < started, "{}", stdout);
+
+    let dev_log = fs::read_to_string(temp_dir.path().join(".meta/logs/dev.log")).unwrap();
+    assert!(dev_log.contains("[api] READY: log /^listening$/"), "{}", dev_log);
+}
+
+// A dev task whose port is taken fails before launching and names the
+// process holding it; `port = "auto"` exports a free port as PORT
+#[cfg(unix)]
+#[test]
+fn test_dev_checks_ports_and_exports_port() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("apps/api")).unwrap();
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let taken = listener.local_addr().unwrap().port();
+    let write_config = |port: &str| {
+        fs::write(
+            temp_dir.path().join("meta.toml"),
+            format!(
+                r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.printenv]
+enabled = true
+command = "printenv"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+dev = {{ tool = "printenv", command = "PORT", port = {} }}
+"#,
+                port
+            ),
+        )
+        .unwrap();
+    };
+
+    write_config(&taken.to_string());
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["dev", "--no-tmux"]);
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!("Port {} (api) is already in use", taken)),
+        "{}",
+        stdout
+    );
+    #[cfg(target_os = "linux")]
+    assert!(stdout.contains(&format!("by pid {}", std::process::id())), "{}", stdout);
+
+    write_config("\"auto\"");
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["dev", "--no-tmux"]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let exported = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("[api] "))
+        .and_then(|port| port.trim().parse::<u16>().ok());
+    assert!(exported.is_some_and(|port| port != taken), "{}", stdout);
+    // Recorded so `meta restart` hands the project the same port
+    let recorded = fs::read_to_string(temp_dir.path().join(".meta/ports.json")).unwrap();
+    let recorded: serde_json::Value = serde_json::from_str(&recorded).unwrap();
+    assert_eq!(recorded["api"].as_u64(), exported.map(u64::from), "{}", recorded);
+    drop(listener);
+}
+
+// Doctor warns when dev tasks declare the same port
+#[test]
+fn test_doctor_flags_duplicate_dev_ports() {
+    let temp_dir = TempDir::new().unwrap();
+    for project in ["api", "web"] {
+        fs::create_dir_all(temp_dir.path().join("apps").join(project)).unwrap();
+    }
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.echo]
+enabled = true
+command = "echo"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+dev = { tool = "echo", command = "api", port = 4400 }
+
+[projects.web]
+type = "next"
+path = "apps/web"
+
+[projects.web.tasks]
+dev = { tool = "echo", command = "web", port = 4400 }
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.arg("doctor");
+    cmd.assert().stdout(predicate::str::contains(
+        "Port 4400 is declared by several dev tasks: api, web",
+    ));
+}
+
+// doctor --json reports each check with a stable id; --strict fails on
+// warnings
+#[test]
+fn test_doctor_json_and_strict() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("apps/web")).unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.echo]
+enabled = true
+command = "echo"
+
+[projects.web]
+type = "next"
+path = "apps/web"
+
+[projects.web.tasks]
+dev = { tool = "echo", command = "web", port = 4400 }
+
+[projects.docs]
+type = "next"
+path = "apps/docs"
+
+[projects.docs.tasks]
+dev = { tool = "echo", command = "docs", port = 4400 }
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["doctor", "--json"]);
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["ok"], false);
+    let checks = report["checks"].as_array().unwrap();
+    let missing = checks
+        .iter()
+        .find(|check| check["id"] == "project.path-not-found")
+        .unwrap();
+    assert_eq!(missing["severity"], "error");
+    assert_eq!(missing["subject"], "docs");
+    assert!(missing["fix"].as_str().unwrap().contains("[projects.docs]"));
+    assert!(checks.iter().any(|check| check["id"] == "dev.port-conflict"));
+
+    // With the path fixed only the port warning is left
+    fs::create_dir_all(temp_dir.path().join("apps/docs")).unwrap();
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["doctor", "--json"]);
+    let output = cmd.output().unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["errors"], 0);
+    assert_eq!(output.status.success(), report["ok"] == true);
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["doctor", "--json", "--strict"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Configuration has warnings (--strict)"));
+}
+
+// `meta restart`/`meta stop` act on a pane of the running tmux session and
+// explain what is missing instead of starting anything
+#[test]
+fn test_restart_and_stop_require_dev_session() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.echo]
+enabled = true
+command = "echo"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+dev = { tool = "echo", command = "api listening" }
+"#,
+    )
+    .unwrap();
+
+    for subcommand in ["restart", "stop"] {
+        let mut cmd = cargo_bin_cmd!("meta");
+        cmd.current_dir(&temp_dir);
+        cmd.args([subcommand, "web"]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Project not found: web"));
+
+        let mut cmd = cargo_bin_cmd!("meta");
+        cmd.current_dir(&temp_dir);
+        cmd.args([subcommand, "api"]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("No active meta-"));
+    }
+}
+
+// Tasks with `inputs` are skipped when nothing changed since the last
+// successful run, and re-run once an input changes
+#[cfg(unix)]
+#[test]
+fn test_run_uses_cache_until_inputs_change() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("apps/api/src")).unwrap();
+    fs::write(temp_dir.path().join("apps/api/src/main.rs"), "fn main() {}").unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.echo]
+enabled = true
+command = "echo"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+lint = { tool = "echo", command = "linted", inputs = ["src/**"] }
+"#,
+    )
+    .unwrap();
+
+    let run = || {
+        let mut cmd = cargo_bin_cmd!("meta");
+        cmd.current_dir(&temp_dir);
+        cmd.args(["run", "lint"]);
+        let output = cmd.output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let first = run();
+    assert!(!first.contains("cache hit"));
+
+    let second = run();
+    assert!(second.contains("cache hit"), "{}", second);
+    assert!(second.contains("[api:lint] linted"), "cached output is replayed");
+
+    fs::write(temp_dir.path().join("apps/api/src/main.rs"), "fn main() { }").unwrap();
+    let third = run();
+    assert!(!third.contains("cache hit"), "{}", third);
+}
+
+// A task is not cached when it depends on one without `inputs`, whose
+// outputs can differ on every run (codegen)
+#[cfg(unix)]
+#[test]
+fn test_run_does_not_cache_after_uncached_dependency() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("apps/api/src")).unwrap();
+    fs::write(temp_dir.path().join("apps/api/src/main.rs"), "fn main() {}").unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.sh]
+enabled = true
+command = "sh"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+codegen = { tool = "sh", command = ["-c", "date +%s%N > generated.txt"] }
+build = { tool = "sh", command = ["-c", "cp generated.txt built.txt"], inputs = ["src/**"], outputs = ["built.txt"], depends_on = ["codegen"] }
+"#,
+    )
+    .unwrap();
+
+    let api = temp_dir.path().join("apps/api");
+    for _ in 0..2 {
+        let mut cmd = cargo_bin_cmd!("meta");
+        cmd.current_dir(&temp_dir);
+        cmd.args(["run", "build"]);
+        let output = cmd.output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(!stdout.contains("cache hit"), "{}", stdout);
+        assert_eq!(
+            fs::read_to_string(api.join("built.txt")).unwrap(),
+            fs::read_to_string(api.join("generated.txt")).unwrap()
+        );
+    }
+}
+
+// --affected only runs projects touched since the base ref, plus their
+// dependents
+#[cfg(unix)]
+#[test]
+fn test_run_affected_selects_changed_projects() {
+    let temp_dir = TempDir::new().unwrap();
+    write_task_workspace(&temp_dir);
+    fs::write(temp_dir.path().join("apps/api/main.rs"), "fn main() {}").unwrap();
+    fs::write(temp_dir.path().join("apps/web/index.ts"), "").unwrap();
+
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(&temp_dir)
+            .env("GIT_AUTHOR_NAME", "test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "-q"]);
+    git(&["add", "-A"]);
+    git(&["commit", "-qm", "initial"]);
+
+    // app depends on api (app:build depends_on api:build), web is unrelated
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        fs::read_to_string(temp_dir.path().join("meta.toml"))
+            .unwrap()
+            .replace("path = \"apps/app\"", "path = \"apps/app\"\ndepends_on = [\"api\"]"),
+    )
+    .unwrap();
+    git(&["commit", "-qam", "deps"]);
+    fs::write(temp_dir.path().join("apps/api/main.rs"), "fn main() { }").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["run", "build", "--affected", "--base", "HEAD"]);
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Affected since HEAD: api, app"), "{}", stdout);
+    assert!(stdout.contains("[api:build] built-api"));
+    assert!(stdout.contains("[app:build] built-app"));
+    assert!(!stdout.contains("web"), "{}", stdout);
+}
+
+// doctor --fix --yes completes a turbo dev command and finds a moved
+// project, keeping meta.toml's comments, then re-checks
+#[test]
+fn test_doctor_fix_yes_edits_meta_toml() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("apps/web")).unwrap();
+    fs::create_dir_all(temp_dir.path().join("packages/docs")).unwrap();
+    fs::write(
+        temp_dir.path().join("apps/web/package.json"),
+        r#"{ "name": "@acme/web" }"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.turborepo]
+enabled = true
+command = "echo"
+
+[projects.web]
+type = "next"
+path = "apps/web"
+
+[projects.web.tasks]
+dev = { tool = "turborepo", command = "dev" } # started by turbo
+
+# Moved to packages/
+[projects.docs]
+type = "next"
+path = "apps/docs"
+
+[projects.docs.tasks]
+build = { tool = "turborepo", command = "run build --filter=docs" }
+"#,
+    )
+    .unwrap();
+
+    // Without --yes or a terminal nothing is written
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["doctor", "--fix"]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("+path = \"packages/docs\""))
+        .stdout(predicate::str::contains("Re-run with --yes"));
+    let meta_toml = fs::read_to_string(temp_dir.path().join("meta.toml")).unwrap();
+    assert!(meta_toml.contains(r#"path = "apps/docs""#));
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["doctor", "--fix", "--yes"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Updated meta.toml"))
+        .stdout(predicate::str::contains("After fixes"));
+    let meta_toml = fs::read_to_string(temp_dir.path().join("meta.toml")).unwrap();
+    assert!(meta_toml.contains(
+        r#"dev = { tool = "turborepo", command = "run dev --filter=@acme/web" } # started by turbo"#
+    ));
+    assert!(meta_toml.contains("# Moved to packages/\n[projects.docs]"));
+    assert!(meta_toml.contains(r#"path = "packages/docs""#));
+}
+
+// doctor resolves turbo filters against workspace packages and cargo --bin
+// against real targets
+#[test]
+fn test_doctor_checks_turbo_filters_and_cargo_targets() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("package.json"), r#"{"workspaces": ["apps/*"]}"#).unwrap();
+    fs::write(root.join("turbo.json"), r#"{"tasks": {"dev": {}}}"#).unwrap();
+    fs::create_dir_all(root.join("apps/web")).unwrap();
+    fs::write(
+        root.join("apps/web/package.json"),
+        r#"{"name": "@acme/web", "scripts": {"dev": "next dev"}}"#,
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("apps/api/src")).unwrap();
+    fs::write(root.join("apps/api/Cargo.toml"), "[package]\nname = \"api\"\n").unwrap();
+    fs::write(root.join("apps/api/src/main.rs"), "fn main() {}\n").unwrap();
+    fs::write(
+        root.join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.turborepo]
+enabled = true
+command = "echo"
+
+[tools.cargo]
+enabled = true
+command = "echo"
+
+[projects.web]
+type = "next"
+path = "apps/web"
+
+[projects.web.tasks]
+dev = { tool = "turborepo", command = "run dev --filter=web" }
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+dev = { tool = "cargo", command = "run --bin server" }
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["doctor", "--json"]);
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let checks = report["checks"].as_array().unwrap();
+    let find = |id: &str| checks.iter().find(|check| check["id"] == id).unwrap();
+    assert_eq!(find("turbo.filter-unknown")["subject"], "web:dev");
+    assert!(find("turbo.filter-unknown")["fix"].as_str().unwrap().contains("@acme/web"));
+    assert_eq!(find("cargo.bin-not-found")["subject"], "api:dev");
+    assert!(find("cargo.bin-not-found")["fix"].as_str().unwrap().contains("api"));
+
+    // With the real package name and binary the checks pass
+    let meta_toml = fs::read_to_string(root.join("meta.toml")).unwrap();
+    let meta_toml = meta_toml
+        .replace("--filter=web", "--filter=@acme/web")
+        .replace("--bin server", "--bin api");
+    fs::write(root.join("meta.toml"), meta_toml).unwrap();
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["doctor", "--json"]);
+    let output = cmd.output().unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["errors"], 0, "{}", report);
+}
+
+// A tool outside its declared version range fails doctor and stops run
+// before any task starts
+#[test]
+fn test_tool_version_requirement_blocks_run() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("api")).unwrap();
+    let meta_toml = |version: &str| {
+        format!(
+            r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.cargo]
+enabled = true
+command = "cargo"
+version = "{}"
+
+[projects.api]
+type = "rust"
+path = "api"
+
+[projects.api.tasks]
+check = {{ tool = "cargo", command = "--version" }}
+"#,
+            version
+        )
+    };
+    fs::write(temp_dir.path().join("meta.toml"), meta_toml(">=999")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["run", "check"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "does not satisfy version = \">=999\" under [tools.cargo]",
+        ))
+        .stdout(predicate::str::contains("[api:check]").not());
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["doctor", "--json"]);
+    let output = cmd.output().unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let checks = report["checks"].as_array().unwrap();
+    let mismatch = checks
+        .iter()
+        .find(|check| check["id"] == "tool.version-mismatch")
+        .unwrap();
+    assert_eq!(mismatch["subject"], "cargo");
+    assert_eq!(mismatch["severity"], "error");
+
+    fs::write(temp_dir.path().join("meta.toml"), meta_toml(">=1.0, <999")).unwrap();
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["run", "check"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[api:check] cargo"));
+}
+
+// With a version required, a tool whose version can't be read is an error
+// for doctor just as it stops run
+#[cfg(unix)]
+#[test]
+fn test_tool_version_requirement_without_readable_version() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("api")).unwrap();
+    let tool = temp_dir.path().join("tool");
+    let meta_toml = format!(
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.custom]
+enabled = true
+command = "{}"
+version = ">=1.0"
+
+[projects.api]
+type = "rust"
+path = "api"
+
+[projects.api.tasks]
+check = {{ tool = "custom", command = "check" }}
+"#,
+        tool.display()
+    );
+    fs::write(temp_dir.path().join("meta.toml"), meta_toml).unwrap();
+
+    for (script, id) in [
+        ("#!/bin/sh\necho custom tool\n", "tool.version-unknown"),
+        ("#!/bin/sh\nexit 1\n", "tool.version-check-failed"),
+    ] {
+        fs::write(&tool, script).unwrap();
+        fs::set_permissions(&tool, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut cmd = cargo_bin_cmd!("meta");
+        cmd.current_dir(&temp_dir);
+        cmd.args(["doctor", "--json"]);
+        let output = cmd.output().unwrap();
+        assert!(!output.status.success(), "{}", id);
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let check = report["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|check| check["id"] == id)
+            .unwrap();
+        assert_eq!(check["severity"], "error");
+
+        let mut cmd = cargo_bin_cmd!("meta");
+        cmd.current_dir(&temp_dir);
+        cmd.args(["run", "check"]);
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Could not tell which"));
+    }
+}
+
+// meta finds meta.toml above the current directory, or takes --config /
+// META_CONFIG, and resolves project paths from workspace.root
+#[test]
+fn test_config_discovery_and_workspace_root() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("apps/api/src")).unwrap();
+    fs::create_dir_all(root.join("tools/meta")).unwrap();
+    let meta_toml = |workspace_root: &str| {
+        format!(
+            r#"[workspace]
+name = "Test"
+root = "{}"
+
+[tools.sh]
+enabled = true
+command = "sh"
+
+[projects.api]
+type = "rust"
+path = "apps/api"
+
+[projects.api.tasks]
+where = {{ tool = "sh", command = "-c pwd" }}
+"#,
+            workspace_root
+        )
+    };
+    fs::write(root.join("meta.toml"), meta_toml(".")).unwrap();
+    let api = root.join("apps/api").canonicalize().unwrap();
+    let expected = format!("[api:where] {}", api.display());
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(root.join("apps/api/src"));
+    cmd.args(["run", "where"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(expected.as_str()));
+
+    // A meta.toml kept below the workspace root, pointing back up to it
+    fs::remove_file(root.join("meta.toml")).unwrap();
+    fs::write(root.join("tools/meta/meta.toml"), meta_toml("../..")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(root.join("apps/api"));
+    cmd.args(["run", "where"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("or pass --config"));
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(root.join("apps/api"));
+    cmd.args(["--config", "../../tools/meta/meta.toml", "run", "where"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(expected.as_str()));
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(root.join("apps"));
+    cmd.env("META_CONFIG", root.join("tools/meta/meta.toml"));
+    cmd.args(["run", "where"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(expected.as_str()));
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(root);
+    cmd.args(["--config", "missing.toml", "status"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("missing.toml' not found"));
+}
+
+// meta.local.toml is merged over meta.toml, and --profile / META_PROFILE
+// merge a [profiles.<name>] over both
+#[test]
+fn test_local_config_and_profiles() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("api")).unwrap();
+    fs::write(
+        temp_dir.path().join("meta.toml"),
+        r#"[workspace]
+name = "Test"
+root = "."
+
+[tools.echo]
+enabled = true
+command = "echo"
+
+[projects.api]
+type = "rust"
+path = "api"
+
+[projects.api.tasks]
+hello = { tool = "echo", command = "shared" }
+
+[profiles.ci.projects.api.tasks.hello]
+command = "from-ci"
+"#,
+    )
+    .unwrap();
+
+    let run_hello = |args: &[&str], env: Option<&str>| {
+        let mut cmd = cargo_bin_cmd!("meta");
+        cmd.current_dir(&temp_dir);
+        cmd.env_remove("META_PROFILE");
+        if let Some(profile) = env {
+            cmd.env("META_PROFILE", profile);
+        }
+        cmd.args(args).args(["run", "hello"]);
+        cmd.assert()
+    };
+
+    run_hello(&[], None).success().stdout(predicate::str::contains("[api:hello] shared"));
+
+    fs::write(
+        temp_dir.path().join("meta.local.toml"),
+        "[projects.api.tasks.hello]\ncommand = \"from-local\"\n",
+    )
+    .unwrap();
+    run_hello(&[], None).success().stdout(predicate::str::contains("[api:hello] from-local"));
+    run_hello(&["--profile", "ci"], None)
+        .success()
+        .stdout(predicate::str::contains("[api:hello] from-ci"));
+    run_hello(&[], Some("ci")).success().stdout(predicate::str::contains("[api:hello] from-ci"));
+    run_hello(&["--profile", "staging"], None)
+        .failure()
+        .stderr(predicate::str::contains("Profile 'staging' is not defined (profiles: ci)"));
+
+    let mut cmd = cargo_bin_cmd!("meta");
+    cmd.current_dir(&temp_dir);
+    cmd.args(["--profile", "ci", "doctor"]);
+    cmd.assert().stdout(predicate::str::contains(
+        "loaded successfully with meta.local.toml and profile 'ci'",
+    ));
+}